@@ -1,7 +1,7 @@
 //! Build script to compile guillotine-mini Zig library
 
 use std::process::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::env;
 
 /// Check if a command exists in PATH (cross-platform)
@@ -46,6 +46,65 @@ fn check_zig_version(version: &str) -> bool {
     parts[0] > 0 || (parts[0] == 0 && parts[1] > 15) || (parts[0] == 0 && parts[1] == 15 && parts[2] >= 1)
 }
 
+/// Whether the target links weak undefined symbols to null (ELF targets)
+fn weak_symbols_supported() -> bool {
+    env::var_os("CARGO_CFG_UNIX").is_some() && env::var("CARGO_CFG_TARGET_VENDOR").as_deref() != Ok("apple")
+}
+
+/// Compile the shim resolving the config ABI through weak references and link it
+///
+/// The function names are read from the `config_abi!` declarations in ffi.rs, so the shim knows
+/// exactly the functions of `ConfigApi`.
+fn build_config_symbols(manifest_dir: &Path, out_dir: &Path) {
+    let ffi_rs = manifest_dir.join("src/guillotine_mini/ffi.rs");
+    println!("cargo:rerun-if-changed={}", ffi_rs.display());
+    let source = std::fs::read_to_string(&ffi_rs).expect("Failed to read src/guillotine_mini/ffi.rs");
+    let declarations = source
+        .rsplit_once("\nconfig_abi! {")
+        .and_then(|(_, rest)| rest.split_once("\n}\n"))
+        .expect("config_abi! declarations not found in ffi.rs")
+        .0;
+    let names: Vec<&str> = declarations
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("pub fn "))
+        .filter_map(|rest| rest.split_once('(').map(|(name, _)| name))
+        .collect();
+
+    let mut shim = String::from("#include <stddef.h>\n#include <string.h>\n\n");
+    for name in &names {
+        shim.push_str(&format!("extern void {}(void) __attribute__((weak));\n", name));
+    }
+    shim.push_str("\nvoid *guillotine_rs_config_symbol(const char *name) {\n");
+    for name in &names {
+        shim.push_str(&format!("    if (strcmp(name, \"{0}\") == 0) return (void *)&{0};\n", name));
+    }
+    shim.push_str("    return NULL;\n}\n");
+
+    let shim_c = out_dir.join("config_symbols.c");
+    let shim_o = out_dir.join("config_symbols.o");
+    std::fs::write(&shim_c, shim).expect("Failed to write config_symbols.c");
+    let compiled = Command::new("zig")
+        .args(["cc", "-c", "-O2", "-fPIC", "-o"])
+        .arg(&shim_o)
+        .arg(&shim_c)
+        .status()
+        .expect("Failed to execute zig cc");
+    if !compiled.success() {
+        panic!("zig cc failed to compile {:?}", shim_c);
+    }
+    let archived = Command::new("zig")
+        .args(["ar", "rcs"])
+        .arg(out_dir.join("libguillotine_rs_config_symbols.a"))
+        .arg(&shim_o)
+        .status()
+        .expect("Failed to execute zig ar");
+    if !archived.success() {
+        panic!("zig ar failed to archive {:?}", shim_o);
+    }
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+    println!("cargo:rustc-link-lib=static=guillotine_rs_config_symbols");
+}
+
 fn main() {
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")
         .expect("CARGO_MANIFEST_DIR environment variable not set"));
@@ -119,7 +178,7 @@ fn main() {
     let guillotine_mini_dir = manifest_dir.join("lib/guillotine-mini");
 
     let status = Command::new("zig")
-        .args(&[
+        .args([
             "build",
             "native",  // Use native target for FFI integration
             "--prefix", zig_out_dir.to_str()
@@ -173,6 +232,12 @@ fn main() {
     println!("cargo:rustc-link-search=native={}", lib_dir.display());
     println!("cargo:rustc-link-lib=static=guillotine_mini");
 
+    // With the `config-api` feature, the config builder's part of the C ABI is only referenced
+    // weakly, so a guillotine-mini without it still links (see src/guillotine_mini/symbols.rs)
+    if env::var_os("CARGO_FEATURE_CONFIG_API").is_some() && weak_symbols_supported() {
+        build_config_symbols(&manifest_dir, &out_dir);
+    }
+
     // Also link primitives_c from the zig cache
     let zig_cache_lib_dir = zig_cache_dir.join("o");
     let mut primitives_found = false;
@@ -0,0 +1,90 @@
+//! Arbitrary values fed through the safe FFI wrappers
+//!
+//! Exercises the buffer handling on both sides of the boundary: account and storage writes
+//! read back through the length-prefixed getters, blob hash validation and packed log parsing.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use guillotine_rs::guillotine_mini::{
+    database_bridge,
+    ffi::{self, OwnedEvmHandle},
+    EvmAdapterError, Hardfork,
+};
+use libfuzzer_sys::fuzz_target;
+use revm::{
+    database::{CacheDB, EmptyDB},
+    database_interface::Database,
+    primitives::{hardfork::SpecId, keccak256, Address, Bytes, B256, U256},
+    state::{AccountInfo, Bytecode},
+};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    hardfork: u8,
+    address: [u8; 20],
+    balance: [u8; 32],
+    nonce: u64,
+    code: Vec<u8>,
+    storage: Vec<([u8; 32], [u8; 32])>,
+    blob_hashes: Vec<[u8; 32]>,
+    packed_logs: Vec<u8>,
+}
+
+fuzz_target!(|input: Input| {
+    // Pure parsing of guillotine-mini's packed log layout
+    let _ = database_bridge::unpack_logs(&input.packed_logs);
+
+    // Arbitrary hardfork ids are rejected by guillotine-mini
+    let _ = OwnedEvmHandle::new(Hardfork::from_id(input.hardfork).unwrap_or(Hardfork::Cancun));
+    if Hardfork::from_id(input.hardfork).is_none() {
+        assert!(unsafe { ffi::evm_create_with_hardfork(input.hardfork, 0) }.is_null());
+    }
+
+    let Ok(evm) = OwnedEvmHandle::new(Hardfork::Cancun) else {
+        return;
+    };
+    let handle = evm.as_ptr();
+    let address = Address::from(input.address);
+
+    let code = Bytes::from(input.code);
+    let info = AccountInfo {
+        balance: U256::from_be_bytes(input.balance),
+        nonce: input.nonce,
+        code_hash: keccak256(&code),
+        code: Some(Bytecode::new_raw(code)),
+    };
+    // SAFETY: `handle` is owned by `evm`, which outlives every call below
+    unsafe {
+        if database_bridge::write_account_info_to_ffi(handle, address, &info).is_ok() {
+            assert_eq!(database_bridge::read_balance_from_ffi(handle, address), Ok(info.balance));
+            assert_eq!(database_bridge::read_nonce_from_ffi(handle, address), Ok(info.nonce));
+            let _ = database_bridge::read_code_from_ffi(handle, address);
+        }
+    }
+
+    // Storage round-trips through a database; the last write to a slot wins
+    let mut db = CacheDB::new(EmptyDB::default());
+    for (slot, value) in &input.storage {
+        let _ = db.insert_account_storage(address, U256::from_be_bytes(*slot), U256::from_be_bytes(*value));
+    }
+    for (slot, _) in &input.storage {
+        let slot = U256::from_be_bytes(*slot);
+        unsafe {
+            if database_bridge::sync_storage_to_ffi(handle, &mut db, address, slot).is_ok() {
+                let expected = db.storage(address, slot).unwrap();
+                assert_eq!(database_bridge::read_storage_from_ffi(handle, address, slot), Ok(expected));
+            }
+        }
+    }
+
+    let hashes: Vec<B256> = input.blob_hashes.iter().map(|hash| B256::from(*hash)).collect();
+    let result: Result<(), EvmAdapterError<()>> =
+        unsafe { database_bridge::set_blob_hashes(handle, SpecId::CANCUN, &hashes) };
+    if hashes.len() > 6 {
+        assert!(result.is_err());
+    }
+
+    let _ = unsafe { database_bridge::read_logs_from_ffi(handle) };
+    let _ = unsafe { database_bridge::read_state_from_ffi(handle) };
+});
@@ -0,0 +1,106 @@
+//! Arbitrary bytecode, calldata and context values executed through `transact`
+//!
+//! Any input must produce a result or an `EvmAdapterError`; a Zig-side panic aborts the
+//! process and is reported as a crash.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use guillotine_rs::GuillotineMiniEvm;
+use libfuzzer_sys::fuzz_target;
+use revm::{
+    context::{Context, TxEnv},
+    database::{CacheDB, EmptyDB},
+    primitives::{hardfork::SpecId, keccak256, Address, Bytes, TxKind, U256},
+    state::{AccountInfo, Bytecode},
+    MainContext,
+};
+
+const SPECS: &[SpecId] = &[
+    SpecId::FRONTIER,
+    SpecId::HOMESTEAD,
+    SpecId::BYZANTIUM,
+    SpecId::ISTANBUL,
+    SpecId::BERLIN,
+    SpecId::LONDON,
+    SpecId::SHANGHAI,
+    SpecId::CANCUN,
+    SpecId::PRAGUE,
+    SpecId::OSAKA,
+];
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    code: Vec<u8>,
+    calldata: Vec<u8>,
+    /// Deploy `code` as init code instead of calling it
+    create: bool,
+    value: u64,
+    gas_limit: u32,
+    gas_price: u64,
+    sender_balance: u64,
+    spec: u8,
+    block_number: u64,
+    timestamp: u64,
+    /// Storage of the called contract
+    storage: Vec<([u8; 32], [u8; 32])>,
+}
+
+fuzz_target!(|input: Input| {
+    let sender = Address::with_last_byte(0xaa);
+    let contract = Address::with_last_byte(0xcc);
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(sender, AccountInfo { balance: U256::from(input.sender_balance), ..Default::default() });
+    if !input.create {
+        let code = Bytes::from(input.code.clone());
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code_hash: keccak256(&code),
+                code: Some(Bytecode::new_raw(code)),
+                ..Default::default()
+            },
+        );
+        for (slot, value) in &input.storage {
+            let _ = db.insert_account_storage(contract, U256::from_be_bytes(*slot), U256::from_be_bytes(*value));
+        }
+    }
+
+    let spec = SPECS[input.spec as usize % SPECS.len()];
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| {
+            cfg.spec = spec;
+            cfg.disable_nonce_check = true;
+        })
+        .modify_block_chained(|block| {
+            block.number = U256::from(input.block_number);
+            block.timestamp = U256::from(input.timestamp);
+        })
+        .with_db(db);
+    let Ok(mut evm) = GuillotineMiniEvm::try_new(ctx) else {
+        return;
+    };
+
+    let (kind, data) = if input.create {
+        (TxKind::Create, input.code)
+    } else {
+        (TxKind::Call(contract), input.calldata)
+    };
+    let Ok(tx) = TxEnv::builder()
+        .caller(sender)
+        .kind(kind)
+        .data(Bytes::from(data))
+        .value(U256::from(input.value))
+        .gas_limit(input.gas_limit as u64)
+        .gas_price(input.gas_price as u128)
+        .build()
+    else {
+        return;
+    };
+
+    if let Ok(result) = evm.transact(tx) {
+        // Gas accounting must stay within the limit whatever the engine did
+        assert!(result.result.gas_used() <= input.gas_limit as u64);
+    }
+});
@@ -0,0 +1,232 @@
+//! EIP-2930 access list generation
+//!
+//! Builds access lists from the addresses and storage slots guillotine-mini tracked as
+//! accessed during execution, in the manner of `eth_createAccessList`.
+
+use super::ffi::{self, EvmHandle};
+use super::ffi_safe;
+use super::types;
+use revm::{
+    context_interface::{
+        result::ExecutionResult,
+        transaction::{AccessList, AccessListItem},
+    },
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::{hardfork::SpecId, Address, B256, U256},
+};
+
+/// Outcome of `GuillotineMiniEvm::create_access_list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessListResult {
+    /// Addresses and storage slots accessed by the transaction
+    pub access_list: AccessList,
+    /// Gas used by the transaction with the access list attached
+    pub gas_used: u64,
+    /// Execution result with the access list attached
+    pub result: ExecutionResult,
+}
+
+/// Accessed addresses and `(address, slot)` storage keys
+type Accessed = (Vec<Address>, Vec<(Address, B256)>);
+
+/// Read the addresses and storage slots accessed during the last execution
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+///
+/// # Errors
+/// Returns an error if the handle is null or an entry cannot be read.
+pub(crate) fn read_accessed_from_ffi(handle: *mut EvmHandle) -> Result<Accessed, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let address_count = unsafe { ffi::evm_get_accessed_address_count(handle) };
+    let mut addresses = Vec::with_capacity(address_count);
+    for i in 0..address_count {
+        let mut addr_bytes = [0u8; 20];
+        if !unsafe { ffi::evm_get_accessed_address(handle, i, addr_bytes.as_mut_ptr()) } {
+            return Err("evm_get_accessed_address failed");
+        }
+        addresses.push(types::address_from_bytes(&addr_bytes));
+    }
+
+    let slot_count = unsafe { ffi::evm_get_accessed_storage_count(handle) };
+    let mut slots = Vec::with_capacity(slot_count);
+    for i in 0..slot_count {
+        let mut addr_bytes = [0u8; 20];
+        let mut slot_bytes = [0u8; 32];
+        if !unsafe { ffi::evm_get_accessed_storage(handle, i, addr_bytes.as_mut_ptr(), slot_bytes.as_mut_ptr()) } {
+            return Err("evm_get_accessed_storage failed");
+        }
+        slots.push((types::address_from_bytes(&addr_bytes), B256::from(slot_bytes)));
+    }
+
+    Ok((addresses, slots))
+}
+
+/// Whether `address` was warm (EIP-2929) when the last execution finished
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn is_address_warm_in_ffi(handle: *mut EvmHandle, address: Address) -> Result<bool, &'static str> {
+    ffi_safe::check_handle(handle)?;
+    let addr_bytes = types::address_to_bytes(&address);
+    Ok(unsafe { ffi::evm_is_address_warm(handle, addr_bytes.as_ptr()) })
+}
+
+/// Whether storage `slot` of `address` was warm (EIP-2929) when the last execution finished
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn is_storage_warm_in_ffi(handle: *mut EvmHandle, address: Address, slot: U256) -> Result<bool, &'static str> {
+    ffi_safe::check_handle(handle)?;
+    let addr_bytes = types::address_to_bytes(&address);
+    let slot_bytes = types::u256_to_be_bytes(&slot);
+    Ok(unsafe { ffi::evm_is_storage_warm(handle, addr_bytes.as_ptr(), slot_bytes.as_ptr()) })
+}
+
+/// Addresses and storage slots that are warm when a transaction starts (EIP-2929)
+///
+/// These are the precompiles, the coinbase from Shanghai (EIP-3651) and the entries of the
+/// transaction's access list (EIP-2930). The sender and recipient are warmed by guillotine-mini
+/// itself. Nothing is warm before Berlin.
+pub fn initially_warm(
+    spec: SpecId,
+    coinbase: Address,
+    access_list: &AccessList,
+) -> (Vec<Address>, Vec<(Address, B256)>) {
+    if !spec.is_enabled_in(SpecId::BERLIN) {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut addresses: Vec<Address> =
+        Precompiles::new(PrecompileSpecId::from_spec_id(spec)).addresses().copied().collect();
+    if spec.is_enabled_in(SpecId::SHANGHAI) {
+        addresses.push(coinbase);
+    }
+
+    let mut slots = Vec::new();
+    for item in access_list.0.iter() {
+        addresses.push(item.address);
+        slots.extend(item.storage_keys.iter().map(|key| (item.address, *key)));
+    }
+
+    (addresses, slots)
+}
+
+/// Mark addresses and storage slots as warm in guillotine-mini
+///
+/// Replaces any previously set warm entries.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn write_warm_to_ffi(
+    handle: *mut EvmHandle,
+    addresses: &[Address],
+    slots: &[(Address, B256)],
+) -> Result<(), &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let address_bytes: Vec<u8> = addresses.iter().flat_map(|address| address.0 .0).collect();
+    ffi_safe::check_packed_len(address_bytes.len(), addresses.len(), 20)?;
+    if !unsafe { ffi::evm_set_access_list_addresses(handle, address_bytes.as_ptr(), addresses.len()) } {
+        return Err("evm_set_access_list_addresses");
+    }
+
+    let slot_bytes: Vec<u8> = slots
+        .iter()
+        .flat_map(|(address, slot)| address.0 .0.into_iter().chain(slot.0))
+        .collect();
+    ffi_safe::check_packed_len(slot_bytes.len(), slots.len(), 52)?;
+    if !unsafe { ffi::evm_set_access_list_storage_keys(handle, slot_bytes.as_ptr(), slots.len()) } {
+        return Err("evm_set_access_list_storage_keys");
+    }
+
+    Ok(())
+}
+
+/// Build an access list from accessed addresses and storage slots
+///
+/// Addresses for which `is_excluded` returns true (sender, recipient, precompiles) are warm
+/// regardless and only listed when storage slots of theirs were accessed. Entries keep their
+/// first-access order and are deduplicated.
+pub fn build_access_list(
+    addresses: &[Address],
+    slots: &[(Address, B256)],
+    is_excluded: impl Fn(&Address) -> bool,
+) -> AccessList {
+    let mut items: Vec<AccessListItem> = Vec::new();
+
+    fn item_for(items: &mut Vec<AccessListItem>, address: Address) -> &mut AccessListItem {
+        let index = match items.iter().position(|item| item.address == address) {
+            Some(index) => index,
+            None => {
+                items.push(AccessListItem { address, storage_keys: Vec::new() });
+                items.len() - 1
+            }
+        };
+        &mut items[index]
+    }
+
+    for address in addresses {
+        if !is_excluded(address) {
+            item_for(&mut items, *address);
+        }
+    }
+    for (address, slot) in slots {
+        let item = item_for(&mut items, *address);
+        if !item.storage_keys.contains(slot) {
+            item.storage_keys.push(*slot);
+        }
+    }
+
+    AccessList(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::address;
+
+    #[test]
+    fn test_initially_warm_by_hardfork() {
+        let coinbase = address!("c000000000000000000000000000000000000000");
+        let other = address!("2000000000000000000000000000000000000000");
+        let access_list =
+            AccessList(vec![AccessListItem { address: other, storage_keys: vec![B256::with_last_byte(1)] }]);
+
+        let (addresses, slots) = initially_warm(SpecId::ISTANBUL, coinbase, &access_list);
+        assert!(addresses.is_empty() && slots.is_empty(), "Nothing is warm before Berlin");
+
+        let (addresses, slots) = initially_warm(SpecId::BERLIN, coinbase, &access_list);
+        assert!(addresses.contains(&address!("0000000000000000000000000000000000000001")));
+        assert!(!addresses.contains(&coinbase), "Coinbase is only warm from Shanghai");
+        assert!(addresses.contains(&other));
+        assert_eq!(slots, vec![(other, B256::with_last_byte(1))]);
+
+        let (addresses, _) = initially_warm(SpecId::CANCUN, coinbase, &access_list);
+        assert!(addresses.contains(&address!("000000000000000000000000000000000000000a")));
+        assert!(addresses.contains(&coinbase));
+    }
+
+    #[test]
+    fn test_build_access_list_excludes_and_dedupes() {
+        let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+        let target = address!("1000000000000000000000000000000000000000");
+        let other = address!("2000000000000000000000000000000000000000");
+        let slot = B256::with_last_byte(1);
+
+        let list = build_access_list(
+            &[sender, target, other, other],
+            &[(target, slot), (target, slot), (other, B256::ZERO)],
+            |addr| *addr == sender || *addr == target,
+        );
+
+        assert_eq!(
+            list.0,
+            vec![
+                AccessListItem { address: other, storage_keys: vec![B256::ZERO] },
+                AccessListItem { address: target, storage_keys: vec![slot] },
+            ]
+        );
+    }
+}
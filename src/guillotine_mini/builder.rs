@@ -0,0 +1,347 @@
+//! Fluent construction of a [`GuillotineMiniEvm`]
+//!
+//! [`EvmBuilder`] gathers the options otherwise applied one setter at a time after
+//! construction, so an EVM is fully configured when it is created:
+//!
+//! ```rust,no_run
+//! use guillotine_rs::guillotine_mini::{AccessListSlots, GuillotineMiniEvm};
+//! use revm::{Context, MainContext};
+//!
+//! let evm = GuillotineMiniEvm::builder()
+//!     .ctx(Context::mainnet())
+//!     .sync(AccessListSlots)
+//!     .tracing(true)
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use super::{
+    cancel::CancellationToken,
+    chain_spec::{ChainContext, ChainSpec},
+    error::EvmAdapterError,
+    evm::GuillotineMiniEvm,
+    ffi,
+    hardfork::Hardfork,
+    struct_log::DeepTraceLimits,
+    sync_strategy::SyncStrategy,
+};
+use revm::{
+    context::{Cfg, Context},
+    database_interface::Database,
+    primitives::{hardfork::SpecId, Address},
+};
+#[cfg(feature = "config-api")]
+use std::sync::Arc;
+
+/// Builder for [`GuillotineMiniEvm`], obtained from [`GuillotineMiniEvm::builder`]
+///
+/// Options left unset keep the defaults of [`GuillotineMiniEvm::try_new`]. `CTX` is `()` until
+/// a context is given with [`ctx`](Self::ctx); only then can the EVM be built.
+pub struct EvmBuilder<CTX> {
+    ctx: CTX,
+    hardfork: Option<Hardfork>,
+    log_level: Option<u8>,
+    sync_strategy: Option<Box<dyn SyncStrategy>>,
+    native_tracing: bool,
+    deep_tracing: Option<DeepTraceLimits>,
+    persist_state: bool,
+    discovery_mode: bool,
+    host_callbacks: bool,
+    scan_bytecode: bool,
+    verify_code_hashes: bool,
+    code_cache: bool,
+    bridge_stats: bool,
+    tx_origin: Option<Address>,
+    disable_balance_check: bool,
+    disable_nonce_check: bool,
+    cancellation: Option<CancellationToken>,
+    #[cfg(feature = "config-api")]
+    config: Option<Arc<super::config::EvmConfig>>,
+}
+
+impl GuillotineMiniEvm<()> {
+    /// Start building an EVM
+    pub fn builder() -> EvmBuilder<()> {
+        EvmBuilder::new()
+    }
+}
+
+impl EvmBuilder<()> {
+    /// Builder with default options and no context
+    pub fn new() -> Self {
+        Self {
+            ctx: (),
+            hardfork: None,
+            log_level: None,
+            sync_strategy: None,
+            native_tracing: false,
+            deep_tracing: None,
+            persist_state: false,
+            discovery_mode: false,
+            host_callbacks: true,
+            scan_bytecode: true,
+            verify_code_hashes: false,
+            code_cache: false,
+            bridge_stats: false,
+            tx_origin: None,
+            disable_balance_check: false,
+            disable_nonce_check: false,
+            cancellation: None,
+            #[cfg(feature = "config-api")]
+            config: None,
+        }
+    }
+}
+
+impl Default for EvmBuilder<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<CTX> EvmBuilder<CTX> {
+    /// REVM context the EVM executes against
+    pub fn ctx<C>(self, ctx: C) -> EvmBuilder<C> {
+        EvmBuilder {
+            ctx,
+            hardfork: self.hardfork,
+            log_level: self.log_level,
+            sync_strategy: self.sync_strategy,
+            native_tracing: self.native_tracing,
+            deep_tracing: self.deep_tracing,
+            persist_state: self.persist_state,
+            discovery_mode: self.discovery_mode,
+            host_callbacks: self.host_callbacks,
+            scan_bytecode: self.scan_bytecode,
+            verify_code_hashes: self.verify_code_hashes,
+            code_cache: self.code_cache,
+            bridge_stats: self.bridge_stats,
+            tx_origin: self.tx_origin,
+            disable_balance_check: self.disable_balance_check,
+            disable_nonce_check: self.disable_nonce_check,
+            cancellation: self.cancellation,
+            #[cfg(feature = "config-api")]
+            config: self.config,
+        }
+    }
+
+    /// Execute against the genesis block of `chain` (see [`ChainSpec::context`])
+    pub fn chain(self, chain: &ChainSpec) -> EvmBuilder<ChainContext> {
+        self.ctx(chain.context())
+    }
+
+    /// Create guillotine-mini for `hardfork` instead of the hardfork of the context's spec
+    ///
+    /// Only the engine is affected: validation, fees and precompiles still follow the context.
+    pub fn hardfork(mut self, hardfork: Hardfork) -> Self {
+        self.hardfork = Some(hardfork);
+        self
+    }
+
+    /// Log level of guillotine-mini (see `ffi::LOG_LEVEL_*`)
+    ///
+    /// Defaults to the level enabled by the current `tracing` subscriber with the `tracing`
+    /// feature, and to `LOG_LEVEL_OFF` without it.
+    pub fn log_level(mut self, level: u8) -> Self {
+        self.log_level = Some(level);
+        self
+    }
+
+    /// Sync strategy (see `GuillotineMiniEvm::set_sync_strategy`)
+    pub fn sync(mut self, strategy: impl SyncStrategy + 'static) -> Self {
+        self.sync_strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Native tracing (see `GuillotineMiniEvm::set_native_tracing`)
+    pub fn tracing(mut self, enabled: bool) -> Self {
+        self.native_tracing = enabled;
+        self
+    }
+
+    /// Deep tracing within `limits` (see `GuillotineMiniEvm::set_deep_tracing`)
+    pub fn deep_tracing(mut self, limits: DeepTraceLimits) -> Self {
+        self.deep_tracing = Some(limits);
+        self
+    }
+
+    /// State persistence between transactions (see `GuillotineMiniEvm::set_persist_state`)
+    pub fn persist_state(mut self, persist: bool) -> Self {
+        self.persist_state = persist;
+        self
+    }
+
+    /// Discovery mode (see `GuillotineMiniEvm::set_discovery_mode`)
+    pub fn discovery_mode(mut self, enabled: bool) -> Self {
+        self.discovery_mode = enabled;
+        self
+    }
+
+    /// Host callbacks (see `GuillotineMiniEvm::set_host_callbacks`)
+    pub fn host_callbacks(mut self, enabled: bool) -> Self {
+        self.host_callbacks = enabled;
+        self
+    }
+
+    /// Bytecode slot scanning (see `GuillotineMiniEvm::set_scan_bytecode`)
+    pub fn scan_bytecode(mut self, enabled: bool) -> Self {
+        self.scan_bytecode = enabled;
+        self
+    }
+
+    /// Code hash verification (see `GuillotineMiniEvm::set_verify_code_hashes`)
+    pub fn verify_code_hashes(mut self, verify: bool) -> Self {
+        self.verify_code_hashes = verify;
+        self
+    }
+
+    /// Bytecode analysis cache (see `GuillotineMiniEvm::enable_code_cache`)
+    pub fn code_cache(mut self, enabled: bool) -> Self {
+        self.code_cache = enabled;
+        self
+    }
+
+    /// Sync counters (see `GuillotineMiniEvm::set_bridge_stats`)
+    pub fn bridge_stats(mut self, enabled: bool) -> Self {
+        self.bridge_stats = enabled;
+        self
+    }
+
+    /// ORIGIN override (see `GuillotineMiniEvm::set_tx_origin`)
+    pub fn tx_origin(mut self, origin: Address) -> Self {
+        self.tx_origin = Some(origin);
+        self
+    }
+
+    /// Skip the sender balance check (see `GuillotineMiniEvm::set_disable_balance_check`)
+    pub fn disable_balance_check(mut self, disabled: bool) -> Self {
+        self.disable_balance_check = disabled;
+        self
+    }
+
+    /// Skip the sender nonce check (see `GuillotineMiniEvm::set_disable_nonce_check`)
+    pub fn disable_nonce_check(mut self, disabled: bool) -> Self {
+        self.disable_nonce_check = disabled;
+        self
+    }
+
+    /// Cancellation token, e.g. one shared by several EVMs (see
+    /// `GuillotineMiniEvm::set_cancellation_token`)
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Create guillotine-mini from a custom configuration instead of the hardfork defaults
+    ///
+    /// Requires the `config-api` feature. Every instance the EVM creates, including internal
+    /// ones (e.g. for `estimate_gas`), comes from the configuration, which sets the engine's
+    /// hardfork; [`hardfork`](Self::hardfork) then only records the hardfork the EVM reports.
+    /// Pass an `Arc<EvmConfig>` to share one configuration, and its handlers, between EVMs.
+    ///
+    /// Without a configuration, a context whose `limit_contract_code_size` differs from the
+    /// EIP-170 default gets one carrying that limit.
+    #[cfg(feature = "config-api")]
+    pub fn config(mut self, config: impl Into<Arc<super::config::EvmConfig>>) -> Self {
+        self.config = Some(config.into());
+        self
+    }
+}
+
+/// EVM returned by [`EvmBuilder::build`]
+type BuildResult<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> = Result<
+    GuillotineMiniEvm<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>,
+    EvmAdapterError<<DB as Database>::Error>,
+>;
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> EvmBuilder<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>
+where
+    BLOCK: revm::context_interface::Block,
+    TX: revm::context_interface::Transaction,
+    CFG: Cfg<Spec = SpecId>,
+    DB: Database,
+    JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+    LOCAL: revm::context_interface::LocalContextTr,
+{
+    /// Create the EVM
+    ///
+    /// Fails with [`EvmAdapterError::AbiMismatch`] if the linked libguillotine_mini implements a
+    /// different C ABI version than these bindings, and with `EvmAdapterError::Unsupported("config")`
+    /// if a configuration is set but the library does not export the config ABI.
+    pub fn build(self) -> BuildResult<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> {
+        ffi::check_abi_version()
+            .map_err(|found| EvmAdapterError::AbiMismatch { expected: ffi::ABI_VERSION, found })?;
+
+        let hardfork = self.hardfork.unwrap_or_else(|| Hardfork::from_spec(self.ctx.cfg.spec()));
+        #[cfg(feature = "config-api")]
+        let config = self.config.or_else(|| code_size_config(hardfork, self.ctx.cfg.max_code_size()).map(Arc::new));
+        #[cfg(feature = "config-api")]
+        if config.as_ref().is_some_and(|config| !config.is_supported()) {
+            return Err(EvmAdapterError::Unsupported("config"));
+        }
+        #[cfg(feature = "config-api")]
+        let handle = match &config {
+            Some(config) => ffi::OwnedEvmHandle::with_config(config.as_raw(), self.log_level),
+            None => ffi::OwnedEvmHandle::with_log_level(hardfork, self.log_level),
+        };
+        #[cfg(not(feature = "config-api"))]
+        let handle = ffi::OwnedEvmHandle::with_log_level(hardfork, self.log_level);
+
+        let mut evm = GuillotineMiniEvm::from_handle(self.ctx, handle.map_err(EvmAdapterError::ffi)?, hardfork);
+        #[cfg(feature = "config-api")]
+        evm.set_config(config);
+        evm.set_log_level(self.log_level);
+        if let Some(strategy) = self.sync_strategy {
+            evm.set_boxed_sync_strategy(strategy);
+        }
+        evm.set_native_tracing(self.native_tracing);
+        evm.set_deep_tracing(self.deep_tracing);
+        evm.set_persist_state(self.persist_state);
+        evm.set_discovery_mode(self.discovery_mode);
+        evm.set_host_callbacks(self.host_callbacks);
+        evm.set_scan_bytecode(self.scan_bytecode);
+        evm.set_verify_code_hashes(self.verify_code_hashes);
+        evm.set_bridge_stats(self.bridge_stats);
+        evm.set_tx_origin(self.tx_origin);
+        evm.set_disable_balance_check(self.disable_balance_check);
+        evm.set_disable_nonce_check(self.disable_nonce_check);
+        if let Some(token) = self.cancellation {
+            evm.set_cancellation_token(token);
+        }
+        if self.code_cache {
+            evm.enable_code_cache()?;
+        }
+        Ok(evm)
+    }
+}
+
+/// Configuration carrying a context's `limit_contract_code_size` to guillotine-mini, if it differs
+/// from the EIP-170 default
+///
+/// The initcode limit follows at twice the code size limit, as in EIP-3860. Without the config
+/// ABI the limit stays at guillotine-mini's default.
+#[cfg(feature = "config-api")]
+fn code_size_config(hardfork: Hardfork, max_code_size: usize) -> Option<super::config::EvmConfig> {
+    if max_code_size == revm::primitives::eip170::MAX_CODE_SIZE || ffi::config_api().is_none() {
+        return None;
+    }
+    let max_code_size = u32::try_from(max_code_size).unwrap_or(u32::MAX);
+    let config = super::config::EvmConfigBuilder::new()
+        .hardfork(hardfork.spec())
+        .max_bytecode_size(max_code_size)
+        .max_initcode_size(max_code_size.saturating_mul(2));
+    Some(config.build())
+}
+
+impl<CTX> std::fmt::Debug for EvmBuilder<CTX> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmBuilder")
+            .field("hardfork", &self.hardfork)
+            .field("log_level", &self.log_level)
+            .field("native_tracing", &self.native_tracing)
+            .field("deep_tracing", &self.deep_tracing)
+            .field("persist_state", &self.persist_state)
+            .field("discovery_mode", &self.discovery_mode)
+            .finish_non_exhaustive()
+    }
+}
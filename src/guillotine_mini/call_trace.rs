@@ -0,0 +1,142 @@
+//! Internal call readback
+//!
+//! guillotine-mini records every message call and contract creation performed by the executed
+//! code. The raw records are read back here, independently of any tracer output format.
+
+use super::ffi::{self, EvmHandle, FfiCallInfo};
+use super::{ffi_safe, types};
+use revm::{
+    context_interface::result::HaltReason,
+    primitives::{Address, Bytes, U256},
+};
+
+/// Kind of an internal call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum CallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+impl CallKind {
+    /// Map a guillotine-mini call kind code (`EVM_CALL_*`)
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code {
+            ffi::EVM_CALL_CALL => Some(Self::Call),
+            ffi::EVM_CALL_CALLCODE => Some(Self::CallCode),
+            ffi::EVM_CALL_DELEGATECALL => Some(Self::DelegateCall),
+            ffi::EVM_CALL_STATICCALL => Some(Self::StaticCall),
+            ffi::EVM_CALL_CREATE => Some(Self::Create),
+            ffi::EVM_CALL_CREATE2 => Some(Self::Create2),
+            _ => None,
+        }
+    }
+
+    /// Whether the call creates a contract
+    pub fn is_create(&self) -> bool {
+        matches!(self, Self::Create | Self::Create2)
+    }
+}
+
+/// Message call or contract creation performed during execution, in the order it started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalCall {
+    pub kind: CallKind,
+    /// Call depth (1 for calls made by the transaction's own code)
+    pub depth: usize,
+    /// Caller, i.e. the address executing the calling code
+    pub from: Address,
+    /// Callee, or the created contract's address
+    pub to: Address,
+    /// Value transferred (the apparent value for DELEGATECALL)
+    pub value: U256,
+    /// Gas made available to the call
+    pub gas_limit: u64,
+    /// Gas consumed by the call
+    pub gas_used: u64,
+    /// Calldata, or the init code of a creation
+    pub input: Bytes,
+    /// Execution status code (`EVM_STATUS_*`)
+    pub status: u8,
+    /// Return or revert data (the deployed code for successful creations)
+    pub output: Bytes,
+}
+
+impl InternalCall {
+    /// Whether the call completed without reverting or halting
+    pub fn is_success(&self) -> bool {
+        self.status == ffi::EVM_STATUS_SUCCESS
+    }
+
+    /// Whether the call ended with REVERT
+    pub fn is_revert(&self) -> bool {
+        self.status == ffi::EVM_STATUS_REVERT
+    }
+
+    /// Reason of an exceptional halt, if the call halted
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        types::halt_reason_from_status(self.status)
+    }
+}
+
+/// Read the internal calls of the last execution from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn read_calls_from_ffi(handle: *mut EvmHandle) -> Result<Vec<InternalCall>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { ffi::evm_get_call_count(handle) };
+    let mut calls = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut info = FfiCallInfo::default();
+        if !unsafe { ffi::evm_get_call(handle, i, &mut info) } {
+            return Err("evm_get_call failed");
+        }
+        let kind = CallKind::from_code(info.kind).ok_or("evm_get_call: unknown call kind")?;
+
+        let mut output = vec![0u8; info.output_len];
+        let copied = unsafe { ffi::evm_get_call_output(handle, i, output.as_mut_ptr(), output.len()) };
+        ffi_safe::check_buffer(output.len(), copied)?;
+        output.truncate(copied);
+
+        let input_len = unsafe { ffi::evm_get_call_input(handle, i, std::ptr::null_mut(), 0) };
+        let mut input = vec![0u8; input_len];
+        let copied = unsafe { ffi::evm_get_call_input(handle, i, input.as_mut_ptr(), input.len()) };
+        if copied != input_len {
+            return Err("evm_get_call_input");
+        }
+
+        calls.push(InternalCall {
+            kind,
+            depth: info.depth as usize,
+            from: types::address_from_bytes(&info.from),
+            to: types::address_from_bytes(&info.to),
+            value: types::u256_from_be_bytes(&info.value),
+            gas_limit: info.gas_limit,
+            gas_used: info.gas_used,
+            input: Bytes::from(input),
+            status: info.status,
+            output: Bytes::from(output),
+        });
+    }
+    Ok(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_kind_from_code() {
+        assert_eq!(CallKind::from_code(ffi::EVM_CALL_DELEGATECALL), Some(CallKind::DelegateCall));
+        assert_eq!(CallKind::from_code(ffi::EVM_CALL_CREATE2), Some(CallKind::Create2));
+        assert_eq!(CallKind::from_code(0xff), None);
+        assert!(CallKind::Create.is_create() && !CallKind::StaticCall.is_create());
+    }
+}
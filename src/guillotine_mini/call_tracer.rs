@@ -0,0 +1,334 @@
+//! geth `callTracer` call trees
+//!
+//! [`CallTracer`] is a REVM `Inspector` building the tree of message calls and creations of a
+//! transaction in the shape of geth's `callTracer`. With the `serde` feature a [`CallFrame`]
+//! serializes to the JSON block explorers and debugging frontends consume. Run it with
+//! `GuillotineMiniEvm::inspect`, or on REVM itself.
+
+use super::call_trace::CallKind;
+use super::revert::decode_revert_reason;
+use revm::{
+    context_interface::{ContextTr, CreateScheme},
+    inspector::Inspector,
+    interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult, Interpreter},
+    primitives::{Address, Bytes, Log, B256, U256},
+};
+
+/// Message call or creation of a call trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallFrame {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub kind: CallKind,
+    pub from: Address,
+    /// Callee (the code address for DELEGATECALL and CALLCODE), or the created contract
+    /// (`None` when the creation failed)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub to: Option<Address>,
+    /// Value transferred (`None` for STATICCALL and DELEGATECALL)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub value: Option<U256>,
+    /// Gas made available to the call
+    #[cfg_attr(feature = "serde", serde(serialize_with = "hex_u64"))]
+    pub gas: u64,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "hex_u64"))]
+    pub gas_used: u64,
+    /// Calldata, or the init code of a creation
+    pub input: Bytes,
+    /// Return or revert data (the deployed code for successful creations)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_empty"))]
+    pub output: Bytes,
+    /// Why the call failed, in geth's wording (`"execution reverted"`, `"out of gas"`, ...)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+    /// Reason decoded from the revert data (see [`decode_revert_reason`])
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub revert_reason: Option<String>,
+    /// Logs emitted by the call itself (empty when the call or one of its callers failed)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub logs: Vec<CallLog>,
+    /// Calls made by the call, in the order they started
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub calls: Vec<CallFrame>,
+}
+
+/// Log emitted by a [`CallFrame`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CallLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+    /// Number of calls the frame had made when the log was emitted
+    #[cfg_attr(feature = "serde", serde(serialize_with = "hex_u64"))]
+    pub position: u64,
+}
+
+impl CallFrame {
+    /// Whether the call completed without reverting or halting
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Pretty-printed JSON document in geth's `callTracer` format
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("call frames serialize to JSON")
+    }
+
+    /// Drop the logs of this frame and its descendants, which a failed call undoes
+    fn clear_logs(&mut self) {
+        self.logs.clear();
+        self.calls.iter_mut().for_each(CallFrame::clear_logs);
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_empty(bytes: &Bytes) -> bool {
+    bytes.is_empty()
+}
+
+#[cfg(feature = "serde")]
+fn hex_u64<S: serde::Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+/// `Inspector` recording the call tree of the transactions it observes
+///
+/// Each top-level call replaces the tree of the previous transaction.
+#[derive(Debug, Clone, Default)]
+pub struct CallTracer {
+    /// Calls entered and not exited yet, innermost last
+    open: Vec<CallFrame>,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call tree of the last transaction traced to completion
+    pub fn frame(&self) -> Option<&CallFrame> {
+        self.root.as_ref()
+    }
+
+    /// Take the call tree of the last transaction traced to completion
+    pub fn into_frame(self) -> Option<CallFrame> {
+        self.root
+    }
+
+    fn enter(
+        &mut self,
+        kind: CallKind,
+        from: Address,
+        to: Option<Address>,
+        value: Option<U256>,
+        gas: u64,
+        input: Bytes,
+    ) {
+        self.open.push(CallFrame {
+            kind,
+            from,
+            to,
+            value,
+            gas,
+            gas_used: 0,
+            input,
+            output: Bytes::new(),
+            error: None,
+            revert_reason: None,
+            logs: Vec::new(),
+            calls: Vec::new(),
+        });
+    }
+
+    fn exit(&mut self, result: InstructionResult, output: &Bytes, gas_used: u64, created: Option<Address>) {
+        let Some(mut frame) = self.open.pop() else { return };
+        frame.gas_used = gas_used;
+        frame.output = output.clone();
+        frame.error = error_message(result);
+        if result.is_revert() {
+            frame.revert_reason = decode_revert_reason(output);
+        }
+        if frame.kind.is_create() {
+            frame.to = created;
+        }
+        if !frame.is_success() {
+            frame.clear_logs();
+        }
+
+        match self.open.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for CallTracer {
+    fn log(&mut self, _interp: &mut Interpreter, _context: &mut CTX, log: Log) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.logs.push(CallLog {
+                address: log.address,
+                topics: log.topics().to_vec(),
+                data: log.data.data,
+                position: frame.calls.len() as u64,
+            });
+        }
+    }
+
+    fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let (kind, value) = match inputs.scheme {
+            CallScheme::Call => (CallKind::Call, Some(inputs.value.get())),
+            CallScheme::CallCode => (CallKind::CallCode, Some(inputs.value.get())),
+            CallScheme::DelegateCall => (CallKind::DelegateCall, None),
+            CallScheme::StaticCall => (CallKind::StaticCall, None),
+        };
+        let input = inputs.input.bytes(context);
+        self.enter(kind, inputs.caller, Some(inputs.bytecode_address), value, inputs.gas_limit, input);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        let result = &outcome.result;
+        self.exit(result.result, &result.output, result.gas.spent(), None);
+    }
+
+    fn create(&mut self, _context: &mut CTX, inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme {
+            CreateScheme::Create2 { .. } => CallKind::Create2,
+            _ => CallKind::Create,
+        };
+        self.enter(kind, inputs.caller, None, Some(inputs.value), inputs.gas_limit, inputs.init_code.clone());
+        None
+    }
+
+    fn create_end(&mut self, _context: &mut CTX, _inputs: &CreateInputs, outcome: &mut CreateOutcome) {
+        let result = &outcome.result;
+        self.exit(result.result, &result.output, result.gas.spent(), outcome.address);
+    }
+}
+
+/// geth's error message for a call ending with `result`; `None` for success
+pub fn error_message(result: InstructionResult) -> Option<String> {
+    use InstructionResult::*;
+
+    let message = match result {
+        _ if result.is_ok() => return None,
+        Revert => "execution reverted",
+        OutOfGas | MemoryOOG | MemoryLimitOOG | PrecompileOOG | InvalidOperandOOG | ReentrancySentryOOG => "out of gas",
+        OpcodeNotFound | InvalidFEOpcode | NotActivated => "invalid opcode",
+        InvalidJump => "invalid jump destination",
+        StackUnderflow => "stack underflow",
+        StackOverflow => "stack limit reached 1024",
+        CallTooDeep => "max call depth exceeded",
+        OutOfFunds => "insufficient balance for transfer",
+        CallNotAllowedInsideStatic | StateChangeDuringStaticCall => "write protection",
+        CreateCollision => "contract address collision",
+        NonceOverflow => "nonce uint64 overflow",
+        CreateContractSizeLimit => "max code size exceeded",
+        CreateInitCodeSizeLimit => "max initcode size exceeded",
+        CreateContractStartingWithEF => "invalid code: must not begin with 0xef",
+        OutOfOffset => "return data out of bounds",
+        PrecompileError => "precompile failed",
+        other => return Some(format!("{other:?}")),
+    };
+    Some(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        context::Context,
+        interpreter::{CallInput, CallValue, Gas, InterpreterResult},
+        MainContext,
+    };
+
+    fn call_inputs(scheme: CallScheme, caller: Address, target: Address) -> CallInputs {
+        CallInputs {
+            input: CallInput::Bytes(Bytes::from_static(&[0xab])),
+            return_memory_offset: 0..0,
+            gas_limit: 1_000,
+            bytecode_address: target,
+            known_bytecode: None,
+            target_address: target,
+            caller,
+            value: CallValue::Transfer(U256::from(5)),
+            scheme,
+            is_static: false,
+        }
+    }
+
+    fn outcome(result: InstructionResult, output: Bytes, spent: u64) -> CallOutcome {
+        let mut gas = Gas::new(1_000);
+        gas.set_spent(spent);
+        CallOutcome::new(InterpreterResult::new(result, output, gas), 0..0)
+    }
+
+    #[test]
+    fn test_call_tracer_tree() {
+        let mut ctx = Context::mainnet();
+        let (a, b, c) = (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let mut interp = Interpreter::default_ext();
+        let mut tracer = CallTracer::new();
+
+        let mut top = call_inputs(CallScheme::Call, a, b);
+        tracer.call(&mut ctx, &mut top);
+        let mut inner = call_inputs(CallScheme::StaticCall, b, c);
+        tracer.call(&mut ctx, &mut inner);
+        tracer.log(&mut interp, &mut ctx, Log { address: c, ..Default::default() });
+        let mut reverted = outcome(InstructionResult::Revert, Bytes::new(), 300);
+        tracer.call_end(&mut ctx, &inner, &mut reverted);
+        tracer.log(&mut interp, &mut ctx, Log { address: b, ..Default::default() });
+        let mut returned = outcome(InstructionResult::Return, Bytes::from_static(&[1]), 700);
+        tracer.call_end(&mut ctx, &top, &mut returned);
+
+        let frame = tracer.into_frame().unwrap();
+        assert_eq!((frame.kind, frame.from, frame.to, frame.value), (CallKind::Call, a, Some(b), Some(U256::from(5))));
+        assert_eq!((frame.gas, frame.gas_used, frame.error.as_deref()), (1_000, 700, None));
+        assert_eq!(frame.logs.len(), 1);
+        assert_eq!((frame.logs[0].address, frame.logs[0].position), (b, 1));
+
+        let inner = &frame.calls[0];
+        assert_eq!((inner.kind, inner.value, inner.input.as_ref()), (CallKind::StaticCall, None, &[0xab][..]));
+        assert_eq!(inner.error.as_deref(), Some("execution reverted"));
+        assert!(inner.logs.is_empty(), "A reverted call keeps no logs");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_call_frame_json() {
+        let mut ctx = Context::mainnet();
+        let mut tracer = CallTracer::new();
+        let mut inputs = call_inputs(CallScheme::Call, Address::with_last_byte(1), Address::with_last_byte(2));
+        tracer.call(&mut ctx, &mut inputs);
+        let mut outcome = outcome(InstructionResult::Stop, Bytes::new(), 21);
+        tracer.call_end(&mut ctx, &inputs, &mut outcome);
+
+        let json: serde_json::Value = serde_json::from_str(&tracer.frame().unwrap().to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "CALL",
+                "from": "0x0000000000000000000000000000000000000001",
+                "to": "0x0000000000000000000000000000000000000002",
+                "value": "0x5",
+                "gas": "0x3e8",
+                "gasUsed": "0x15",
+                "input": "0xab",
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(error_message(InstructionResult::Stop), None);
+        assert_eq!(error_message(InstructionResult::MemoryOOG).as_deref(), Some("out of gas"));
+        assert_eq!(error_message(InstructionResult::InvalidJump).as_deref(), Some("invalid jump destination"));
+        assert_eq!(error_message(InstructionResult::FatalExternalError).as_deref(), Some("FatalExternalError"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Cooperative cancellation
+//!
+//! A [`CancellationToken`] lets another thread abort an execution that would otherwise run
+//! for a long time (an infinite loop under a huge gas limit). Cancelling asks guillotine-mini to
+//! stop at the next opcode with `evm_request_interrupt`; the interrupted transaction fails with
+//! `EvmAdapterError::Cancelled` and leaves no state behind.
+//!
+//! Deadlines set with `TransactOptions::deadline` interrupt executions the same way, from a
+//! watchdog thread that lives as long as the execution.
+
+use super::ffi::{self, EvmHandle};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+/// Handle of an instance currently executing
+#[derive(PartialEq)]
+struct Running(*mut EvmHandle);
+
+// Safety: the pointer is only used to call `evm_request_interrupt`, which guillotine-mini
+// allows from any thread, and only while the executing thread keeps it registered.
+unsafe impl Send for Running {}
+
+impl Running {
+    fn interrupt(&self) {
+        unsafe { ffi::evm_request_interrupt(self.0) };
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    running: Mutex<Vec<Running>>,
+}
+
+/// Shared flag aborting the executions of the EVMs holding it, obtained from
+/// `GuillotineMiniEvm::cancellation_token`
+///
+/// Cancellation is permanent: every later transaction on those EVMs fails with
+/// `EvmAdapterError::Cancelled` until they are given a new token with
+/// `GuillotineMiniEvm::set_cancellation_token`.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+impl CancellationToken {
+    /// Token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel, interrupting the executions in progress
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        for running in self.lock().iter() {
+            running.interrupt();
+        }
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Run `execute` on `handle`, interruptible by `cancel` from another thread
+    ///
+    /// An interrupt requested as `execute` returns stays pending on the handle, so callers
+    /// check [`is_cancelled`](Self::is_cancelled) afterwards and reset the handle.
+    pub(crate) fn run<T>(&self, handle: *mut EvmHandle, execute: impl FnOnce() -> T) -> T {
+        self.lock().push(Running(handle));
+        let _registration = Registration { token: self, handle };
+        if self.is_cancelled() {
+            // Cancelled before the handle was registered: `cancel` could not interrupt it
+            unsafe { ffi::evm_request_interrupt(handle) };
+        }
+        execute()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Running>> {
+        self.inner.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Unregisters a handle from a token when dropped, so a panicking execution does not leave a
+/// handle that may be freed registered for interrupts
+struct Registration<'a> {
+    token: &'a CancellationToken,
+    handle: *mut EvmHandle,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.token.lock().retain(|running| *running != Running(self.handle));
+    }
+}
+
+/// Run `execute` on `handle`, interrupting it if it is still running at `deadline`
+///
+/// Returns whether the deadline interrupted it. As with cancellation, an interrupt requested as
+/// `execute` returns stays pending, so callers reset the handle when the deadline was hit.
+pub(crate) fn run_until<T>(
+    handle: *mut EvmHandle,
+    deadline: Option<Instant>,
+    execute: impl FnOnce() -> T,
+) -> (T, bool) {
+    let Some(deadline) = deadline else {
+        return (execute(), false);
+    };
+    let running = Running(handle);
+    let (done, finished) = mpsc::channel::<()>();
+    std::thread::scope(|scope| {
+        let watchdog = scope.spawn(move || {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let expired = matches!(finished.recv_timeout(timeout), Err(RecvTimeoutError::Timeout));
+            if expired {
+                running.interrupt();
+            }
+            expired
+        });
+        let result = execute();
+        drop(done);
+        (result, watchdog.join().unwrap_or(false))
+    })
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_unregisters_handle_on_panic() {
+        let token = CancellationToken::new();
+        let handle = std::ptr::NonNull::<EvmHandle>::dangling().as_ptr();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            token.run(handle, || panic!("execution panicked"));
+        }));
+        assert!(result.is_err());
+        assert!(token.lock().is_empty(), "a panicking execution should not stay registered");
+    }
+}
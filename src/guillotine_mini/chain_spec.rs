@@ -0,0 +1,274 @@
+//! Chain specifications for networks other than mainnet
+//!
+//! A [`ChainSpec`] describes a chain by its id, the block or timestamp at which each hardfork
+//! activates and its [`Genesis`]. It builds the REVM context of the genesis block (see
+//! [`ChainSpec::context`] and `EvmBuilder::chain`), and
+//! `GuillotineMiniEvm::advance_block` moves an EVM to a later block, switching hardfork as the
+//! chain's schedule says.
+
+use super::{error::EvmAdapterError, evm::GuillotineMiniEvm, hardfork::Hardfork, validation};
+use revm::{
+    context::{BlockEnv, CfgEnv, Context, TxEnv},
+    database::{CacheDB, EmptyDB},
+    database_interface::Database,
+    primitives::{hardfork::SpecId, Address, Bytes, B256, U256},
+    state::{AccountInfo, Bytecode},
+    MainContext,
+};
+use std::collections::BTreeMap;
+
+/// REVM context of a chain built from a [`ChainSpec`], holding the genesis state
+pub type ChainContext = Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDB>>;
+
+/// When a hardfork activates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ForkCondition {
+    /// At this block number
+    Block(u64),
+    /// At the first block with at least this timestamp
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    /// Whether the fork is active in the block with `number` and `timestamp`
+    pub fn is_active(&self, number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(block) => number >= block,
+            Self::Timestamp(time) => timestamp >= time,
+        }
+    }
+}
+
+/// Account allocated in the genesis block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct GenesisAccount {
+    /// Balance in wei
+    pub balance: U256,
+    /// Nonce
+    pub nonce: u64,
+    /// Runtime bytecode, if a contract
+    pub code: Option<Bytes>,
+    /// Non-zero storage slots
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Parameters of the genesis block
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct Genesis {
+    /// Block timestamp
+    pub timestamp: u64,
+    /// Block gas limit
+    pub gas_limit: u64,
+    /// Base fee per gas (used from London)
+    pub base_fee: u64,
+    /// Difficulty (before the merge)
+    pub difficulty: U256,
+    /// PREVRANDAO value (from the merge)
+    pub prevrandao: B256,
+    /// Fee recipient
+    pub coinbase: Address,
+    /// Excess blob gas (used from Cancun)
+    pub excess_blob_gas: u64,
+    /// Pre-funded accounts and contracts
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+}
+
+impl Default for Genesis {
+    fn default() -> Self {
+        Self {
+            timestamp: 0,
+            gas_limit: 30_000_000,
+            base_fee: 1_000_000_000,
+            difficulty: U256::ZERO,
+            prevrandao: B256::ZERO,
+            coinbase: Address::ZERO,
+            excess_blob_gas: 0,
+            alloc: BTreeMap::new(),
+        }
+    }
+}
+
+/// Chain id, hardfork schedule and genesis of a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ChainSpec {
+    /// Chain id reported by CHAINID and checked against transactions
+    pub chain_id: u64,
+    /// Hardfork activations; the latest active one applies
+    pub forks: Vec<(SpecId, ForkCondition)>,
+    /// Genesis block
+    pub genesis: Genesis,
+}
+
+impl ChainSpec {
+    /// Chain with id `chain_id`, every hardfork up to `spec` active from genesis and a default
+    /// genesis (e.g. a local development chain)
+    pub fn dev(chain_id: u64, spec: SpecId) -> Self {
+        Self { chain_id, forks: vec![(spec, ForkCondition::Block(0))], genesis: Genesis::default() }
+    }
+
+    /// Ethereum mainnet schedule (chain id 1) with an empty genesis allocation
+    ///
+    /// The merge is approximated by its first block, 15537394.
+    pub fn mainnet() -> Self {
+        let forks = vec![
+            (SpecId::HOMESTEAD, ForkCondition::Block(1_150_000)),
+            (SpecId::DAO_FORK, ForkCondition::Block(1_920_000)),
+            (SpecId::TANGERINE, ForkCondition::Block(2_463_000)),
+            (SpecId::SPURIOUS_DRAGON, ForkCondition::Block(2_675_000)),
+            (SpecId::BYZANTIUM, ForkCondition::Block(4_370_000)),
+            (SpecId::PETERSBURG, ForkCondition::Block(7_280_000)),
+            (SpecId::ISTANBUL, ForkCondition::Block(9_069_000)),
+            (SpecId::MUIR_GLACIER, ForkCondition::Block(9_200_000)),
+            (SpecId::BERLIN, ForkCondition::Block(12_244_000)),
+            (SpecId::LONDON, ForkCondition::Block(12_965_000)),
+            (SpecId::ARROW_GLACIER, ForkCondition::Block(13_773_000)),
+            (SpecId::GRAY_GLACIER, ForkCondition::Block(15_050_000)),
+            (SpecId::MERGE, ForkCondition::Block(15_537_394)),
+            (SpecId::SHANGHAI, ForkCondition::Timestamp(1_681_338_455)),
+            (SpecId::CANCUN, ForkCondition::Timestamp(1_710_338_135)),
+            (SpecId::PRAGUE, ForkCondition::Timestamp(1_746_612_311)),
+        ];
+        let genesis = Genesis { gas_limit: 5_000, difficulty: U256::from(0x4_0000_0000u64), ..Genesis::default() };
+        Self { chain_id: 1, forks, genesis }
+    }
+
+    /// Activate `spec` at `condition`
+    pub fn with_fork(mut self, spec: SpecId, condition: ForkCondition) -> Self {
+        self.forks.push((spec, condition));
+        self
+    }
+
+    /// Replace the genesis block
+    pub fn with_genesis(mut self, genesis: Genesis) -> Self {
+        self.genesis = genesis;
+        self
+    }
+
+    /// Allocate `account` at `address` in the genesis block
+    pub fn with_account(mut self, address: Address, account: GenesisAccount) -> Self {
+        self.genesis.alloc.insert(address, account);
+        self
+    }
+
+    /// Hardfork of the block with `number` and `timestamp` (Frontier if none is active)
+    pub fn spec_at(&self, number: u64, timestamp: u64) -> SpecId {
+        self.forks
+            .iter()
+            .filter(|(_, condition)| condition.is_active(number, timestamp))
+            .map(|(spec, _)| *spec)
+            .max_by_key(|spec| *spec as u8)
+            .unwrap_or(SpecId::FRONTIER)
+    }
+
+    /// Hardfork of `block`
+    pub fn spec_for_block(&self, block: &BlockEnv) -> SpecId {
+        self.spec_at(block.number.saturating_to(), block.timestamp.saturating_to())
+    }
+
+    /// Configuration of the block with `number` and `timestamp`
+    pub fn cfg_at(&self, number: u64, timestamp: u64) -> CfgEnv {
+        let mut cfg = CfgEnv::new_with_spec(self.spec_at(number, timestamp));
+        cfg.chain_id = self.chain_id;
+        cfg
+    }
+
+    /// Block environment of the genesis block
+    pub fn genesis_block(&self) -> BlockEnv {
+        let genesis = &self.genesis;
+        let spec = self.spec_at(0, genesis.timestamp);
+        let mut block = BlockEnv {
+            number: U256::ZERO,
+            beneficiary: genesis.coinbase,
+            timestamp: U256::from(genesis.timestamp),
+            gas_limit: genesis.gas_limit,
+            basefee: if spec.is_enabled_in(SpecId::LONDON) { genesis.base_fee } else { 0 },
+            difficulty: genesis.difficulty,
+            prevrandao: spec.is_enabled_in(SpecId::MERGE).then_some(genesis.prevrandao),
+            blob_excess_gas_and_price: None,
+        };
+        if spec.is_enabled_in(SpecId::CANCUN) {
+            block.set_blob_excess_gas_and_price(
+                genesis.excess_blob_gas,
+                validation::blob_base_fee_update_fraction(spec),
+            );
+        }
+        block
+    }
+
+    /// Database holding the genesis allocation
+    pub fn genesis_db(&self) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        for (address, account) in &self.genesis.alloc {
+            let code = account.code.clone().map(Bytecode::new_raw);
+            let info = AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: code.as_ref().map_or(revm::primitives::KECCAK_EMPTY, Bytecode::hash_slow),
+                code,
+            };
+            db.insert_account_info(*address, info);
+            for (slot, value) in &account.storage {
+                db.insert_account_storage(*address, *slot, *value).expect("account was just inserted");
+            }
+        }
+        db
+    }
+
+    /// REVM context of the genesis block: chain id, hardfork, block environment and allocation
+    pub fn context(&self) -> ChainContext {
+        let cfg = self.cfg_at(0, self.genesis.timestamp);
+        Context::mainnet().with_db(self.genesis_db()).with_cfg(cfg).with_block(self.genesis_block())
+    }
+}
+
+impl<DB: Database> GuillotineMiniEvm<Context<BlockEnv, TxEnv, CfgEnv, DB>> {
+    /// Move to `block` of `chain`: set the block environment, chain id and the hardfork active
+    /// at that block
+    ///
+    /// When the hardfork changes, guillotine-mini is recreated for it, discarding state kept by
+    /// `set_persist_state`.
+    pub fn advance_block(&mut self, chain: &ChainSpec, block: BlockEnv) -> Result<(), EvmAdapterError<DB::Error>> {
+        let spec = chain.spec_for_block(&block);
+        self.ctx.cfg.chain_id = chain.chain_id;
+        self.ctx.cfg.spec = spec;
+        self.ctx.block = block;
+        self.switch_hardfork(Hardfork::from_spec(spec)).map_err(EvmAdapterError::ffi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_at_follows_schedule() {
+        let chain = ChainSpec::dev(1337, SpecId::SHANGHAI).with_fork(SpecId::CANCUN, ForkCondition::Timestamp(1_000));
+        assert_eq!(chain.spec_at(0, 0), SpecId::SHANGHAI);
+        assert_eq!(chain.spec_at(5, 999), SpecId::SHANGHAI);
+        assert_eq!(chain.spec_at(6, 1_000), SpecId::CANCUN);
+
+        let mainnet = ChainSpec::mainnet();
+        assert_eq!(mainnet.spec_at(0, 0), SpecId::FRONTIER);
+        assert_eq!(mainnet.spec_at(12_965_000, 0), SpecId::LONDON);
+        assert_eq!(mainnet.spec_at(20_000_000, 1_720_000_000), SpecId::CANCUN);
+    }
+
+    #[test]
+    fn test_genesis_block_matches_fork() {
+        let block = ChainSpec::dev(1337, SpecId::BERLIN).genesis_block();
+        assert_eq!((block.basefee, block.prevrandao), (0, None));
+
+        let block = ChainSpec::dev(1337, SpecId::CANCUN).genesis_block();
+        assert_eq!(block.basefee, 1_000_000_000);
+        assert_eq!(block.prevrandao, Some(B256::ZERO));
+        assert!(block.blob_excess_gas_and_price.is_some());
+    }
+}
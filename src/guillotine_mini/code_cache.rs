@@ -0,0 +1,80 @@
+//! Bytecode analysis cache keyed by code hash
+//!
+//! guillotine-mini analyzes bytecode (jumpdest validation) every time it is set on an instance.
+//! A [`CodeCache`] registers each distinct contract once and lets instances reference it by
+//! code hash, so contracts called by many transactions are only copied and analyzed once.
+//! The cache outlives the per-transaction instances it is attached to.
+
+use super::ffi::{self, CodeCacheHandle, EvmHandle};
+use revm::primitives::B256;
+use std::collections::HashSet;
+
+/// Bytecode registered with guillotine-mini under its code hash
+pub struct CodeCache {
+    handle: *mut CodeCacheHandle,
+    /// Code hashes already registered (mirrors the Zig-side cache)
+    registered: HashSet<B256>,
+}
+
+impl CodeCache {
+    /// Create an empty cache
+    pub fn new() -> Result<Self, &'static str> {
+        let handle = unsafe { ffi::evm_code_cache_create() };
+        if handle.is_null() {
+            return Err("evm_code_cache_create");
+        }
+        Ok(Self { handle, registered: HashSet::new() })
+    }
+
+    /// Register `code` under `hash` unless it is already cached
+    ///
+    /// The caller is responsible for `hash` being the hash of `code`.
+    pub fn register(&mut self, hash: B256, code: &[u8]) -> Result<(), &'static str> {
+        if self.registered.contains(&hash) {
+            return Ok(());
+        }
+        let ok = unsafe { ffi::evm_code_cache_register(self.handle, hash.as_ptr(), code.as_ptr(), code.len()) };
+        if !ok {
+            return Err("evm_code_cache_register");
+        }
+        self.registered.insert(hash);
+        Ok(())
+    }
+
+    /// Whether bytecode with this hash has been registered
+    pub fn contains(&self, hash: &B256) -> bool {
+        self.registered.contains(hash)
+    }
+
+    /// Number of distinct bytecodes in the cache
+    pub fn len(&self) -> usize {
+        self.registered.len()
+    }
+
+    /// Whether the cache holds no bytecode
+    pub fn is_empty(&self) -> bool {
+        self.registered.is_empty()
+    }
+
+    /// Make the cache available to an EVM instance
+    pub(crate) fn attach(&self, evm: *mut EvmHandle) -> Result<(), &'static str> {
+        super::ffi_safe::check_handle(evm)?;
+        let ok = unsafe { ffi::evm_attach_code_cache(evm, self.handle) };
+        if !ok {
+            return Err("evm_attach_code_cache");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CodeCache {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::evm_code_cache_destroy(self.handle);
+        }
+    }
+}
+
+// Safety: the cache is used through its owner only, and the instances it is attached to belong
+// to the same `GuillotineMiniEvm`, so they move between threads together. Not `Sync`.
+unsafe impl Send for CodeCache {}
@@ -15,8 +15,8 @@
 //!
 //! ```rust,ignore
 //! // Handlers stored in these vectors for lifetime management
-//! _opcode_handlers: Vec<Box<OpcodeHandlerFn>>
-//! _precompile_handlers: Vec<Box<PrecompileHandlerFn>>
+//! _opcode_handlers: Vec<ThinHandler<OpcodeHandlerFn>>
+//! _precompile_handlers: Vec<ThinHandler<PrecompileHandlerFn>>
 //! ```
 //!
 //! ## FFI Boundary Contracts
@@ -57,11 +57,15 @@
 //!
 //! ## Configuration Handle Lifecycle
 //!
-//! 1. **Creation**: `evm_config_create()` allocates handle on Zig side
+//! 1. **Creation**: `evm_config_create()` allocates handle on Zig side; without the config ABI
+//!    (see [`ffi::config_api`]) the handle stays null, the builder only records its settings and
+//!    the built config is unsupported (see [`EvmConfig::is_supported`])
 //! 2. **Mutation**: Builder methods call `evm_config_set_*` functions
 //! 3. **Transfer**: `build()` transfers ownership to `EvmConfig`
-//! 4. **Consumption**: `into_raw()` transfers handle to EVM creation
-//! 5. **Destruction**: EVM instance owns handle, freed on EVM destruction
+//! 4. **Sharing**: EVMs hold the `EvmConfig` in an `Arc` and create their instances from it
+//!    with `evm_create_from_config()`, which only reads the handle
+//! 5. **Destruction**: dropping the last `Arc` destroys the handle and the handlers, after
+//!    every instance created from it
 //!
 //! The builder uses null pointer checks to prevent double-free:
 //!
@@ -78,9 +82,13 @@
 //! `EvmConfig` is marked `Send + Sync` because:
 //!
 //! - Handler closures are required to be `Send + Sync + 'static`
-//! - FFI handle is opaque and managed by Zig's thread-safe allocator
+//! - A shared `&EvmConfig` only reaches `evm_create_from_config`, which reads the handle
+//!   without modifying it, so instances can be created from one configuration on several
+//!   threads at once; destroying the handle requires ownership
 //! - No interior mutability after configuration is built
 //!
+//! `EvmConfigBuilder` mutates its handle through the FFI and is neither `Send` nor `Sync`.
+//!
 //! # Examples
 //!
 //! ## Custom Opcode Handler
@@ -89,8 +97,8 @@
 //! use guillotine_rs::guillotine_mini::EvmConfigBuilder;
 //!
 //! let config = EvmConfigBuilder::new()
-//!     .override_opcode(0x01, |frame_ptr, opcode| {
-//!         println!("Custom ADD at frame {:#x}", frame_ptr);
+//!     .override_opcode(0x01, |frame, opcode| {
+//!         println!("Custom ADD at pc {} (depth {})", frame.pc(), frame.depth());
 //!         false // Let default handler process it
 //!     })
 //!     .build();
@@ -100,10 +108,11 @@
 //!
 //! ```rust,ignore
 //! use guillotine_rs::guillotine_mini::{EvmConfigBuilder, PrecompileResult};
+//! use revm::primitives::Address;
 //!
 //! let config = EvmConfigBuilder::new()
 //!     .override_precompile(
-//!         [0u8; 20], // Address 0x0
+//!         Address::ZERO,
 //!         |_addr, input, _gas| {
 //!             Ok(PrecompileResult {
 //!                 output: input.to_vec(),
@@ -114,158 +123,507 @@
 //!     .build();
 //! ```
 
-use super::ffi;
+use super::{
+    error::FfiError,
+    ffi,
+    frame::FrameView,
+    hardfork::Hardfork,
+    precompile_provider::{Precompile, PrecompileProvider},
+    types,
+};
+use revm::{
+    bytecode::opcode::OpCode,
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::{hardfork::SpecId, Address},
+};
 use std::ffi::c_void;
 
 /// Result type for precompile execution
 #[derive(Debug, Clone)]
 pub struct PrecompileResult {
     pub output: Vec<u8>,
+    /// Gas consumed; more than the gas limit is treated as [`PrecompileError::OutOfGas`]
     pub gas_used: u64,
 }
 
 /// Error type for precompile execution
+///
+/// Like a failing built-in precompile, every error consumes all gas passed to the call.
+/// `OutOfGas` halts the calling frame as running out of gas (`HaltReason::OutOfGas` at the
+/// top level); the others halt it as a precompile failure (`HaltReason::PrecompileError`).
 #[derive(Debug, Clone)]
 pub enum PrecompileError {
+    /// The gas limit does not cover the work
     OutOfGas,
+    /// The input is malformed
     InvalidInput,
+    /// The precompile failed for another reason
     ExecutionFailed(String),
 }
 
+/// Error configuring guillotine-mini through a `try_*` method of [`EvmConfigBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A guillotine-mini config call failed (e.g. creating the config or registering a handler)
+    Ffi(FfiError),
+    /// No built-in precompile of the configured hardfork lives at the address to disable
+    UnknownPrecompile(Address),
+    /// guillotine-mini cannot toggle the EIP
+    UnsupportedEip(u16),
+    /// The opcode to define is already assigned; replace it with `override_opcode` instead
+    AssignedOpcode(u8),
+    /// A configuration document could not be parsed
+    Parse(String),
+    /// No hardfork has this name
+    UnknownHardfork(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ffi(e) => write!(f, "ffi call failed: {}", e),
+            Self::UnknownPrecompile(address) => write!(f, "no built-in precompile at {}", address),
+            Self::UnsupportedEip(eip) => write!(f, "EIP {} cannot be toggled", eip),
+            Self::AssignedOpcode(opcode) => write!(f, "opcode 0x{:02x} is already assigned", opcode),
+            Self::Parse(message) => write!(f, "invalid configuration: {}", message),
+            Self::UnknownHardfork(name) => write!(f, "unknown hardfork {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Serializable part of a configuration: every setting except opcode and precompile handlers
+///
+/// Recorded by [`EvmConfigBuilder`] as it is configured and applied with
+/// [`EvmConfigBuilder::from_settings`]; unset fields keep guillotine-mini's defaults. With the
+/// `serde` feature it (de)serializes with snake_case field names, so a deployment can ship it as
+/// a file:
+///
+/// ```toml
+/// hardfork = "Prague"
+/// max_call_depth = 512
+/// disabled_precompiles = ["0x000000000000000000000000000000000000000a"]
+/// disabled_eips = [1153]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ConfigSettings {
+    /// Hardfork name (e.g. "Cancun")
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub hardfork: Option<String>,
+    /// Maximum stack size
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub stack_size: Option<u16>,
+    /// Maximum bytecode size
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_bytecode_size: Option<u32>,
+    /// Maximum initcode size
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_initcode_size: Option<u32>,
+    /// Block gas limit
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub block_gas_limit: Option<u64>,
+    /// Initial memory capacity
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub memory_initial_capacity: Option<usize>,
+    /// Memory limit
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub memory_limit: Option<u64>,
+    /// Maximum call depth
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_call_depth: Option<u16>,
+    /// Loop quota (0 = disabled)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub loop_quota: Option<u32>,
+    /// System contract features
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub system_contracts: Option<SystemContracts>,
+    /// Built-in precompiles kept, if the set was replaced
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub precompiles: Option<Vec<Address>>,
+    /// Built-in precompiles removed (after replacing the set)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub disabled_precompiles: Vec<Address>,
+    /// EIPs enabled regardless of the hardfork
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub enabled_eips: Vec<u16>,
+    /// EIPs disabled regardless of the hardfork
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub disabled_eips: Vec<u16>,
+}
+
+impl ConfigSettings {
+    /// Ethereum mainnet rules for `spec`: its hardfork, the standard limits and the system
+    /// contracts that hardfork introduced
+    pub fn mainnet(spec: SpecId) -> Self {
+        Self {
+            hardfork: Some(Hardfork::from_spec(spec).name().to_string()),
+            system_contracts: Some(SystemContracts {
+                beacon_roots: spec.is_enabled_in(SpecId::CANCUN),
+                block_hashes: spec.is_enabled_in(SpecId::PRAGUE),
+                deposits: spec.is_enabled_in(SpecId::PRAGUE),
+                withdrawals: spec.is_enabled_in(SpecId::PRAGUE),
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Latest hardfork with size, gas, memory and loop limits lifted and system contracts off,
+    /// for tests and tooling that should not trip over mainnet limits
+    pub fn testing() -> Self {
+        Self {
+            hardfork: Some(Hardfork::Prague.name().to_string()),
+            max_bytecode_size: Some(u32::MAX),
+            max_initcode_size: Some(u32::MAX),
+            block_gas_limit: Some(u64::MAX),
+            memory_limit: Some(u64::MAX),
+            loop_quota: Some(0),
+            system_contracts: Some(SystemContracts {
+                beacon_roots: false,
+                block_hashes: false,
+                deposits: false,
+                withdrawals: false,
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Rollup defaults shared by most EVM-equivalent L2s: Cancun rules with the beacon roots
+    /// contract, a higher block gas limit and no L1 deposit or withdrawal request contracts
+    pub fn l2_generic() -> Self {
+        Self {
+            hardfork: Some(Hardfork::Cancun.name().to_string()),
+            block_gas_limit: Some(60_000_000),
+            system_contracts: Some(SystemContracts {
+                beacon_roots: true,
+                block_hashes: false,
+                deposits: false,
+                withdrawals: false,
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Pretty-printed JSON document
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("settings serialize to JSON")
+    }
+
+    /// TOML document
+    ///
+    /// Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("settings serialize to TOML")
+    }
+}
+
+/// System contract features (see [`EvmConfigBuilder::system_contracts`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct SystemContracts {
+    /// EIP-4788 beacon block roots
+    pub beacon_roots: bool,
+    /// EIP-2935 historical block hashes
+    pub block_hashes: bool,
+    /// EIP-6110 deposits
+    pub deposits: bool,
+    /// EIP-7002 withdrawals
+    pub withdrawals: bool,
+}
+
+/// Engine parameters that can change between blocks, updated in place on a running EVM with
+/// `GuillotineMiniEvm::update_config`
+///
+/// Unlike the rest of an [`EvmConfig`], these apply to an existing guillotine-mini instance
+/// without recreating it, so synced state and the attached code cache are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Block gas limit guillotine-mini enforces (default: 30000000)
+    pub block_gas_limit: u64,
+    /// Accept transactions whose maximum fee is below the block base fee, like REVM's
+    /// `disable_base_fee`; the fee they pay is not reduced by the base fee
+    pub disable_base_fee: bool,
+    /// System contract features
+    pub system_contracts: SystemContracts,
+}
+
+impl RuntimeConfig {
+    /// Parameters an instance starts with: those of `settings` where set, else mainnet's for
+    /// `hardfork`
+    pub fn from_settings(settings: &ConfigSettings, hardfork: Hardfork) -> Self {
+        let mainnet = ConfigSettings::mainnet(hardfork.spec());
+        Self {
+            block_gas_limit: settings.block_gas_limit.unwrap_or(30_000_000),
+            disable_base_fee: false,
+            system_contracts: settings
+                .system_contracts
+                .or(mainnet.system_contracts)
+                .expect("mainnet settings name system contracts"),
+        }
+    }
+
+    /// Apply the engine parameters to a guillotine-mini instance through the config ABI
+    pub(crate) fn apply(&self, api: &ffi::ConfigApi, handle: *mut ffi::EvmHandle) -> Result<(), &'static str> {
+        let SystemContracts { beacon_roots, block_hashes, deposits, withdrawals } = self.system_contracts;
+        if !unsafe { (api.evm_set_block_gas_limit)(handle, self.block_gas_limit) } {
+            return Err("evm_set_block_gas_limit");
+        }
+        if !unsafe { (api.evm_set_system_contracts)(handle, beacon_roots, block_hashes, deposits, withdrawals) } {
+            return Err("evm_set_system_contracts");
+        }
+        Ok(())
+    }
+}
+
 /// Type-safe configuration builder for guillotine-mini EVM
 pub struct EvmConfigBuilder {
     handle: *mut ffi::EvmConfigHandle,
+    settings: ConfigSettings,
+    // Addresses of the precompiles added with `override_precompile`
+    custom_precompiles: Vec<Address>,
     // Keep closures alive for their lifetime
-    _opcode_handlers: Vec<Box<OpcodeHandlerFn>>,
-    _precompile_handlers: Vec<Box<PrecompileHandlerFn>>,
+    _opcode_handlers: Vec<ThinHandler<OpcodeHandlerFn>>,
+    _precompile_handlers: Vec<ThinHandler<PrecompileHandlerFn>>,
 }
 
-// Boxed trait objects for opcode and precompile handlers
-type OpcodeHandlerFn = dyn Fn(usize, u8) -> bool + Send + Sync + 'static;
-type PrecompileHandlerFn =
-    dyn Fn(&[u8], &[u8], u64) -> Result<PrecompileResult, PrecompileError> + Send + Sync + 'static;
+// Trait objects for opcode and precompile handlers
+type OpcodeHandlerFn = dyn Fn(&mut FrameView<'_>, u8) -> bool + Send + Sync + 'static;
+type PrecompileHandlerFn = super::precompile_provider::PrecompileHandler;
+
+/// Handler boxed twice, so the context pointer handed to guillotine-mini is a thin pointer
+struct ThinHandler<T: ?Sized> {
+    _handler: Box<Box<T>>,
+}
 
 impl EvmConfigBuilder {
     /// Create a new configuration builder with default values
     ///
     /// # Panics
-    /// Panics if the FFI call to create the config handle fails (returns null).
+    /// Panics if the FFI call to create the config handle fails (returns null); see
+    /// [`try_new`](Self::try_new).
     pub fn new() -> Self {
-        let handle = unsafe { ffi::evm_config_create() };
-        assert!(!handle.is_null(), "Failed to create EVM config");
+        Self::try_new().unwrap_or_else(|e| panic!("Failed to create EVM config: {}", e))
+    }
 
-        Self {
+    /// Fallible [`new`](Self::new)
+    ///
+    /// If the linked libguillotine_mini does not export the config ABI (see
+    /// [`ffi::config_api`]), the builder only records its settings and the built config is
+    /// rejected by `GuillotineMiniEvm::with_config` with `EvmAdapterError::Unsupported("config")`.
+    pub fn try_new() -> Result<Self, ConfigError> {
+        let handle = match ffi::config_api() {
+            Some(_) => {
+                let handle = unsafe { ffi::evm_config_create() };
+                if handle.is_null() {
+                    return Err(ConfigError::Ffi(FfiError::last("evm_config_create")));
+                }
+                handle
+            }
+            None => std::ptr::null_mut(),
+        };
+
+        Ok(Self {
             handle,
+            settings: ConfigSettings::default(),
+            custom_precompiles: Vec::new(),
             _opcode_handlers: Vec::new(),
             _precompile_handlers: Vec::new(),
-        }
+        })
     }
 
-    /// Set the hardfork for EVM execution
+    /// Set the hardfork for EVM execution to the one guillotine-mini runs `spec` under
+    ///
+    /// Specs map to hardforks as for `GuillotineMiniEvm::new` (see `Hardfork::from_spec`).
     ///
     /// # Example
     /// ```ignore
     /// let config = EvmConfigBuilder::new()
-    ///     .hardfork("Cancun")
+    ///     .hardfork(SpecId::CANCUN)
     ///     .build();
     /// ```
-    pub fn hardfork(self, name: &str) -> Self {
-        unsafe {
-            ffi::evm_config_set_hardfork(self.handle, name.as_ptr(), name.len());
+    pub fn hardfork(mut self, spec: SpecId) -> Self {
+        let name = Hardfork::from_spec(spec).name();
+        self.settings.hardfork = Some(name.to_string());
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_hardfork(self.handle, name.as_ptr(), name.len());
+            }
         }
         self
     }
 
+    /// Set the hardfork by guillotine-mini's name for it (e.g. "Cancun"), as in configuration
+    /// files
+    ///
+    /// # Panics
+    /// Panics if `name` names no hardfork; see [`try_hardfork_name`](Self::try_hardfork_name).
+    pub fn hardfork_name(self, name: &str) -> Self {
+        self.try_hardfork_name(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`hardfork_name`](Self::hardfork_name)
+    pub fn try_hardfork_name(self, name: &str) -> Result<Self, ConfigError> {
+        let hardfork: Hardfork = name.parse().map_err(|_| ConfigError::UnknownHardfork(name.to_string()))?;
+        Ok(self.hardfork(hardfork.spec()))
+    }
+
     /// Set maximum stack size (default: 1024)
-    pub fn stack_size(self, size: u16) -> Self {
-        unsafe {
-            ffi::evm_config_set_stack_size(self.handle, size);
+    pub fn stack_size(mut self, size: u16) -> Self {
+        self.settings.stack_size = Some(size);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_stack_size(self.handle, size);
+            }
         }
         self
     }
 
     /// Set maximum bytecode size (default: 24576)
-    pub fn max_bytecode_size(self, size: u32) -> Self {
-        unsafe {
-            ffi::evm_config_set_max_bytecode_size(self.handle, size);
+    pub fn max_bytecode_size(mut self, size: u32) -> Self {
+        self.settings.max_bytecode_size = Some(size);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_max_bytecode_size(self.handle, size);
+            }
         }
         self
     }
 
     /// Set maximum initcode size (default: 49152)
-    pub fn max_initcode_size(self, size: u32) -> Self {
-        unsafe {
-            ffi::evm_config_set_max_initcode_size(self.handle, size);
+    pub fn max_initcode_size(mut self, size: u32) -> Self {
+        self.settings.max_initcode_size = Some(size);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_max_initcode_size(self.handle, size);
+            }
         }
         self
     }
 
+    /// Lift the contract code size limit (EIP-170) and the initcode size limit (EIP-3860)
+    ///
+    /// The counterpart of REVM's `limit_contract_code_size = Some(usize::MAX)`, for development
+    /// environments deploying large test or instrumented contracts.
+    pub fn unlimited_code_size(self) -> Self {
+        self.max_bytecode_size(u32::MAX).max_initcode_size(u32::MAX)
+    }
+
     /// Set block gas limit (default: 30000000)
-    pub fn block_gas_limit(self, limit: u64) -> Self {
-        unsafe {
-            ffi::evm_config_set_block_gas_limit(self.handle, limit);
+    pub fn block_gas_limit(mut self, limit: u64) -> Self {
+        self.settings.block_gas_limit = Some(limit);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_block_gas_limit(self.handle, limit);
+            }
         }
         self
     }
 
     /// Set memory initial capacity (default: 4096)
-    pub fn memory_initial_capacity(self, capacity: usize) -> Self {
-        unsafe {
-            ffi::evm_config_set_memory_initial_capacity(self.handle, capacity);
+    pub fn memory_initial_capacity(mut self, capacity: usize) -> Self {
+        self.settings.memory_initial_capacity = Some(capacity);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_memory_initial_capacity(self.handle, capacity);
+            }
         }
         self
     }
 
     /// Set memory limit (default: 0xFFFFFF)
-    pub fn memory_limit(self, limit: u64) -> Self {
-        unsafe {
-            ffi::evm_config_set_memory_limit(self.handle, limit);
+    pub fn memory_limit(mut self, limit: u64) -> Self {
+        self.settings.memory_limit = Some(limit);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_memory_limit(self.handle, limit);
+            }
         }
         self
     }
 
     /// Set maximum call depth (default: 1024)
-    pub fn max_call_depth(self, depth: u16) -> Self {
-        unsafe {
-            ffi::evm_config_set_max_call_depth(self.handle, depth);
+    pub fn max_call_depth(mut self, depth: u16) -> Self {
+        self.settings.max_call_depth = Some(depth);
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_max_call_depth(self.handle, depth);
+            }
         }
         self
     }
 
     /// Set loop quota for safety counters
     /// None = disabled, Some(n) = max iterations before panic
-    pub fn loop_quota(self, quota: Option<u32>) -> Self {
-        unsafe {
-            ffi::evm_config_set_loop_quota(self.handle, quota.unwrap_or(0));
+    pub fn loop_quota(mut self, quota: Option<u32>) -> Self {
+        self.settings.loop_quota = Some(quota.unwrap_or(0));
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_set_loop_quota(self.handle, quota.unwrap_or(0));
+            }
         }
         self
     }
 
     /// Enable or disable system contract features
     pub fn system_contracts(
-        self,
+        mut self,
         beacon_roots: bool,
         block_hashes: bool,
         deposits: bool,
         withdrawals: bool,
     ) -> Self {
-        unsafe {
-            ffi::evm_config_enable_system_contracts(
-                self.handle,
-                beacon_roots,
-                block_hashes,
-                deposits,
-                withdrawals,
-            );
+        self.settings.system_contracts = Some(SystemContracts { beacon_roots, block_hashes, deposits, withdrawals });
+        if self.is_supported() {
+            unsafe {
+                ffi::evm_config_enable_system_contracts(self.handle, beacon_roots, block_hashes, deposits, withdrawals);
+            }
         }
         self
     }
 
+    /// Enable or disable an individual EIP regardless of the configured hardfork
+    ///
+    /// For protocol research: e.g. `eip(1153, false)` removes transient storage from Cancun and
+    /// `eip(6780, false)` restores the pre-Cancun SELFDESTRUCT. Only the engine is affected;
+    /// transaction validation and fees in the adapter still follow the REVM spec. Toggles are
+    /// applied after the hardfork, whatever the call order.
+    ///
+    /// # Panics
+    /// Panics if guillotine-mini cannot toggle `eip`; see [`try_eip`](Self::try_eip).
+    pub fn eip(self, eip: u16, enabled: bool) -> Self {
+        self.try_eip(eip, enabled).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`eip`](Self::eip)
+    pub fn try_eip(mut self, eip: u16, enabled: bool) -> Result<Self, ConfigError> {
+        if self.is_supported() && !unsafe { ffi::evm_config_set_eip(self.handle, eip, enabled) } {
+            return Err(ConfigError::UnsupportedEip(eip));
+        }
+        self.settings.enabled_eips.retain(|&toggled| toggled != eip);
+        self.settings.disabled_eips.retain(|&toggled| toggled != eip);
+        match enabled {
+            true => self.settings.enabled_eips.push(eip),
+            false => self.settings.disabled_eips.push(eip),
+        }
+        Ok(self)
+    }
+
     /// Override a specific opcode with a custom handler
     ///
     /// # Arguments
     /// * `opcode` - The opcode byte to override (e.g., 0x01 for ADD)
-    /// * `handler` - Closure that receives (frame_ptr, opcode) and returns true if handled
+    /// * `handler` - Closure that receives (frame, opcode) and returns true if handled; the
+    ///   [`FrameView`] reads the executing frame's context, stack and memory and updates them.
+    ///   A handled opcode is not executed by guillotine-mini: the handler pops its operands,
+    ///   pushes its results and charges its gas. Returning false leaves the frame to the
+    ///   built-in implementation (an invalid opcode halt for undefined opcodes).
     ///
     /// # Safety
     /// The handler closure is boxed and its pointer is passed to the FFI layer. The closure
@@ -273,49 +631,100 @@ impl EvmConfigBuilder {
     /// the `_opcode_handlers` vector.
     ///
     /// # Panics
-    /// Panics if the FFI call to add the opcode override fails.
+    /// Panics if the FFI call to add the opcode override fails; see
+    /// [`try_override_opcode`](Self::try_override_opcode).
     ///
     /// # Example
     /// ```ignore
+    /// // 0x0c doubles the top stack item for 5 gas
     /// let config = EvmConfigBuilder::new()
-    ///     .override_opcode(0x01, |_frame_ptr, _opcode| {
-    ///         println!("Custom ADD handler");
-    ///         true // Handled
+    ///     .override_opcode(0x0c, |frame, _opcode| {
+    ///         if frame.stack_len() < 1 || frame.consume_gas(5).is_err() {
+    ///             return false;
+    ///         }
+    ///         let value = frame.pop().unwrap();
+    ///         frame.push(value * U256::from(2)).is_ok()
     ///     })
     ///     .build();
     /// ```
-    pub fn override_opcode<F>(mut self, opcode: u8, handler: F) -> Self
+    pub fn override_opcode<F>(self, opcode: u8, handler: F) -> Self
     where
-        F: Fn(usize, u8) -> bool + Send + Sync + 'static,
+        F: Fn(&mut FrameView<'_>, u8) -> bool + Send + Sync + 'static,
+    {
+        self.try_override_opcode(opcode, handler).unwrap_or_else(|e| panic!("Failed to add opcode override: {}", e))
+    }
+
+    /// Fallible [`override_opcode`](Self::override_opcode)
+    pub fn try_override_opcode<F>(mut self, opcode: u8, handler: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&mut FrameView<'_>, u8) -> bool + Send + Sync + 'static,
     {
         // Box the closure once for the trait object
-        let boxed: Box<OpcodeHandlerFn> = Box::new(handler);
+        let boxed: Box<Box<OpcodeHandlerFn>> = Box::new(Box::new(handler));
         let ctx_ptr = Box::into_raw(boxed) as *mut c_void;
 
-        let success = unsafe {
-            ffi::evm_config_add_opcode_override(self.handle, opcode, opcode_trampoline, ctx_ptr)
-        };
+        let success = !self.is_supported()
+            || unsafe { ffi::evm_config_add_opcode_override(self.handle, opcode, opcode_trampoline, ctx_ptr) };
 
         if success {
             // Keep the box alive by storing it
-            let boxed = unsafe { Box::from_raw(ctx_ptr as *mut OpcodeHandlerFn) };
-            self._opcode_handlers.push(boxed);
+            let boxed = unsafe { Box::from_raw(ctx_ptr as *mut Box<OpcodeHandlerFn>) };
+            self._opcode_handlers.push(ThinHandler { _handler: boxed });
         } else {
+            let error = FfiError::last("evm_config_add_opcode_override");
             // Clean up on failure
             unsafe {
-                let _boxed = Box::from_raw(ctx_ptr as *mut OpcodeHandlerFn);
+                let _boxed = Box::from_raw(ctx_ptr as *mut Box<OpcodeHandlerFn>);
             }
-            panic!("Failed to add opcode override");
+            return Err(ConfigError::Ffi(error));
         }
 
-        self
+        Ok(self)
+    }
+
+    /// Define a new opcode at an unassigned byte, charging `gas` before `handler` runs
+    ///
+    /// For prototyping protocol changes: the byte must be unassigned in every hardfork (e.g.
+    /// 0x0c-0x0f, 0x21-0x2f, 0xb0-0xef), so existing bytecode keeps its meaning. The static
+    /// `gas` is charged first; the handler charges any dynamic cost itself with
+    /// [`FrameView::consume_gas`] and returns false to halt the frame, as the byte did before.
+    /// An opcode without enough gas for `gas` halts the same way.
+    ///
+    /// # Panics
+    /// Panics if `opcode` is assigned or the handler cannot be registered; see
+    /// [`try_define_opcode`](Self::try_define_opcode).
+    ///
+    /// # Example
+    /// ```ignore
+    /// // 0x21 pushes the call depth for 2 gas
+    /// let config = EvmConfigBuilder::new()
+    ///     .define_opcode(0x21, 2, |frame| frame.push(U256::from(frame.depth())).is_ok())
+    ///     .build();
+    /// ```
+    pub fn define_opcode<F>(self, opcode: u8, gas: u64, handler: F) -> Self
+    where
+        F: Fn(&mut FrameView<'_>) -> bool + Send + Sync + 'static,
+    {
+        self.try_define_opcode(opcode, gas, handler).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`define_opcode`](Self::define_opcode)
+    pub fn try_define_opcode<F>(self, opcode: u8, gas: u64, handler: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&mut FrameView<'_>) -> bool + Send + Sync + 'static,
+    {
+        if OpCode::new(opcode).is_some() {
+            return Err(ConfigError::AssignedOpcode(opcode));
+        }
+        self.try_override_opcode(opcode, move |frame, _| frame.consume_gas(gas).is_ok() && handler(frame))
     }
 
     /// Override or add a custom precompile at a specific address
     ///
     /// # Arguments
-    /// * `address` - 20-byte Ethereum address
-    /// * `handler` - Closure that receives (address, input, gas_limit) and returns Result
+    /// * `address` - Address the precompile is called at
+    /// * `handler` - Closure that receives (address, input, gas_limit) and returns the output and
+    ///   gas used, or a [`PrecompileError`]
     ///
     /// # Safety
     /// The handler closure is boxed and its pointer is passed to the FFI layer. The closure
@@ -323,7 +732,8 @@ impl EvmConfigBuilder {
     /// the `_precompile_handlers` vector.
     ///
     /// # Panics
-    /// Panics if the FFI call to add the precompile override fails.
+    /// Panics if the FFI call to add the precompile override fails; see
+    /// [`try_override_precompile`](Self::try_override_precompile).
     ///
     /// # Example
     /// ```ignore
@@ -341,39 +751,235 @@ impl EvmConfigBuilder {
     ///     )
     ///     .build();
     /// ```
-    pub fn override_precompile<F>(mut self, address: [u8; 20], handler: F) -> Self
+    pub fn override_precompile<F>(self, address: Address, handler: F) -> Self
+    where
+        F: Fn(Address, &[u8], u64) -> Result<PrecompileResult, PrecompileError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.try_override_precompile(address, handler)
+            .unwrap_or_else(|e| panic!("Failed to add precompile override: {}", e))
+    }
+
+    /// Fallible [`override_precompile`](Self::override_precompile)
+    pub fn try_override_precompile<F>(mut self, address: Address, handler: F) -> Result<Self, ConfigError>
     where
-        F: Fn(&[u8], &[u8], u64) -> Result<PrecompileResult, PrecompileError>
+        F: Fn(Address, &[u8], u64) -> Result<PrecompileResult, PrecompileError>
             + Send
             + Sync
             + 'static,
     {
         // Box the closure once for the trait object
-        let boxed: Box<PrecompileHandlerFn> = Box::new(handler);
+        let boxed: Box<Box<PrecompileHandlerFn>> = Box::new(Box::new(handler));
         let ctx_ptr = Box::into_raw(boxed) as *mut c_void;
 
-        let success = unsafe {
-            ffi::evm_config_add_precompile_override(
-                self.handle,
-                address.as_ptr(),
-                precompile_trampoline,
-                ctx_ptr,
-            )
-        };
+        let success = !self.is_supported()
+            || unsafe {
+                ffi::evm_config_add_precompile_override(
+                    self.handle,
+                    types::address_to_bytes(&address).as_ptr(),
+                    precompile_trampoline,
+                    ctx_ptr,
+                )
+            };
 
         if success {
             // Keep the box alive
-            let boxed = unsafe { Box::from_raw(ctx_ptr as *mut PrecompileHandlerFn) };
-            self._precompile_handlers.push(boxed);
+            let boxed = unsafe { Box::from_raw(ctx_ptr as *mut Box<PrecompileHandlerFn>) };
+            self._precompile_handlers.push(ThinHandler { _handler: boxed });
+            self.custom_precompiles.push(address);
         } else {
+            let error = FfiError::last("evm_config_add_precompile_override");
             // Clean up on failure
             unsafe {
-                let _boxed = Box::from_raw(ctx_ptr as *mut PrecompileHandlerFn);
+                let _boxed = Box::from_raw(ctx_ptr as *mut Box<PrecompileHandlerFn>);
             }
-            panic!("Failed to add precompile override");
+            return Err(ConfigError::Ffi(error));
         }
 
-        self
+        Ok(self)
+    }
+
+    /// Remove the built-in precompile at `address`
+    ///
+    /// Calls to the address then execute whatever code the account holds, as for any other
+    /// account. Precompiles added with [`override_precompile`](Self::override_precompile) are
+    /// not affected.
+    ///
+    /// # Panics
+    /// Panics if no built-in precompile of the configured hardfork lives at `address`; see
+    /// [`try_disable_precompile`](Self::try_disable_precompile).
+    pub fn disable_precompile(self, address: Address) -> Self {
+        self.try_disable_precompile(address).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`disable_precompile`](Self::disable_precompile)
+    pub fn try_disable_precompile(mut self, address: Address) -> Result<Self, ConfigError> {
+        let bytes = types::address_to_bytes(&address);
+        if self.is_supported() && !unsafe { ffi::evm_config_disable_precompile(self.handle, bytes.as_ptr()) } {
+            return Err(ConfigError::UnknownPrecompile(address));
+        }
+        self.settings.disabled_precompiles.push(address);
+        Ok(self)
+    }
+
+    /// Replace the set of built-in precompiles with those at `addresses`
+    ///
+    /// Every other built-in precompile is removed; an empty set removes them all (e.g. for an
+    /// environment without BN254 or KZG support). Precompiles added with
+    /// [`override_precompile`](Self::override_precompile) are kept.
+    ///
+    /// # Panics
+    /// Panics if one of the addresses holds no built-in precompile of the configured hardfork;
+    /// see [`try_precompiles`](Self::try_precompiles).
+    ///
+    /// # Example
+    /// ```ignore
+    /// // ECRECOVER, SHA256, RIPEMD160 and IDENTITY only
+    /// let config = EvmConfigBuilder::new()
+    ///     .hardfork(SpecId::CANCUN)
+    ///     .precompiles((1..=4).map(Address::with_last_byte))
+    ///     .build();
+    /// ```
+    pub fn precompiles(self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.try_precompiles(addresses).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`precompiles`](Self::precompiles)
+    ///
+    /// Fails with [`ConfigError::Ffi`], carrying guillotine-mini's diagnostic, if an address
+    /// holds no built-in precompile.
+    pub fn try_precompiles(mut self, addresses: impl IntoIterator<Item = Address>) -> Result<Self, ConfigError> {
+        let addresses: Vec<Address> = addresses.into_iter().collect();
+        let packed: Vec<u8> = addresses.iter().flat_map(types::address_to_bytes).collect();
+        if self.is_supported()
+            && !unsafe { ffi::evm_config_set_precompiles(self.handle, packed.as_ptr(), addresses.len()) }
+        {
+            return Err(ConfigError::Ffi(FfiError::last("evm_config_set_precompiles")));
+        }
+        self.settings.precompiles = Some(addresses);
+        self.settings.disabled_precompiles.clear();
+        Ok(self)
+    }
+
+    /// Set the hardfork and install the precompile set `provider` supplies for it
+    ///
+    /// The provider's built-in entries replace the built-in set as with
+    /// [`precompiles`](Self::precompiles), and its custom entries are registered as with
+    /// [`override_precompile`](Self::override_precompile). Use `GuillotinePrecompiles` as a
+    /// starting point to keep guillotine-mini's set and add to it.
+    ///
+    /// # Panics
+    /// Panics if a built-in entry names no built-in precompile of `hardfork` or a handler
+    /// cannot be registered; see [`try_precompile_provider`](Self::try_precompile_provider).
+    pub fn precompile_provider(self, hardfork: Hardfork, provider: &impl PrecompileProvider) -> Self {
+        self.try_precompile_provider(hardfork, provider).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`precompile_provider`](Self::precompile_provider)
+    pub fn try_precompile_provider(
+        self,
+        hardfork: Hardfork,
+        provider: &impl PrecompileProvider,
+    ) -> Result<Self, ConfigError> {
+        let set = provider.precompiles(hardfork);
+        let builtin = set.iter().filter(|(_, precompile)| matches!(precompile, Precompile::Builtin));
+        let mut builder = self.hardfork(hardfork.spec()).try_precompiles(builtin.map(|(address, _)| *address))?;
+        for (address, precompile) in set {
+            if let Precompile::Custom(handler) = precompile {
+                builder = builder.try_override_precompile(address, move |address, input, gas_limit| {
+                    handler(address, input, gas_limit)
+                })?;
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Settings applied so far, without the opcode and precompile handlers
+    pub fn settings(&self) -> &ConfigSettings {
+        &self.settings
+    }
+
+    /// Builder with `settings` applied, to add handlers to
+    ///
+    /// # Panics
+    /// Panics like the setters do on precompiles or EIPs guillotine-mini cannot change; see
+    /// [`try_from_settings`](Self::try_from_settings).
+    pub fn from_settings(settings: &ConfigSettings) -> Self {
+        Self::try_from_settings(settings).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible [`from_settings`](Self::from_settings)
+    pub fn try_from_settings(settings: &ConfigSettings) -> Result<Self, ConfigError> {
+        let mut builder = Self::try_new()?;
+        if let Some(name) = &settings.hardfork {
+            builder = builder.try_hardfork_name(name)?;
+        }
+        if let Some(size) = settings.stack_size {
+            builder = builder.stack_size(size);
+        }
+        if let Some(size) = settings.max_bytecode_size {
+            builder = builder.max_bytecode_size(size);
+        }
+        if let Some(size) = settings.max_initcode_size {
+            builder = builder.max_initcode_size(size);
+        }
+        if let Some(limit) = settings.block_gas_limit {
+            builder = builder.block_gas_limit(limit);
+        }
+        if let Some(capacity) = settings.memory_initial_capacity {
+            builder = builder.memory_initial_capacity(capacity);
+        }
+        if let Some(limit) = settings.memory_limit {
+            builder = builder.memory_limit(limit);
+        }
+        if let Some(depth) = settings.max_call_depth {
+            builder = builder.max_call_depth(depth);
+        }
+        if let Some(quota) = settings.loop_quota {
+            builder = builder.loop_quota((quota != 0).then_some(quota));
+        }
+        if let Some(system) = settings.system_contracts {
+            let SystemContracts { beacon_roots, block_hashes, deposits, withdrawals } = system;
+            builder = builder.system_contracts(beacon_roots, block_hashes, deposits, withdrawals);
+        }
+        if let Some(addresses) = &settings.precompiles {
+            builder = builder.try_precompiles(addresses.iter().copied())?;
+        }
+        for address in &settings.disabled_precompiles {
+            builder = builder.try_disable_precompile(*address)?;
+        }
+        for eip in &settings.enabled_eips {
+            builder = builder.try_eip(*eip, true)?;
+        }
+        for eip in &settings.disabled_eips {
+            builder = builder.try_eip(*eip, false)?;
+        }
+        Ok(builder)
+    }
+
+    /// Builder with the settings of a JSON document applied (see [`ConfigSettings`])
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let settings = serde_json::from_str(json).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        Self::try_from_settings(&settings)
+    }
+
+    /// Builder with the settings of a TOML document applied (see [`ConfigSettings`])
+    ///
+    /// Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        let settings = toml::from_str(toml).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        Self::try_from_settings(&settings)
+    }
+
+    /// Whether settings reach libguillotine_mini, i.e. the config ABI is available
+    fn is_supported(&self) -> bool {
+        !self.handle.is_null()
     }
 
     /// Build the final configuration and consume the builder
@@ -384,6 +990,8 @@ impl EvmConfigBuilder {
 
         EvmConfig {
             handle,
+            settings: std::mem::take(&mut self.settings),
+            custom_precompiles: std::mem::take(&mut self.custom_precompiles),
             _opcode_handlers: std::mem::take(&mut self._opcode_handlers),
             _precompile_handlers: std::mem::take(&mut self._precompile_handlers),
         }
@@ -406,21 +1014,71 @@ impl Default for EvmConfigBuilder {
     }
 }
 
-/// Built EVM configuration (consumed by EVM creation)
+/// Built EVM configuration
+///
+/// Shared by every EVM created from it: pass it in an `Arc` to
+/// `GuillotineMiniEvm::with_config`, `EvmBuilder::config` or `EvmPool::with_config` to create
+/// many instances without rebuilding the configuration or registering its handlers again.
 pub struct EvmConfig {
     pub(crate) handle: *mut ffi::EvmConfigHandle,
+    settings: ConfigSettings,
+    custom_precompiles: Vec<Address>,
     // Keep handlers alive
-    _opcode_handlers: Vec<Box<OpcodeHandlerFn>>,
-    _precompile_handlers: Vec<Box<PrecompileHandlerFn>>,
+    _opcode_handlers: Vec<ThinHandler<OpcodeHandlerFn>>,
+    _precompile_handlers: Vec<ThinHandler<PrecompileHandlerFn>>,
 }
 
 impl EvmConfig {
-    /// Consume the config and return the raw handle (ownership transferred)
-    pub(crate) fn into_raw(mut self) -> *mut ffi::EvmConfigHandle {
-        let handle = self.handle;
-        self.handle = std::ptr::null_mut(); // Prevent drop
-        std::mem::forget(self); // Prevent handler drop
-        handle
+    /// Configuration of [`ConfigSettings::mainnet`]
+    pub fn mainnet(spec: SpecId) -> Self {
+        EvmConfigBuilder::from_settings(&ConfigSettings::mainnet(spec)).build()
+    }
+
+    /// Configuration of [`ConfigSettings::testing`]
+    pub fn testing() -> Self {
+        EvmConfigBuilder::from_settings(&ConfigSettings::testing()).build()
+    }
+
+    /// Configuration of [`ConfigSettings::l2_generic`]
+    pub fn l2_generic() -> Self {
+        EvmConfigBuilder::from_settings(&ConfigSettings::l2_generic()).build()
+    }
+
+    /// Settings the configuration was built with, without the opcode and precompile handlers
+    pub fn settings(&self) -> &ConfigSettings {
+        &self.settings
+    }
+
+    /// Whether the config was created by libguillotine_mini, i.e. the config ABI is available
+    ///
+    /// `GuillotineMiniEvm::with_config` rejects unsupported configs with
+    /// `EvmAdapterError::Unsupported("config")`.
+    pub fn is_supported(&self) -> bool {
+        !self.handle.is_null()
+    }
+
+    /// Whether calls to `address` run a precompile: one added with `override_precompile`, or a
+    /// built-in of the configured hardfork (`hardfork` if none is set) left by the replaced set
+    /// and the removals
+    pub(crate) fn is_precompile(&self, address: Address, hardfork: Hardfork) -> bool {
+        if self.custom_precompiles.contains(&address) {
+            return true;
+        }
+        if self.settings.disabled_precompiles.contains(&address) {
+            return false;
+        }
+        match &self.settings.precompiles {
+            Some(kept) => kept.contains(&address),
+            None => {
+                let hardfork = self.settings.hardfork.as_deref().and_then(|name| name.parse().ok()).unwrap_or(hardfork);
+                Precompiles::new(PrecompileSpecId::from_spec_id(hardfork.spec())).contains(&address)
+            }
+        }
+    }
+
+    /// Raw handle to create instances from; the config must outlive them
+    pub(crate) fn as_raw(&self) -> *const ffi::EvmConfigHandle {
+        self.handle
     }
 }
 
@@ -434,7 +1092,8 @@ impl Drop for EvmConfig {
     }
 }
 
-// Safety: Config is thread-safe after construction
+// Safety: the handle is only destroyed by the owner and `&EvmConfig` only reaches the read-only
+// `evm_create_from_config`; the handlers are Send + Sync
 unsafe impl Send for EvmConfig {}
 unsafe impl Sync for EvmConfig {}
 
@@ -443,7 +1102,7 @@ unsafe impl Sync for EvmConfig {}
 /// Trampoline function for opcode handlers
 ///
 /// # Safety
-/// The `ctx` pointer must be a valid pointer to an `OpcodeHandlerFn` trait object created by
+/// The `ctx` pointer must be a valid pointer to a boxed `OpcodeHandlerFn` trait object created by
 /// `Box::into_raw` in `override_opcode`. The pointer must remain valid for the lifetime
 /// of the EVM config.
 extern "C" fn opcode_trampoline(ctx: *mut c_void, frame_ptr: usize, opcode: u8) -> bool {
@@ -452,14 +1111,20 @@ extern "C" fn opcode_trampoline(ctx: *mut c_void, frame_ptr: usize, opcode: u8)
     }
 
     // SAFETY: ctx was created by Box::into_raw in override_opcode and points to a valid OpcodeHandlerFn
-    let handler = unsafe { &*(ctx as *const OpcodeHandlerFn) };
-    handler(frame_ptr, opcode)
+    let handler = unsafe { &**(ctx as *const Box<OpcodeHandlerFn>) };
+    // SAFETY: guillotine-mini passes the executing frame, live until the handler returns
+    match unsafe { FrameView::from_raw(frame_ptr) } {
+        Some(mut frame) => handler(&mut frame, opcode),
+        None => false,
+    }
 }
 
 /// Trampoline function for precompile handlers
 ///
+/// Returns one of the `PRECOMPILE_*` status codes; the output is only handed over on success.
+///
 /// # Safety
-/// The `ctx` pointer must be a valid pointer to a `PrecompileHandlerFn` trait object created by
+/// The `ctx` pointer must be a valid pointer to a boxed `PrecompileHandlerFn` trait object created by
 /// `Box::into_raw` in `override_precompile`. The pointer must remain valid for the lifetime
 /// of the EVM config. The `address` and `input` pointers must be valid for their respective
 /// lengths.
@@ -472,48 +1137,56 @@ extern "C" fn precompile_trampoline(
     output_ptr: *mut *mut u8,
     output_len: *mut usize,
     gas_used: *mut u64,
-) -> bool {
+) -> u8 {
     if ctx.is_null() {
-        return false;
+        return ffi::PRECOMPILE_FAILURE;
     }
 
     // Validate address pointer
     if address.is_null() {
-        return false;
+        return ffi::PRECOMPILE_FAILURE;
     }
 
     // SAFETY: ctx was created by Box::into_raw in override_precompile and points to a valid PrecompileHandlerFn
-    let handler = unsafe { &*(ctx as *const PrecompileHandlerFn) };
+    let handler = unsafe { &**(ctx as *const Box<PrecompileHandlerFn>) };
 
-    let addr_slice = unsafe { std::slice::from_raw_parts(address, 20) };
+    let address = types::address_from_bytes(unsafe { &*(address as *const [u8; 20]) });
 
     // Handle null input pointer (empty input case)
     let input_slice = if input.is_null() && input_len == 0 {
         &[]
     } else if input.is_null() {
-        return false;
+        return ffi::PRECOMPILE_FAILURE;
     } else {
         unsafe { std::slice::from_raw_parts(input, input_len) }
     };
 
-    match handler(addr_slice, input_slice, gas_limit) {
-        Ok(result) => {
-            // Allocate output on heap and transfer ownership to C
-            let mut output_vec = result.output;
-            output_vec.shrink_to_fit();
+    let result = handler(address, input_slice, gas_limit);
+    let status = precompile_status(&result, gas_limit);
+    if let (ffi::PRECOMPILE_SUCCESS, Ok(result)) = (status, result) {
+        // Allocate output on heap and transfer ownership to C
+        let mut output_vec = result.output;
+        output_vec.shrink_to_fit();
 
-            unsafe {
-                *output_ptr = output_vec.as_mut_ptr();
-                *output_len = output_vec.len();
-                *gas_used = result.gas_used;
-            }
+        unsafe {
+            *output_ptr = output_vec.as_mut_ptr();
+            *output_len = output_vec.len();
+            *gas_used = result.gas_used;
+        }
 
-            // Leak the vec so C side can use it
-            std::mem::forget(output_vec);
+        // Leak the vec so C side can use it
+        std::mem::forget(output_vec);
+    }
+    status
+}
 
-            true
-        }
-        Err(_) => false,
+/// Status reported to guillotine-mini for a precompile handler's result
+fn precompile_status(result: &Result<PrecompileResult, PrecompileError>, gas_limit: u64) -> u8 {
+    match result {
+        Ok(result) if result.gas_used > gas_limit => ffi::PRECOMPILE_OUT_OF_GAS,
+        Ok(_) => ffi::PRECOMPILE_SUCCESS,
+        Err(PrecompileError::OutOfGas) => ffi::PRECOMPILE_OUT_OF_GAS,
+        Err(PrecompileError::InvalidInput | PrecompileError::ExecutionFailed(_)) => ffi::PRECOMPILE_FAILURE,
     }
 }
 
@@ -524,19 +1197,25 @@ mod tests {
     #[test]
     fn test_config_builder_creation() {
         let config = EvmConfigBuilder::new().build();
-        assert!(!config.handle.is_null());
+        assert_eq!(config.is_supported(), ffi::config_api().is_some());
     }
 
     #[test]
     fn test_config_builder_hardfork() {
-        let config = EvmConfigBuilder::new().hardfork("Cancun").build();
-        assert!(!config.handle.is_null());
+        let config = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).build();
+        assert_eq!(config.is_supported(), ffi::config_api().is_some());
+        assert_eq!(config.settings().hardfork.as_deref(), Some("Cancun"));
+
+        let builder = EvmConfigBuilder::new().hardfork(SpecId::PETERSBURG);
+        assert_eq!(builder.settings().hardfork.as_deref(), Some("Constantinople"));
+        let builder = EvmConfigBuilder::new().hardfork_name("Prague");
+        assert_eq!(builder.settings().hardfork.as_deref(), Some("Prague"));
     }
 
     #[test]
     fn test_config_builder_stack_size() {
         let config = EvmConfigBuilder::new().stack_size(512).build();
-        assert!(!config.handle.is_null());
+        assert_eq!(config.is_supported(), ffi::config_api().is_some());
     }
 
     #[test]
@@ -544,6 +1223,19 @@ mod tests {
         let config = EvmConfigBuilder::new()
             .loop_quota(Some(1_000_000))
             .build();
-        assert!(!config.handle.is_null());
+        assert_eq!(config.is_supported(), ffi::config_api().is_some());
+    }
+
+    #[test]
+    fn test_precompile_status() {
+        let ok = |gas_used| Ok(PrecompileResult { output: vec![], gas_used });
+        assert_eq!(precompile_status(&ok(100), 100), ffi::PRECOMPILE_SUCCESS);
+        assert_eq!(precompile_status(&ok(101), 100), ffi::PRECOMPILE_OUT_OF_GAS);
+        assert_eq!(precompile_status(&Err(PrecompileError::OutOfGas), 100), ffi::PRECOMPILE_OUT_OF_GAS);
+        assert_eq!(precompile_status(&Err(PrecompileError::InvalidInput), 100), ffi::PRECOMPILE_FAILURE);
+        assert_eq!(
+            precompile_status(&Err(PrecompileError::ExecutionFailed("bad".into())), 100),
+            ffi::PRECOMPILE_FAILURE
+        );
     }
 }
@@ -5,59 +5,136 @@
 
 use super::error::EvmAdapterError;
 use super::ffi::EvmHandle;
+use super::ffi_safe;
+use super::report::{CreatedContract, SelfDestruct};
 use super::types::{address_to_bytes, u256_to_be_bytes};
-use revm::database_interface::Database;
-use revm::primitives::{Address, U256};
+use super::validation;
+use revm::context::Cfg;
+use revm::context_interface::{transaction::AccessList, Block};
+use revm::database::CacheDB;
+#[cfg(feature = "asyncdb")]
+use revm::database_interface::DatabaseAsync;
+use revm::database_interface::{Database, DatabaseRef, WrapDatabaseRef};
+use revm::primitives::{hardfork::SpecId, keccak256, Address, Bytes, Log, LogData, B256, KECCAK_EMPTY, U256};
+use revm::state::{Account, AccountInfo, AccountStatus, Bytecode, EvmState, EvmStorageSlot, TransientStorage};
 
 /// Synchronize account state from REVM Database to guillotine-mini
 ///
-/// Sets up pre-state in guillotine-mini before execution
+/// Sets up pre-state in guillotine-mini before execution. Code the database returns only by
+/// hash is fetched via `code_by_hash`, and the code hash is propagated for EXTCODEHASH.
 ///
 /// # Safety
-/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create`.
-pub fn sync_account_to_ffi<DB: Database>(
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_account_to_ffi<DB: Database>(
     handle: *mut EvmHandle,
     db: &mut DB,
     address: Address,
 ) -> Result<(), EvmAdapterError<DB::Error>> {
-    if handle.is_null() {
-        return Err(EvmAdapterError::Ffi("null handle"));
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    if let Some(acc_info) = load_account_with_code(db, address)? {
+        write_account_info_to_ffi(handle, address, &acc_info).map_err(EvmAdapterError::ffi)?;
+    }
+
+    Ok(())
+}
+
+/// Synchronize account state to guillotine-mini after verifying its code hash
+///
+/// Like [`sync_account_to_ffi`], but returns [`EvmAdapterError::CodeHashMismatch`] instead of
+/// syncing when the account's code does not hash to its `code_hash`.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_account_to_ffi_verified<DB: Database>(
+    handle: *mut EvmHandle,
+    db: &mut DB,
+    address: Address,
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    if let Some(acc_info) = load_account_with_code(db, address)? {
+        verify_code_hash(address, &acc_info)?;
+        write_account_info_to_ffi(handle, address, &acc_info).map_err(EvmAdapterError::ffi)?;
+    }
+
+    Ok(())
+}
+
+/// Load account info, fetching its code by hash when the database returned it without code
+pub(crate) fn load_account_with_code<DB: Database>(
+    db: &mut DB,
+    address: Address,
+) -> Result<Option<AccountInfo>, EvmAdapterError<DB::Error>> {
+    let Some(mut acc_info) = db.basic(address).map_err(EvmAdapterError::Db)? else {
+        return Ok(None);
+    };
+    if acc_info.code.is_none() && acc_info.code_hash != KECCAK_EMPTY {
+        acc_info.code = Some(db.code_by_hash(acc_info.code_hash).map_err(EvmAdapterError::Db)?);
+    }
+    Ok(Some(acc_info))
+}
+
+/// Check that an account's code hashes to its `code_hash`
+///
+/// Accounts without code must carry `KECCAK_EMPTY`.
+pub fn verify_code_hash<E>(address: Address, info: &AccountInfo) -> Result<(), EvmAdapterError<E>> {
+    let computed = info.code.as_ref().map_or(KECCAK_EMPTY, |code| code.hash_slow());
+    if computed != info.code_hash {
+        return Err(EvmAdapterError::CodeHashMismatch { address, expected: info.code_hash, computed });
     }
+    Ok(())
+}
+
+/// Write account info (balance, nonce, code, code hash) into guillotine-mini
+///
+/// Code is only written when present, so accounts without code keep whatever
+/// code guillotine-mini already holds for them.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn write_account_info_to_ffi(
+    handle: *mut EvmHandle,
+    address: Address,
+    info: &AccountInfo,
+) -> Result<(), &'static str> {
+    ffi_safe::check_handle(handle)?;
 
     let addr_bytes = address_to_bytes(&address);
 
-    // Get account info from REVM database
-    let acc = db.basic(address).map_err(EvmAdapterError::Db)?;
+    // Set balance
+    let balance_bytes = u256_to_be_bytes(&info.balance);
+    let ok = unsafe { super::ffi::evm_set_balance(handle, addr_bytes.as_ptr(), balance_bytes.as_ptr()) };
+    if !ok {
+        return Err("evm_set_balance");
+    }
 
-    if let Some(acc_info) = acc {
-        // Set balance
-        let balance_bytes = u256_to_be_bytes(&acc_info.balance);
-        let ok = unsafe { super::ffi::evm_set_balance(handle, addr_bytes.as_ptr(), balance_bytes.as_ptr()) };
-        if !ok {
-            return Err(EvmAdapterError::Ffi("evm_set_balance"));
-        }
+    // Set nonce
+    let nonce_set = unsafe { super::ffi::evm_set_nonce(handle, addr_bytes.as_ptr(), info.nonce) };
+    if !nonce_set {
+        return Err("evm_set_nonce");
+    }
 
-        // Set nonce
-        let nonce_set = unsafe { super::ffi::evm_set_nonce(handle, addr_bytes.as_ptr(), acc_info.nonce) };
-        if !nonce_set {
-            return Err(EvmAdapterError::Ffi("evm_set_nonce"));
+    // Set code if exists
+    if let Some(code) = &info.code {
+        let code_bytes = code.bytecode();
+        let ok = unsafe {
+            super::ffi::evm_set_code(
+                handle,
+                addr_bytes.as_ptr(),
+                code_bytes.as_ptr(),
+                code_bytes.len(),
+            )
+        };
+        if !ok {
+            return Err("evm_set_code");
         }
+    }
 
-        // Set code if exists
-        if let Some(code) = &acc_info.code {
-            let code_bytes = code.bytecode();
-            let ok = unsafe {
-                super::ffi::evm_set_code(
-                    handle,
-                    addr_bytes.as_ptr(),
-                    code_bytes.as_ptr(),
-                    code_bytes.len(),
-                )
-            };
-            if !ok {
-                return Err(EvmAdapterError::Ffi("evm_set_code"));
-            }
-        }
+    // Set code hash so EXTCODEHASH matches the database without rehashing in guillotine-mini
+    let hash_set = unsafe { super::ffi::evm_set_code_hash(handle, addr_bytes.as_ptr(), info.code_hash.as_ptr()) };
+    if !hash_set {
+        return Err("evm_set_code_hash");
     }
 
     Ok(())
@@ -66,16 +143,14 @@ pub fn sync_account_to_ffi<DB: Database>(
 /// Synchronize storage slot from REVM Database to guillotine-mini
 ///
 /// # Safety
-/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create`.
-pub fn sync_storage_to_ffi<DB: Database>(
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_storage_to_ffi<DB: Database>(
     handle: *mut EvmHandle,
     db: &mut DB,
     address: Address,
     slot: U256,
 ) -> Result<(), EvmAdapterError<DB::Error>> {
-    if handle.is_null() {
-        return Err(EvmAdapterError::Ffi("null handle"));
-    }
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
 
     let addr_bytes = address_to_bytes(&address);
     let key_bytes = u256_to_be_bytes(&slot);
@@ -93,7 +168,7 @@ pub fn sync_storage_to_ffi<DB: Database>(
         )
     };
     if !ok {
-        return Err(EvmAdapterError::Ffi("evm_set_storage"));
+        return Err(EvmAdapterError::ffi("evm_set_storage"));
     }
 
     Ok(())
@@ -112,20 +187,18 @@ pub fn sync_storage_to_ffi<DB: Database>(
 /// * `slots` - Storage slots to pre-sync
 ///
 /// # Safety
-/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create`.
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
 ///
 /// # Note
 /// This is a temporary solution until callback-based lazy loading is implemented.
 /// Currently, only explicitly pre-synced slots will be available to the contract.
-pub fn sync_storage_slots_to_ffi<DB: Database>(
+pub unsafe fn sync_storage_slots_to_ffi<DB: Database>(
     handle: *mut EvmHandle,
     db: &mut DB,
     address: Address,
     slots: &[U256],
 ) -> Result<(), EvmAdapterError<DB::Error>> {
-    if handle.is_null() {
-        return Err(EvmAdapterError::Ffi("null handle"));
-    }
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
 
     for slot in slots {
         let value = db.storage(address, *slot).map_err(EvmAdapterError::Db)?;
@@ -144,7 +217,315 @@ pub fn sync_storage_slots_to_ffi<DB: Database>(
         };
 
         if !ok {
-            return Err(EvmAdapterError::Ffi("evm_set_storage"));
+            return Err(EvmAdapterError::ffi("evm_set_storage"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the blockchain context (chain id, block number, timestamp, coinbase, fees, ...) in guillotine-mini
+///
+/// The excess blob gas is passed alongside the blob base fee, so BLOBBASEFEE follows the
+/// update fraction of the configured fork.
+///
+/// Converts REVM's block environment and configuration the same way `GuillotineMiniEvm::transact`
+/// does, for custom execution flows that drive the FFI directly.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_block_env<BLOCK: Block, CFG: Cfg>(
+    handle: *mut EvmHandle,
+    block: &BLOCK,
+    cfg: &CFG,
+) -> Result<(), &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let chain_id_bytes = u256_to_be_bytes(&U256::from(cfg.chain_id()));
+    let difficulty_bytes = u256_to_be_bytes(&block.difficulty());
+    let prevrandao_bytes: [u8; 32] = block.prevrandao().unwrap_or_default().into();
+    let coinbase_bytes = address_to_bytes(&block.beneficiary());
+    let base_fee_bytes = u256_to_be_bytes(&U256::from(block.basefee()));
+
+    // EIP-4844: blob_base_fee
+    let blob_base_fee_bytes = u256_to_be_bytes(&U256::from(block.blob_gasprice().unwrap_or_default()));
+
+    unsafe {
+        super::ffi::evm_set_blockchain_context(
+            handle,
+            chain_id_bytes.as_ptr(),
+            block.number().to::<u64>(),
+            block.timestamp().to::<u64>(),
+            difficulty_bytes.as_ptr(),
+            prevrandao_bytes.as_ptr(),
+            coinbase_bytes.as_ptr(),
+            block.gas_limit(),
+            base_fee_bytes.as_ptr(),
+            blob_base_fee_bytes.as_ptr(),
+        );
+        // Lets guillotine-mini derive BLOBBASEFEE itself under the fork's fee rules
+        if let Some(excess_blob_gas) = block.blob_excess_gas() {
+            let fraction = validation::blob_base_fee_update_fraction(cfg.spec().into());
+            super::ffi::evm_set_blob_excess_gas(handle, excess_blob_gas, fraction);
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the EIP-4844 versioned hashes read by BLOBHASH
+///
+/// The hashes are validated for `spec` first (KZG version prefix, blob count limit); an empty
+/// list clears hashes set for a previous transaction.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn set_blob_hashes<E>(
+    handle: *mut EvmHandle,
+    spec: SpecId,
+    hashes: &[B256],
+) -> Result<(), EvmAdapterError<E>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+    validation::validate_blob_hashes(spec, hashes).map_err(EvmAdapterError::Transaction)?;
+
+    let packed: Vec<u8> = hashes.iter().flat_map(|hash| hash.0).collect();
+    let ok = unsafe { super::ffi::evm_set_blob_hashes(handle, packed.as_ptr(), hashes.len()) };
+    if !ok {
+        return Err(EvmAdapterError::ffi("evm_set_blob_hashes"));
+    }
+    Ok(())
+}
+
+/// Number of most recent block hashes available to BLOCKHASH
+pub const BLOCK_HASH_HISTORY: u64 = 256;
+
+/// Synchronize the hashes of the blocks preceding `current_block` to guillotine-mini
+///
+/// BLOCKHASH only exposes the last [`BLOCK_HASH_HISTORY`] blocks, so at most that many
+/// hashes are read from `Database::block_hash`.
+///
+/// Returns the number of hashes synced.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_block_hashes_to_ffi<DB: Database>(
+    handle: *mut EvmHandle,
+    db: &mut DB,
+    current_block: u64,
+) -> Result<usize, EvmAdapterError<DB::Error>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    let first = current_block.saturating_sub(BLOCK_HASH_HISTORY);
+    for number in first..current_block {
+        let hash = db.block_hash(number).map_err(EvmAdapterError::Db)?;
+
+        let ok = unsafe { super::ffi::evm_set_block_hash(handle, number, hash.as_ptr()) };
+        if !ok {
+            return Err(EvmAdapterError::ffi("evm_set_block_hash"));
+        }
+    }
+
+    Ok((current_block - first) as usize)
+}
+
+/// Synchronize every cached non-zero storage slot of an account from a `CacheDB`
+///
+/// A correct (if slower) alternative to syncing a fixed set of slots for contracts with
+/// arbitrary storage layouts (mappings, high slots). Only slots present in the `CacheDB`
+/// cache are synced; values that exist solely in the underlying database are not discovered.
+///
+/// Returns the number of slots synced.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_all_storage_to_ffi<ExtDB: DatabaseRef>(
+    handle: *mut EvmHandle,
+    db: &CacheDB<ExtDB>,
+    address: Address,
+) -> Result<usize, EvmAdapterError<ExtDB::Error>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    let Some(account) = db.cache.accounts.get(&address) else {
+        return Ok(0);
+    };
+
+    let addr_bytes = address_to_bytes(&address);
+    let mut synced = 0;
+    for (slot, value) in account.storage.iter().filter(|(_, value)| !value.is_zero()) {
+        let slot_bytes = u256_to_be_bytes(slot);
+        let value_bytes = u256_to_be_bytes(value);
+
+        let ok = unsafe {
+            super::ffi::evm_set_storage(
+                handle,
+                addr_bytes.as_ptr(),
+                slot_bytes.as_ptr(),
+                value_bytes.as_ptr(),
+            )
+        };
+        if !ok {
+            return Err(EvmAdapterError::ffi("evm_set_storage"));
+        }
+        synced += 1;
+    }
+
+    Ok(synced)
+}
+
+/// Synchronize exactly the accounts and storage keys listed in an EIP-2930 access list
+///
+/// For callers that know which state a transaction touches (e.g. from
+/// `GuillotineMiniEvm::create_access_list` or a node's `eth_createAccessList`), this syncs
+/// everything it reads without relying on the fixed-slot heuristic. Mapping entries can be
+/// listed by computing their keys with [`mapping_slot`].
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_access_list_to_ffi<DB: Database>(
+    handle: *mut EvmHandle,
+    db: &mut DB,
+    access_list: &AccessList,
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    for item in access_list.0.iter() {
+        sync_account_to_ffi(handle, db, item.address)?;
+        let slots: Vec<U256> = item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0)).collect();
+        sync_storage_slots_to_ffi(handle, db, item.address, &slots)?;
+    }
+
+    Ok(())
+}
+
+/// Storage slot of `mapping[key]` for a Solidity mapping declared at `base_slot`
+///
+/// Solidity stores mapping values at `keccak256(key . base_slot)`, with the key left-padded to
+/// 32 bytes (addresses and integers) — e.g. `mapping_slot(U256::ZERO, holder.into_word())` for
+/// an ERC20 `balanceOf` mapping at slot 0.
+pub fn mapping_slot(base_slot: U256, key: B256) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(key.as_slice());
+    preimage[32..].copy_from_slice(&base_slot.to_be_bytes::<32>());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Storage slot of `mapping[keys[0]][keys[1]]...` for nested Solidity mappings at `base_slot`
+///
+/// Applies [`mapping_slot`] once per key, e.g. `[owner.into_word(), spender.into_word()]` for an
+/// ERC20 `allowance` mapping.
+pub fn nested_mapping_slot(base_slot: U256, keys: &[B256]) -> U256 {
+    keys.iter().fold(base_slot, |slot, key| mapping_slot(slot, *key))
+}
+
+/// Storage slot of `array[index]` for a dynamic Solidity array declared at `base_slot`
+///
+/// The array length lives at `base_slot` and its elements start at `keccak256(base_slot)`,
+/// one slot per element (elements smaller than 32 bytes are packed, so `index` counts slots).
+pub fn array_slot(base_slot: U256, index: U256) -> U256 {
+    let start = U256::from_be_bytes(keccak256(base_slot.to_be_bytes::<32>()).0);
+    start.wrapping_add(index)
+}
+
+/// Synchronize account state from a read-only `DatabaseRef` to guillotine-mini
+///
+/// Same as [`sync_account_to_ffi`] for databases that are shared immutably
+/// (e.g. fork caches behind an `Arc`).
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_account_to_ffi_ref<DB: DatabaseRef>(
+    handle: *mut EvmHandle,
+    db: &DB,
+    address: Address,
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    sync_account_to_ffi(handle, &mut WrapDatabaseRef(db), address)
+}
+
+/// Synchronize a storage slot from a read-only `DatabaseRef` to guillotine-mini
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_storage_to_ffi_ref<DB: DatabaseRef>(
+    handle: *mut EvmHandle,
+    db: &DB,
+    address: Address,
+    slot: U256,
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    sync_storage_to_ffi(handle, &mut WrapDatabaseRef(db), address, slot)
+}
+
+/// Synchronize multiple storage slots from a read-only `DatabaseRef` to guillotine-mini
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn sync_storage_slots_to_ffi_ref<DB: DatabaseRef>(
+    handle: *mut EvmHandle,
+    db: &DB,
+    address: Address,
+    slots: &[U256],
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    sync_storage_slots_to_ffi(handle, &mut WrapDatabaseRef(db), address, slots)
+}
+
+/// Asynchronously synchronize account state from a `DatabaseAsync` to guillotine-mini
+///
+/// For RPC-backed databases (e.g. `AlloyDB`): the database read is awaited, so no runtime
+/// thread is blocked while state is fetched. The FFI writes happen once the data is available.
+///
+/// To run `transact` directly on such a database, wrap it in revm's `WrapDatabaseAsync`,
+/// which blocks correctly (`block_in_place`) on multi-threaded tokio runtimes.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+#[cfg(feature = "asyncdb")]
+pub async unsafe fn sync_account_to_ffi_async<DB: DatabaseAsync>(
+    handle: *mut EvmHandle,
+    db: &mut DB,
+    address: Address,
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    if let Some(acc_info) = db.basic_async(address).await.map_err(EvmAdapterError::Db)? {
+        write_account_info_to_ffi(handle, address, &acc_info).map_err(EvmAdapterError::ffi)?;
+    }
+
+    Ok(())
+}
+
+/// Asynchronously synchronize multiple storage slots from a `DatabaseAsync` to guillotine-mini
+///
+/// All slots are fetched before anything is written, so a failed read leaves guillotine-mini
+/// untouched.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+#[cfg(feature = "asyncdb")]
+pub async unsafe fn sync_storage_slots_to_ffi_async<DB: DatabaseAsync>(
+    handle: *mut EvmHandle,
+    db: &mut DB,
+    address: Address,
+    slots: &[U256],
+) -> Result<(), EvmAdapterError<DB::Error>> {
+    ffi_safe::check_handle(handle).map_err(EvmAdapterError::ffi)?;
+
+    let mut values = Vec::with_capacity(slots.len());
+    for slot in slots {
+        values.push(db.storage_async(address, *slot).await.map_err(EvmAdapterError::Db)?);
+    }
+
+    let addr_bytes = address_to_bytes(&address);
+    for (slot, value) in slots.iter().zip(values) {
+        let slot_bytes = u256_to_be_bytes(slot);
+        let value_bytes = u256_to_be_bytes(&value);
+
+        let ok = unsafe {
+            super::ffi::evm_set_storage(
+                handle,
+                addr_bytes.as_ptr(),
+                slot_bytes.as_ptr(),
+                value_bytes.as_ptr(),
+            )
+        };
+        if !ok {
+            return Err(EvmAdapterError::ffi("evm_set_storage"));
         }
     }
 
@@ -154,20 +535,18 @@ pub fn sync_storage_slots_to_ffi<DB: Database>(
 /// Read storage value back from guillotine-mini FFI
 ///
 /// # Safety
-/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create`.
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
 ///
 /// # Errors
 /// Returns an error if:
 /// - The handle is null
 /// - The FFI call to `evm_get_storage` fails
-pub fn read_storage_from_ffi(
+pub unsafe fn read_storage_from_ffi(
     handle: *mut EvmHandle,
     address: Address,
     slot: U256,
 ) -> Result<U256, &'static str> {
-    if handle.is_null() {
-        return Err("null handle in read_storage_from_ffi");
-    }
+    ffi_safe::check_handle(handle)?;
 
     let addr_bytes = address_to_bytes(&address);
     let key_bytes = u256_to_be_bytes(&slot);
@@ -188,3 +567,509 @@ pub fn read_storage_from_ffi(
 
     Ok(super::types::u256_from_be_bytes(&value_bytes))
 }
+
+/// Read the original (pre-transaction) value of a storage slot from guillotine-mini's journal
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+///
+/// # Errors
+/// Returns an error if:
+/// - The handle is null
+/// - The FFI call to `evm_get_original_storage` fails
+pub unsafe fn read_original_storage_from_ffi(
+    handle: *mut EvmHandle,
+    address: Address,
+    slot: U256,
+) -> Result<U256, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let addr_bytes = address_to_bytes(&address);
+    let key_bytes = u256_to_be_bytes(&slot);
+    let mut value_bytes = [0u8; 32];
+
+    let ok = unsafe {
+        super::ffi::evm_get_original_storage(
+            handle,
+            addr_bytes.as_ptr(),
+            key_bytes.as_ptr(),
+            value_bytes.as_mut_ptr(),
+        )
+    };
+
+    if !ok {
+        return Err("evm_get_original_storage failed");
+    }
+
+    Ok(super::types::u256_from_be_bytes(&value_bytes))
+}
+
+/// Read account balance back from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+///
+/// # Errors
+/// Returns an error if:
+/// - The handle is null
+/// - The FFI call to `evm_get_balance` fails
+pub unsafe fn read_balance_from_ffi(handle: *mut EvmHandle, address: Address) -> Result<U256, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let addr_bytes = address_to_bytes(&address);
+    let mut balance_bytes = [0u8; 32];
+
+    let ok = unsafe { super::ffi::evm_get_balance(handle, addr_bytes.as_ptr(), balance_bytes.as_mut_ptr()) };
+
+    if !ok {
+        return Err("evm_get_balance failed");
+    }
+
+    Ok(super::types::u256_from_be_bytes(&balance_bytes))
+}
+
+/// Read account nonce back from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+///
+/// # Errors
+/// Returns an error if:
+/// - The handle is null
+/// - The FFI call to `evm_get_nonce` fails
+pub unsafe fn read_nonce_from_ffi(handle: *mut EvmHandle, address: Address) -> Result<u64, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let addr_bytes = address_to_bytes(&address);
+    let mut nonce = 0u64;
+
+    let ok = unsafe { super::ffi::evm_get_nonce(handle, addr_bytes.as_ptr(), &mut nonce) };
+
+    if !ok {
+        return Err("evm_get_nonce failed");
+    }
+
+    Ok(nonce)
+}
+
+/// Read account code back from guillotine-mini FFI
+///
+/// Returns empty bytes for accounts without code.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_code_from_ffi(handle: *mut EvmHandle, address: Address) -> Result<Bytes, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let addr_bytes = address_to_bytes(&address);
+    let code_len = unsafe { super::ffi::evm_get_code_len(handle, addr_bytes.as_ptr()) };
+    let mut code = vec![0u8; code_len];
+    if code_len > 0 {
+        let copied = unsafe { super::ffi::evm_get_code(handle, addr_bytes.as_ptr(), code.as_mut_ptr(), code_len) };
+        code.truncate(copied);
+    }
+
+    Ok(Bytes::from(code))
+}
+
+/// Read the data the last execution passed to REVERT from guillotine-mini FFI
+///
+/// Empty unless the top-level frame reverted.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_revert_data_from_ffi(handle: *mut EvmHandle) -> Result<Bytes, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let len = unsafe { super::ffi::evm_get_revert_data_len(handle) };
+    let mut data = vec![0u8; len];
+    if len > 0 {
+        let copied = unsafe { super::ffi::evm_get_revert_data(handle, data.as_mut_ptr(), len) };
+        data.truncate(copied);
+    }
+
+    Ok(Bytes::from(data))
+}
+
+/// Read full account info (balance, nonce, code) back from guillotine-mini FFI
+///
+/// The code hash is computed from the returned code.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_account_info_from_ffi(
+    handle: *mut EvmHandle,
+    address: Address,
+) -> Result<AccountInfo, &'static str> {
+    let balance = read_balance_from_ffi(handle, address)?;
+    let nonce = read_nonce_from_ffi(handle, address)?;
+    let code = read_code_from_ffi(handle, address)?;
+
+    let (code_hash, code) = if code.is_empty() {
+        (KECCAK_EMPTY, None)
+    } else {
+        (keccak256(&code), Some(Bytecode::new_raw(code)))
+    };
+
+    Ok(AccountInfo { balance, nonce, code_hash, code })
+}
+
+/// Read the transient storage (EIP-1153) left by the last execution from guillotine-mini FFI
+///
+/// Transient storage is discarded at the end of every transaction; this returns the non-zero
+/// entries as they were when execution finished.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_transient_storage_from_ffi(handle: *mut EvmHandle) -> Result<TransientStorage, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { super::ffi::evm_get_transient_storage_count(handle) };
+    let mut transient_storage = TransientStorage::default();
+    for i in 0..count {
+        let mut addr_bytes = [0u8; 20];
+        let mut slot_bytes = [0u8; 32];
+        let mut value_bytes = [0u8; 32];
+
+        let ok = unsafe {
+            super::ffi::evm_get_transient_storage(
+                handle,
+                i,
+                addr_bytes.as_mut_ptr(),
+                slot_bytes.as_mut_ptr(),
+                value_bytes.as_mut_ptr(),
+            )
+        };
+        if !ok {
+            return Err("evm_get_transient_storage failed");
+        }
+
+        transient_storage.insert(
+            (super::types::address_from_bytes(&addr_bytes), super::types::u256_from_be_bytes(&slot_bytes)),
+            super::types::u256_from_be_bytes(&value_bytes),
+        );
+    }
+
+    Ok(transient_storage)
+}
+
+/// Read the SELFDESTRUCTs of the last execution from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_selfdestructs_from_ffi(handle: *mut EvmHandle) -> Result<Vec<SelfDestruct>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { super::ffi::evm_get_selfdestruct_count(handle) };
+    let mut selfdestructs = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut addr_bytes = [0u8; 20];
+        let mut beneficiary_bytes = [0u8; 20];
+        let mut balance_bytes = [0u8; 32];
+        let ok = unsafe {
+            super::ffi::evm_get_selfdestruct(
+                handle,
+                i,
+                addr_bytes.as_mut_ptr(),
+                beneficiary_bytes.as_mut_ptr(),
+                balance_bytes.as_mut_ptr(),
+            )
+        };
+        if !ok {
+            return Err("evm_get_selfdestruct failed");
+        }
+        selfdestructs.push(SelfDestruct {
+            address: super::types::address_from_bytes(&addr_bytes),
+            beneficiary: super::types::address_from_bytes(&beneficiary_bytes),
+            balance: super::types::u256_from_be_bytes(&balance_bytes),
+        });
+    }
+    Ok(selfdestructs)
+}
+
+/// Read the contracts created by the last execution from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_created_contracts_from_ffi(handle: *mut EvmHandle) -> Result<Vec<CreatedContract>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { super::ffi::evm_get_created_contract_count(handle) };
+    let mut created = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut addr_bytes = [0u8; 20];
+        let mut deployer_bytes = [0u8; 20];
+        let mut init_code_hash = B256::ZERO;
+        let mut code_len: usize = 0;
+        let ok = unsafe {
+            super::ffi::evm_get_created_contract(
+                handle,
+                i,
+                addr_bytes.as_mut_ptr(),
+                deployer_bytes.as_mut_ptr(),
+                init_code_hash.as_mut_ptr(),
+                &mut code_len,
+            )
+        };
+        if !ok {
+            return Err("evm_get_created_contract failed");
+        }
+
+        let mut code = vec![0u8; code_len];
+        let copied = unsafe { super::ffi::evm_get_created_contract_code(handle, i, code.as_mut_ptr(), code.len()) };
+        if copied != code_len {
+            return Err("evm_get_created_contract_code failed");
+        }
+
+        created.push(CreatedContract {
+            address: super::types::address_from_bytes(&addr_bytes),
+            deployer: super::types::address_from_bytes(&deployer_bytes),
+            init_code_hash,
+            code: Bytes::from(code),
+        });
+    }
+    Ok(created)
+}
+
+/// Read every log emitted by the last execution from guillotine-mini FFI
+///
+/// Logs are copied in a single `evm_get_logs_packed` call instead of one call per log.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_logs_from_ffi(handle: *mut EvmHandle) -> Result<Vec<Log>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let len = unsafe { super::ffi::evm_get_logs_packed(handle, std::ptr::null_mut(), 0) };
+    let mut buf = vec![0u8; len];
+    let copied = unsafe { super::ffi::evm_get_logs_packed(handle, buf.as_mut_ptr(), buf.len()) };
+    if copied != len {
+        return Err("evm_get_logs_packed failed");
+    }
+    unpack_logs(&buf)
+}
+
+/// Decode the buffer filled by `evm_get_logs_packed`
+///
+/// An empty buffer holds no logs. Truncated or trailing bytes and more than 4 topics per log
+/// are rejected.
+pub fn unpack_logs(buf: &[u8]) -> Result<Vec<Log>, &'static str> {
+    const MALFORMED: &str = "malformed packed logs";
+
+    fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], &'static str> {
+        if buf.len() < len {
+            return Err(MALFORMED);
+        }
+        let (head, tail) = buf.split_at(len);
+        *buf = tail;
+        Ok(head)
+    }
+    fn take_u32(buf: &mut &[u8]) -> Result<usize, &'static str> {
+        Ok(u32::from_le_bytes(take(buf, 4)?.try_into().unwrap()) as usize)
+    }
+
+    if buf.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut buf = buf;
+    let count = take_u32(&mut buf)?;
+    // Every log takes at least 25 bytes, which bounds the allocation
+    let mut logs = Vec::with_capacity(count.min(buf.len() / 25));
+    for _ in 0..count {
+        let address = Address::from_slice(take(&mut buf, 20)?);
+        let topic_count = take(&mut buf, 1)?[0] as usize;
+        let topics = take(&mut buf, topic_count * 32)?.chunks_exact(32).map(B256::from_slice).collect();
+        let data_len = take_u32(&mut buf)?;
+        let data = Bytes::copy_from_slice(take(&mut buf, data_len)?);
+        let data = LogData::new(topics, data).ok_or(MALFORMED)?;
+        logs.push(Log { address, data });
+    }
+    if !buf.is_empty() {
+        return Err(MALFORMED);
+    }
+    Ok(logs)
+}
+
+/// Read the storage changes of the last execution from guillotine-mini FFI
+///
+/// Returns `(address, slot, present value)` for every slot written during execution.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_storage_changes_from_ffi(
+    handle: *mut EvmHandle,
+) -> Result<Vec<(Address, U256, U256)>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { super::ffi::evm_get_storage_change_count(handle) };
+    let mut changes = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut addr_bytes = [0u8; 20];
+        let mut slot_bytes = [0u8; 32];
+        let mut value_bytes = [0u8; 32];
+
+        let ok = unsafe {
+            super::ffi::evm_get_storage_change(
+                handle,
+                i,
+                addr_bytes.as_mut_ptr(),
+                slot_bytes.as_mut_ptr(),
+                value_bytes.as_mut_ptr(),
+            )
+        };
+        if !ok {
+            return Err("evm_get_storage_change failed");
+        }
+
+        changes.push((
+            super::types::address_from_bytes(&addr_bytes),
+            super::types::u256_from_be_bytes(&slot_bytes),
+            super::types::u256_from_be_bytes(&value_bytes),
+        ));
+    }
+
+    Ok(changes)
+}
+
+/// Read the accounts touched by the last execution from guillotine-mini FFI
+///
+/// Every account that was accessed, modified or holds a storage change, once each and in
+/// first-touch order, including value transfer recipients, created contracts and self-destruct
+/// beneficiaries.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_touched_accounts_from_ffi(handle: *mut EvmHandle) -> Result<Vec<Address>, &'static str> {
+    // Collected before returning: reading account info must not interleave with the enumeration
+    Ok(touched_accounts_from_ffi(handle)?.collect())
+}
+
+/// Iterator over the accounts touched by the last execution, see [`touched_accounts_from_ffi`]
+pub(crate) struct TouchedAccounts {
+    handle: *mut EvmHandle,
+}
+
+impl Iterator for TouchedAccounts {
+    type Item = Address;
+
+    fn next(&mut self) -> Option<Address> {
+        let mut addr_bytes = [0u8; 20];
+        let ok = unsafe { super::ffi::evm_touched_accounts_next(self.handle, addr_bytes.as_mut_ptr()) };
+        ok.then(|| super::types::address_from_bytes(&addr_bytes))
+    }
+}
+
+/// Enumerate every account touched by the last execution in guillotine-mini
+///
+/// Starting a new enumeration on the same handle ends this one. The `handle` must outlive the
+/// iterator.
+pub(crate) fn touched_accounts_from_ffi(handle: *mut EvmHandle) -> Result<TouchedAccounts, &'static str> {
+    ffi_safe::check_handle(handle)?;
+    if !unsafe { super::ffi::evm_touched_accounts_begin(handle) } {
+        return Err("evm_touched_accounts_begin failed");
+    }
+    Ok(TouchedAccounts { handle })
+}
+
+/// Read the full post-state of the last execution from guillotine-mini FFI
+///
+/// Built from the touched-account enumeration: every account that was modified, accessed
+/// during execution or holds a storage change is included, marked as touched, with balance,
+/// nonce and code read back along with its changed storage slots. Each slot's `original_value` is the pre-transaction value recorded by
+/// guillotine-mini's journal, and accounts and slots the access tracker reports as cold are
+/// marked `AccountStatus::Cold` / `is_cold`.
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub unsafe fn read_state_from_ffi(handle: *mut EvmHandle) -> Result<EvmState, &'static str> {
+    let touched = read_touched_accounts_from_ffi(handle)?;
+    let changes = read_storage_changes_from_ffi(handle)?;
+
+    let mut state = EvmState::default();
+    for address in touched.into_iter().chain(changes.iter().map(|(address, _, _)| *address)) {
+        if state.contains_key(&address) {
+            continue;
+        }
+        let info = read_account_info_from_ffi(handle, address)?;
+        let mut status = AccountStatus::Touched;
+        if !super::access_list::is_address_warm_in_ffi(handle, address)? {
+            status |= AccountStatus::Cold;
+        }
+        state.insert(address, Account { info, storage: Default::default(), status, transaction_id: 0 });
+    }
+
+    for (address, slot, value) in changes {
+        let Some(account) = state.get_mut(&address) else { continue };
+        let original_value = read_original_storage_from_ffi(handle, address, slot)?;
+        let is_cold = !super::access_list::is_storage_warm_in_ffi(handle, address, slot)?;
+        account.storage.insert(slot, EvmStorageSlot { original_value, present_value: value, transaction_id: 0, is_cold });
+    }
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::b256;
+
+    #[test]
+    fn test_unpack_logs() {
+        assert_eq!(unpack_logs(&[]), Ok(Vec::new()));
+
+        let address = Address::repeat_byte(0xaa);
+        let topic = B256::repeat_byte(0x11);
+        let mut buf = 2u32.to_le_bytes().to_vec();
+        buf.extend_from_slice(address.as_slice());
+        buf.push(1);
+        buf.extend_from_slice(topic.as_slice());
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&[1, 2, 3]);
+        buf.extend_from_slice(address.as_slice());
+        buf.push(0);
+        buf.extend_from_slice(&0u32.to_le_bytes());
+
+        let logs = unpack_logs(&buf).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].address, address);
+        assert_eq!(logs[0].data.topics(), &[topic]);
+        assert_eq!(logs[0].data.data, Bytes::from_static(&[1, 2, 3]));
+        assert!(logs[1].data.topics().is_empty() && logs[1].data.data.is_empty());
+
+        assert_eq!(unpack_logs(&buf[..buf.len() - 1]), Err("malformed packed logs"));
+        let mut trailing = buf.clone();
+        trailing.push(0);
+        assert_eq!(unpack_logs(&trailing), Err("malformed packed logs"));
+        let mut five_topics = 1u32.to_le_bytes().to_vec();
+        five_topics.extend_from_slice(address.as_slice());
+        five_topics.push(5);
+        five_topics.extend_from_slice(&[0; 5 * 32]);
+        five_topics.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(unpack_logs(&five_topics), Err("malformed packed logs"));
+    }
+
+    #[test]
+    fn test_mapping_slot_matches_solidity_layout() {
+        // keccak256 of 64 zero bytes: mapping at slot 0, key 0
+        assert_eq!(
+            mapping_slot(U256::ZERO, B256::ZERO),
+            U256::from_be_bytes(b256!("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5").0)
+        );
+    }
+
+    #[test]
+    fn test_array_and_nested_mapping_slots() {
+        // keccak256 of the zero slot, where elements of an array at slot 0 start
+        let start = U256::from_be_bytes(b256!("290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563").0);
+        assert_eq!(array_slot(U256::ZERO, U256::ZERO), start);
+        assert_eq!(array_slot(U256::ZERO, U256::from(3)), start + U256::from(3));
+
+        let owner = B256::with_last_byte(1);
+        let spender = B256::with_last_byte(2);
+        assert_eq!(
+            nested_mapping_slot(U256::from(1), &[owner, spender]),
+            mapping_slot(mapping_slot(U256::from(1), owner), spender)
+        );
+        assert_eq!(nested_mapping_slot(U256::from(1), &[]), U256::from(1));
+    }
+}
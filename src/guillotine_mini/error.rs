@@ -3,7 +3,7 @@
 //! # Error Handling Overview
 //!
 //! This module defines the error types used by the guillotine-mini REVM adapter.
-//! Errors are categorized into two main types:
+//! Errors are categorized into four main types:
 //!
 //! ## Database Errors (`EvmAdapterError::Db`)
 //!
@@ -37,10 +37,29 @@
 //!         eprintln!("Database error: {:?}", e);
 //!         // Handle database failure (e.g., retry, use fallback)
 //!     }
-//!     Err(EvmAdapterError::Ffi(name)) => {
-//!         eprintln!("FFI call '{}' failed", name);
+//!     Err(EvmAdapterError::Ffi(err)) => {
+//!         eprintln!("FFI call '{}' failed: {:?}", err.call, err.message);
 //!         // Handle FFI failure (e.g., log, abort)
 //!     }
+//!     Err(EvmAdapterError::Transaction(e)) => {
+//!         eprintln!("Invalid transaction: {}", e);
+//!         // Reject the transaction (e.g., wrong nonce, insufficient funds)
+//!     }
+//!     Err(EvmAdapterError::CodeHashMismatch { address, .. }) => {
+//!         eprintln!("Database returned inconsistent code for {}", address);
+//!     }
+//!     Err(EvmAdapterError::AbiMismatch { expected, found }) => {
+//!         eprintln!("libguillotine_mini ABI {} does not match bindings ABI {}", found, expected);
+//!     }
+//!     Err(EvmAdapterError::Unsupported(feature)) => {
+//!         eprintln!("libguillotine_mini does not export the {} ABI", feature);
+//!     }
+//!     Err(EvmAdapterError::Cancelled) => {
+//!         eprintln!("Execution was cancelled");
+//!     }
+//!     Err(EvmAdapterError::DeadlineExceeded) => {
+//!         eprintln!("Execution ran past its deadline");
+//!     }
 //! }
 //! ```
 //!
@@ -54,12 +73,14 @@
 //! - Invalid parameters are passed (caught at FFI boundary)
 //!
 //! **When it occurs**:
-//! - `evm_create` returns null (EVM instance creation failed)
+//! - `evm_create_with_hardfork` returns null (EVM instance creation failed)
 //! - `evm_set_bytecode` returns false (bytecode too large or invalid)
 //! - `evm_set_execution_context` returns false (invalid parameters)
 //!
 //! The error contains the name of the FFI function that failed, making it easy to
-//! identify the source of the problem.
+//! identify the source of the problem, along with guillotine-mini's diagnostic for the failure
+//! (`FfiError::kind` and `FfiError::message`) — e.g. whether `evm_set_bytecode` hit a size limit
+//! or failed to allocate.
 //!
 //! **Example**:
 //! ```rust,no_run
@@ -70,7 +91,7 @@
 //!
 //! match GuillotineMiniEvm::try_new(ctx) {
 //!     Ok(evm) => println!("EVM created successfully"),
-//!     Err(EvmAdapterError::Ffi("evm_create")) => {
+//!     Err(EvmAdapterError::Ffi(err)) if err.call == "evm_create_with_hardfork" => {
 //!         eprintln!("Failed to create EVM instance");
 //!         // This is a fatal error - cannot proceed
 //!     }
@@ -78,15 +99,130 @@
 //! }
 //! ```
 //!
+//! ## Transaction Errors (`EvmAdapterError::Transaction`)
+//!
+//! The transaction failed pre-execution validation and was not executed. Carries REVM's
+//! `InvalidTransaction` so callers can handle it exactly as they would with REVM.
+//!
+//! **When it occurs**:
+//! - Nonce does not match the sender's nonce
+//! - Sender cannot pay the value plus `gas_limit * max_fee`
+//! - Gas limit exceeds the block gas limit or is below the intrinsic gas cost
+//! - Chain id mismatch or max fee below the block base fee
+//!
+//! ## Code Hash Errors (`EvmAdapterError::CodeHashMismatch`)
+//!
+//! Only produced when code hash verification is enabled with
+//! `GuillotineMiniEvm::set_verify_code_hashes(true)`: an account synced from the database has
+//! code whose keccak256 differs from its `code_hash`, which indicates a corrupt database.
+//!
+//! ## ABI Errors (`EvmAdapterError::AbiMismatch`)
+//!
+//! Returned by `GuillotineMiniEvm::try_new` when the linked libguillotine_mini implements a
+//! different C ABI version than these bindings (`ffi::ABI_VERSION`). Calling into a mismatched
+//! library could silently corrupt memory, so no instance is created. Rebuild against the
+//! guillotine-mini version pinned by this crate.
+//!
+//! ## Unsupported Features (`EvmAdapterError::Unsupported`)
+//!
+//! Returned by `GuillotineMiniEvm::with_config` and `EvmBuilder::build` when the linked
+//! libguillotine_mini does not export the optional config ABI (`"config"`). Fall back to
+//! `GuillotineMiniEvm::try_new`.
+//!
+//! ## Cancellation (`EvmAdapterError::Cancelled`)
+//!
+//! The EVM's `CancellationToken` was cancelled, interrupting the execution or preventing it
+//! from starting. Nothing is committed and guillotine-mini state is discarded.
+//!
+//! ## Deadlines (`EvmAdapterError::DeadlineExceeded`)
+//!
+//! The deadline set with `TransactOptions::deadline` passed before execution finished. As with
+//! cancellation, nothing is committed and guillotine-mini state is discarded.
+//!
 //! ## Error Recovery
 //!
 //! - **Database errors**: Recoverable - can retry or use alternate database
 //! - **FFI errors**: Generally unrecoverable - indicate fundamental initialization failure
+//! - **Transaction errors**: Recoverable - the transaction is invalid, state is untouched
+//! - **Cancellation**: Recoverable - install a new token to execute again
+//! - **Deadlines**: Recoverable - retry with a later deadline
 //! - **Catastrophic Zig errors**: Cause process abort (panic/unreachable in Zig)
 //!
 //! Note: Normal EVM execution failures (reverts, out of gas) do NOT produce errors.
 //! They are returned as `ExecutionResult::Revert` or similar success variants.
 
+use revm::{
+    context_interface::result::InvalidTransaction,
+    primitives::{Address, B256},
+};
+
+/// Cause of a failed guillotine-mini FFI call, as reported by `evm_get_last_error_code`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiErrorKind {
+    /// No diagnostic was recorded (e.g. the failure was detected on the Rust side)
+    Unknown,
+    /// Invalid input (malformed parameters, unknown hardfork, null pointers)
+    InvalidInput,
+    /// Allocation failure in guillotine-mini
+    OutOfMemory,
+    /// Input exceeded a size limit (e.g. bytecode or calldata length)
+    SizeLimit,
+    /// Error code not known to this version of the bindings
+    Other(i32),
+}
+
+impl FfiErrorKind {
+    /// Map a guillotine-mini error code
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => Self::Unknown,
+            1 => Self::InvalidInput,
+            2 => Self::OutOfMemory,
+            3 => Self::SizeLimit,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// A failed guillotine-mini FFI call with its diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiError {
+    /// Name of the FFI function (or bridge operation) that failed
+    pub call: &'static str,
+    /// Cause reported by guillotine-mini
+    pub kind: FfiErrorKind,
+    /// Human-readable diagnostic reported by guillotine-mini, if any
+    pub message: Option<String>,
+}
+
+impl FfiError {
+    /// Failure of `call`, with the diagnostic guillotine-mini recorded for this thread
+    ///
+    /// Must be called right after the failing call, before any other guillotine-mini call
+    /// replaces the diagnostic.
+    pub fn last(call: &'static str) -> Self {
+        let code = unsafe { super::ffi::evm_get_last_error_code() };
+        let len = unsafe { super::ffi::evm_get_last_error(std::ptr::null_mut(), 0) };
+        let message = (len > 0).then(|| {
+            let mut buf = vec![0u8; len];
+            let copied = unsafe { super::ffi::evm_get_last_error(buf.as_mut_ptr(), buf.len()) };
+            buf.truncate(copied.min(len));
+            String::from_utf8_lossy(&buf).into_owned()
+        });
+        Self { call, kind: FfiErrorKind::from_code(code), message }
+    }
+}
+
+impl core::fmt::Display for FfiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.call)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({})", message)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum EvmAdapterError<DbErr> {
     /// Database-related error from REVM
@@ -97,9 +233,54 @@ pub enum EvmAdapterError<DbErr> {
 
     /// FFI call failed (bool=false or null handle)
     ///
-    /// Contains the name of the FFI function that failed. This typically indicates
-    /// a fundamental initialization failure or invalid parameters at the FFI boundary.
-    Ffi(&'static str),
+    /// Contains the name of the FFI function that failed and guillotine-mini's diagnostic.
+    /// This typically indicates a fundamental initialization failure or invalid parameters at
+    /// the FFI boundary.
+    Ffi(FfiError),
+
+    /// Transaction failed pre-execution validation
+    ///
+    /// The transaction was rejected before reaching guillotine-mini (e.g. nonce mismatch,
+    /// insufficient funds for the maximum fee, gas limit below the intrinsic cost).
+    Transaction(InvalidTransaction),
+
+    /// Account code does not hash to the account's `code_hash`
+    ///
+    /// Only reported when code hash verification is enabled.
+    CodeHashMismatch {
+        /// Account whose code was synced
+        address: Address,
+        /// Code hash stored in the database
+        expected: B256,
+        /// keccak256 of the code stored in the database
+        computed: B256,
+    },
+
+    /// The linked libguillotine_mini implements a different C ABI version than the bindings
+    AbiMismatch {
+        /// ABI version the bindings were written against
+        expected: u32,
+        /// ABI version reported by the library
+        found: u32,
+    },
+
+    /// The linked libguillotine_mini does not export an optional part of the C ABI
+    ///
+    /// Contains the name of the missing feature (e.g. `"config"`).
+    Unsupported(&'static str),
+
+    /// Execution was cancelled through the EVM's `CancellationToken`
+    Cancelled,
+
+    /// Execution was still running at the deadline of its `TransactOptions`
+    DeadlineExceeded,
+}
+
+impl<DbErr> EvmAdapterError<DbErr> {
+    /// FFI failure of `call`, capturing guillotine-mini's diagnostic (see [`FfiError::last`])
+    pub fn ffi(call: &'static str) -> Self {
+        Self::Ffi(FfiError::last(call))
+    }
 }
 
 // Conditional Clone implementation when DbErr implements Clone
@@ -107,7 +288,15 @@ impl<DbErr: Clone> Clone for EvmAdapterError<DbErr> {
     fn clone(&self) -> Self {
         match self {
             Self::Db(e) => Self::Db(e.clone()),
-            Self::Ffi(name) => Self::Ffi(name),
+            Self::Ffi(e) => Self::Ffi(e.clone()),
+            Self::Transaction(e) => Self::Transaction(e.clone()),
+            Self::CodeHashMismatch { address, expected, computed } => {
+                Self::CodeHashMismatch { address: *address, expected: *expected, computed: *computed }
+            }
+            Self::AbiMismatch { expected, found } => Self::AbiMismatch { expected: *expected, found: *found },
+            Self::Unsupported(feature) => Self::Unsupported(feature),
+            Self::Cancelled => Self::Cancelled,
+            Self::DeadlineExceeded => Self::DeadlineExceeded,
         }
     }
 }
@@ -118,6 +307,17 @@ impl<DbErr: PartialEq> PartialEq for EvmAdapterError<DbErr> {
         match (self, other) {
             (Self::Db(a), Self::Db(b)) => a == b,
             (Self::Ffi(a), Self::Ffi(b)) => a == b,
+            (Self::Transaction(a), Self::Transaction(b)) => a == b,
+            (
+                Self::CodeHashMismatch { address: a1, expected: e1, computed: c1 },
+                Self::CodeHashMismatch { address: a2, expected: e2, computed: c2 },
+            ) => a1 == a2 && e1 == e2 && c1 == c2,
+            (Self::AbiMismatch { expected: e1, found: f1 }, Self::AbiMismatch { expected: e2, found: f2 }) => {
+                e1 == e2 && f1 == f2
+            }
+            (Self::Unsupported(a), Self::Unsupported(b)) => a == b,
+            (Self::Cancelled, Self::Cancelled) => true,
+            (Self::DeadlineExceeded, Self::DeadlineExceeded) => true,
             _ => false,
         }
     }
@@ -127,7 +327,17 @@ impl<DbErr: core::fmt::Debug> core::fmt::Display for EvmAdapterError<DbErr> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Db(e) => write!(f, "database error: {:?}", e),
-            Self::Ffi(name) => write!(f, "ffi call failed: {}", name),
+            Self::Ffi(e) => write!(f, "ffi call failed: {}", e),
+            Self::Transaction(e) => write!(f, "invalid transaction: {}", e),
+            Self::CodeHashMismatch { address, expected, computed } => {
+                write!(f, "code hash mismatch for {}: expected {}, computed {}", address, expected, computed)
+            }
+            Self::AbiMismatch { expected, found } => {
+                write!(f, "libguillotine_mini ABI version {} does not match bindings version {}", found, expected)
+            }
+            Self::Unsupported(feature) => write!(f, "libguillotine_mini does not support {}", feature),
+            Self::Cancelled => write!(f, "execution cancelled"),
+            Self::DeadlineExceeded => write!(f, "execution deadline exceeded"),
         }
     }
 }
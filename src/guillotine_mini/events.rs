@@ -0,0 +1,194 @@
+//! Streaming execution events
+//!
+//! [`GuillotineMiniEvm::transact_with_events`](super::evm::GuillotineMiniEvm::transact_with_events)
+//! sends [`ExecutionEvent`]s over a `std::sync::mpsc` channel while a transaction executes, so a
+//! UI or analyzer on another thread can follow a long transaction as it runs. Steps and call
+//! boundaries are sent live from the step callback; call boundaries are inferred from depth
+//! changes, so calls that run no code (precompiles, accounts without code) send none. A log is
+//! sent as soon as the step after its LOG opcode shows the opcode succeeded; a revert later in
+//! the transaction does not retract it. [`ExecutionEvent::Finished`] comes last.
+
+use super::{call_trace::CallKind, frame::FrameView, memory, step::StepInfo};
+use revm::{
+    bytecode::opcode,
+    context::result::ExecutionResult,
+    primitives::{Log, B256, U256},
+};
+use std::sync::mpsc::Sender;
+
+/// Event of an executing transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionEvent {
+    /// Opcode about to be executed
+    Step(StepInfo),
+    /// A call or creation started running code at `depth`
+    CallStart { kind: CallKind, depth: usize },
+    /// The call or creation at `depth` returned
+    CallEnd { depth: usize },
+    /// Log emitted by a LOG opcode, sent once the opcode succeeded, even if its call later reverts
+    Log(Log),
+    /// Execution completed; the last event of a transaction
+    Finished { success: bool, gas_used: u64 },
+}
+
+/// Translates step callbacks into events sent to a channel
+///
+/// Send errors are ignored: a receiver that hung up does not interrupt execution.
+#[derive(Debug)]
+pub(crate) struct EventEmitter {
+    events: Sender<ExecutionEvent>,
+    /// Depth and opcode of the previous step
+    last: Option<(usize, u8)>,
+    /// Log of the LOG opcode executed by the previous step, sent once it is known to have succeeded
+    pending_log: Option<Log>,
+}
+
+impl EventEmitter {
+    pub(crate) fn new(events: Sender<ExecutionEvent>) -> Self {
+        Self { events, last: None, pending_log: None }
+    }
+
+    fn send(&self, event: ExecutionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Send the events of an opcode step, preceded by the call boundaries crossed since the last
+    ///
+    /// `frame` is the frame executing the step; without it, no logs are sent.
+    pub(crate) fn step(&mut self, step: StepInfo, frame: Option<&FrameView<'_>>) {
+        if let Some((depth, op)) = self.last {
+            if let Some(log) = self.pending_log.take() {
+                // Back in the same frame, the LOG succeeded; back in the caller, it did unless the
+                // call failed, which leaves 0 on the caller's stack
+                let success = step.depth == depth || frame.and_then(|frame| frame.peek(0)) != Some(U256::ZERO);
+                if success {
+                    self.send(ExecutionEvent::Log(log));
+                }
+            }
+            if step.depth > depth {
+                let kind = call_kind(op).unwrap_or(CallKind::Call);
+                self.send(ExecutionEvent::CallStart { kind, depth: step.depth });
+            }
+            self.end_calls(depth, step.depth);
+        }
+        self.last = Some((step.depth, step.opcode));
+        if let Some(topics) = log_topics(step.opcode) {
+            self.pending_log = frame.and_then(|frame| log(frame, topics, step.gas_remaining));
+        }
+        self.send(ExecutionEvent::Step(step));
+    }
+
+    /// Send the end of every call from `from` down to, but excluding, `to`
+    fn end_calls(&self, from: usize, to: usize) {
+        for depth in (to + 1..=from).rev() {
+            self.send(ExecutionEvent::CallEnd { depth });
+        }
+    }
+
+    /// Send the log of a final LOG opcode, close the calls still open and send the outcome of the
+    /// transaction
+    pub(crate) fn finish(mut self, result: &ExecutionResult) {
+        if let Some(log) = self.pending_log.take().filter(|_| result.is_success()) {
+            self.send(ExecutionEvent::Log(log));
+        }
+        if let Some((depth, _)) = self.last.take() {
+            self.end_calls(depth, 0);
+        }
+        self.send(ExecutionEvent::Finished { success: result.is_success(), gas_used: result.gas_used() });
+    }
+}
+
+/// Number of topics of a LOG opcode
+fn log_topics(op: u8) -> Option<usize> {
+    (opcode::LOG0..=opcode::LOG4).contains(&op).then(|| usize::from(op - opcode::LOG0))
+}
+
+/// Log the LOG opcode about to execute on `frame` emits, read from its operands and memory
+///
+/// `None` if the data lies beyond what the frame's gas can expand memory to, so the LOG fails.
+fn log(frame: &FrameView<'_>, topics: usize, gas_remaining: u64) -> Option<Log> {
+    let offset = usize::try_from(frame.peek(0)?).ok();
+    let len = usize::try_from(frame.peek(1)?).ok()?;
+    let mut data = Vec::new();
+    if len > 0 {
+        let memory = frame.memory();
+        if offset?.checked_add(len)? > memory.len().max(memory::max_expansion(gas_remaining)) {
+            return None;
+        }
+        data = memory.read(offset?, len);
+        data.resize(len, 0);
+    }
+    let topics = (0..topics).map(|i| frame.peek(2 + i).map(B256::from)).collect::<Option<Vec<_>>>()?;
+    Some(Log::new_unchecked(frame.address(), topics, data.into()))
+}
+
+/// Kind of the call started by a call or create opcode
+fn call_kind(op: u8) -> Option<CallKind> {
+    match op {
+        opcode::CALL => Some(CallKind::Call),
+        opcode::CALLCODE => Some(CallKind::CallCode),
+        opcode::DELEGATECALL => Some(CallKind::DelegateCall),
+        opcode::STATICCALL => Some(CallKind::StaticCall),
+        opcode::CREATE => Some(CallKind::Create),
+        opcode::CREATE2 => Some(CallKind::Create2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::context::result::{Output, SuccessReason};
+    use revm::primitives::{Address, Bytes};
+    use std::sync::mpsc;
+
+    fn step(pc: usize, opcode: u8, depth: usize) -> StepInfo {
+        StepInfo { pc, opcode, gas_remaining: 0, depth, stack_len: 0 }
+    }
+
+    #[test]
+    fn test_emitter_infers_call_boundaries() {
+        let (sender, receiver) = mpsc::channel();
+        let mut emitter = EventEmitter::new(sender);
+        let steps = [step(0, opcode::STATICCALL, 0), step(0, opcode::STOP, 1), step(1, opcode::STOP, 0)];
+        for &s in &steps {
+            emitter.step(s, None);
+        }
+        let log = Log::new_unchecked(Address::with_last_byte(1), vec![], Bytes::new());
+        emitter.finish(&ExecutionResult::Success {
+            reason: SuccessReason::Stop,
+            gas_used: 21_000,
+            gas_refunded: 0,
+            logs: vec![log],
+            output: Output::Call(Bytes::new()),
+        });
+
+        let events: Vec<_> = receiver.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                ExecutionEvent::Step(steps[0]),
+                ExecutionEvent::CallStart { kind: CallKind::StaticCall, depth: 1 },
+                ExecutionEvent::Step(steps[1]),
+                ExecutionEvent::CallEnd { depth: 1 },
+                ExecutionEvent::Step(steps[2]),
+                ExecutionEvent::Finished { success: true, gas_used: 21_000 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emitter_ignores_disconnected_receiver() {
+        let (sender, receiver) = mpsc::channel();
+        drop(receiver);
+        let mut emitter = EventEmitter::new(sender);
+        emitter.step(step(0, opcode::STOP, 0), None);
+    }
+
+    #[test]
+    fn test_log_topics() {
+        assert_eq!(log_topics(opcode::LOG0), Some(0));
+        assert_eq!(log_topics(opcode::LOG4), Some(4));
+        assert_eq!(log_topics(opcode::SSTORE), None);
+    }
+}
@@ -7,40 +7,42 @@
 //!
 //! ## Storage Pre-State Synchronization
 //!
-//! Storage pre-state is now automatically synchronized for common storage slots (0-9) before
-//! execution. This covers most standard contracts (ERC20, ERC721, simple state machines), but
-//! has limitations:
+//! Accounts, code and storage slots that guillotine-mini does not hold are loaded on demand from
+//! the REVM database through host callbacks (see `set_host_callbacks`), so contracts read their
+//! full pre-state, including high-numbered slots and mapping entries.
 //!
-//! - **Simple contracts**: Work correctly (slots 0-9 cover most state variables)
-//! - **Complex contracts**: May miss storage values in high-numbered slots or dynamic mappings
-//! - **Large storage**: Only syncs slots 0-9, not all non-zero slots
+//! Before execution, the caller, the target, the slots in the transaction's access list
+//! (`AccessListSlots`) and fixed slots pushed as immediates before SLOAD/SSTORE in the called
+//! contract's bytecode (see `set_scan_bytecode`) are synced up front, which saves callback round
+//! trips for state that is known to be read.
 //!
-//! The current implementation is a temporary solution. Future improvements include:
-//! 1. EIP-2930 access list integration to sync exactly the slots that will be accessed
-//! 2. On-demand lazy loading via FFI callbacks (requires Zig changes)
-//! 3. Heuristics based on contract bytecode analysis
+//! Other strategies can be selected with `set_sync_strategy` (see the `sync_strategy` module):
+//! `CommonSlots` syncs slots 0-9 of the called contract, and `FullScan` syncs every slot cached
+//! in a `CacheDB`. With host callbacks disabled, only synced state is visible to execution.
 //!
-//! You can manually sync additional storage slots before execution using
+//! You can also manually sync additional storage slots before execution using
 //! [`database_bridge::sync_storage_to_ffi`](../database_bridge/fn.sync_storage_to_ffi.html) or
 //! [`database_bridge::sync_storage_slots_to_ffi`](../database_bridge/fn.sync_storage_slots_to_ffi.html).
 //!
-//! ## EIP-2930 Access Lists
+//! Alternatively, enable discovery mode with `set_discovery_mode(true)`: each transaction is
+//! dry-run first to learn every account and slot it touches, which are then synced before the
+//! real execution.
 //!
-//! Access list support (EIP-2930) is partially implemented in the FFI layer but not yet integrated
-//! into the high-level `transact` method. FFI functions exist (`evm_add_access_list_address`,
-//! `evm_add_access_list_storage`) but are not called during transaction execution.
+//! ## EIP-2930 Access Lists
 //!
-//! **Status**: Planned for future release
+//! From Berlin, the transaction's access list is passed to guillotine-mini before execution,
+//! together with the addresses that are warm by definition: precompiles and, from Shanghai, the
+//! coinbase (EIP-3651). The default `AccessListSlots` sync strategy also syncs the storage it
+//! lists.
 //!
 //! ## EIP-4844 Blob Transactions
 //!
-//! Blob transaction support (EIP-4844) is partially implemented:
+//! Blob transactions (EIP-4844) are supported:
 //!
 //! - Blob base fee is set in blockchain context
-//! - FFI functions exist for blob hash management
-//! - Not yet fully integrated into transaction processing
-//!
-//! **Status**: Under development
+//! - Blob versioned hashes are passed to guillotine-mini (BLOBHASH)
+//! - Blob gas used and excess blob gas are reported via `last_report()`
+//! - Blob fees are deducted from the sender at the block's blob gas price and burned
 //!
 //! ## CREATE2 Nonce Handling
 //!
@@ -51,8 +53,9 @@
 //! ## Error Recovery
 //!
 //! Catastrophic errors in the Zig layer (panic/unreachable) cause immediate process termination.
-//! These cannot be recovered in Rust. Normal execution errors (reverts, out of gas) are properly
-//! handled and returned as `ExecutionResult::Revert`.
+//! These cannot be recovered in Rust. Normal execution errors are properly handled: reverts are
+//! returned as `ExecutionResult::Revert`, and exceptional halts (out of gas, invalid opcode, stack
+//! overflow/underflow, invalid jump, call depth exceeded) as `ExecutionResult::Halt`.
 //!
 //! # Examples
 //!
@@ -75,6 +78,20 @@
 //! let result = evm.transact(tx).unwrap();
 //! ```
 //!
+//! ## Read-Only Databases
+//!
+//! `transact` needs a mutable `Database`. Immutable `DatabaseRef` implementations (e.g. a fork
+//! cache shared behind an `Arc`) can be used by wrapping them with `Context::with_ref_db`:
+//!
+//! ```rust,no_run
+//! use guillotine_rs::guillotine_mini::GuillotineMiniEvm;
+//! use revm::{database::{CacheDB, EmptyDB}, Context, MainContext};
+//! use std::sync::Arc;
+//!
+//! let shared = Arc::new(CacheDB::new(EmptyDB::default()));
+//! let mut evm = GuillotineMiniEvm::new(Context::mainnet().with_ref_db(Arc::clone(&shared)));
+//! ```
+//!
 //! ## Error Handling with try_new
 //!
 //! ```rust,no_run
@@ -84,217 +101,1227 @@
 //! let ctx = Context::mainnet();
 //! let evm = match GuillotineMiniEvm::try_new(ctx) {
 //!     Ok(evm) => evm,
-//!     Err(EvmAdapterError::Ffi(name)) => {
-//!         eprintln!("FFI call failed: {}", name);
+//!     Err(EvmAdapterError::Ffi(err)) => {
+//!         eprintln!("FFI call failed: {}", err);
 //!         return;
 //!     }
-//!     Err(EvmAdapterError::Db(e)) => {
-//!         eprintln!("Database error: {:?}", e);
+//!     Err(e) => {
+//!         eprintln!("Error: {}", e);
 //!         return;
 //!     }
 //! };
 //! ```
 
-use super::{database_bridge, error::EvmAdapterError, ffi, types};
+use super::{
+    access_list::{self, AccessListResult},
+    builder::EvmBuilder,
+    call_trace,
+    cancel::{self, CancellationToken},
+    code_cache::CodeCache,
+    database_bridge,
+    error::EvmAdapterError,
+    events::{EventEmitter, ExecutionEvent},
+    ffi,
+    frame::FrameView,
+    gas,
+    hardfork::Hardfork,
+    host::HostContext,
+    inspector::{self, ContextSlot, InspectorHooks},
+    memory::MemoryView,
+    options::TransactOptions,
+    report::{BridgeStats, ExecutionReport},
+    slot_scan,
+    spans::{self, Phase},
+    state_diff::StateDiff,
+    step::{self, StepInfo, StepObserver, StepResult},
+    struct_log::{self, DeepTraceLimits},
+    sync_strategy::{AccessListSlots, FullScan, SyncStrategy},
+    types, validation,
+};
 use revm::{
     context::{Cfg, Context, TxEnv},
-    context_interface::result::{ExecutionResult, Output, ResultAndState, SuccessReason},
-    database_interface::Database,
-    primitives::{hardfork::SpecId, Address, Bytes, TxKind, U256, B256, Log as RevmLog, LogData},
-    state::{Account, AccountInfo, AccountStatus, EvmState, EvmStorageSlot},
+    context_interface::result::{ExecutionResult, HaltReason, InvalidTransaction, Output, ResultAndState, SuccessReason},
+    database::CacheDB,
+    database_interface::{Database, DatabaseCommit, DatabaseRef},
+    inspector::Inspector,
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::{address, hardfork::SpecId, keccak256, Address, Bytes, TxKind, U256, B256},
+    state::{Account, AccountInfo, AccountStatus, Bytecode, EvmState},
 };
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "config-api")]
+use std::sync::Arc;
+use std::ffi::c_void;
+use std::sync::mpsc::Sender;
+use std::time::Instant;
 
 /// REVM-compatible EVM using guillotine-mini as the execution engine
+///
+/// `Send` when `CTX` is, so an EVM can be moved to another thread, but not `Sync`: the
+/// guillotine-mini instance it owns is not internally synchronized. Share one between threads
+/// with [`SharedEvm`](super::SharedEvm). REVM's default `LocalContext` holds an `Rc`, so a
+/// `Context` is only `Send` with a `Send` local context installed through `Context::with_local`;
+/// otherwise the EVM stays on its thread, and [`EvmPool`](super::EvmPool) builds each context on
+/// the thread executing it instead.
 pub struct GuillotineMiniEvm<CTX> {
     /// REVM context (contains database, config, transaction)
     pub ctx: CTX,
-    /// FFI handle to guillotine-mini EVM instance
-    handle: *mut ffi::EvmHandle,
+    /// guillotine-mini EVM instance (declared before `code_cache`, which must outlive it)
+    handle: ffi::OwnedEvmHandle,
+    /// Custom configuration every instance is created from (declared after `handle`, which it
+    /// must outlive)
+    #[cfg(feature = "config-api")]
+    config: Option<Arc<super::config::EvmConfig>>,
+    /// Engine parameters changed with `update_config`, applied to every instance created
+    #[cfg(feature = "config-api")]
+    runtime_config: Option<super::config::RuntimeConfig>,
+    /// Supplementary information about the last `transact` call
+    last_report: ExecutionReport,
+    /// Hardfork the handle was created with (used to recreate it on reset)
+    hardfork: Hardfork,
+    /// Log level the handle was created with, `None` for the default (used to recreate it)
+    log_level: Option<u8>,
+    /// Keep guillotine-mini state between `transact` calls instead of starting fresh
+    persist_state: bool,
+    /// Dry-run transactions to discover and sync their full pre-state before executing
+    discovery_mode: bool,
+    /// Reject accounts whose code does not match their code hash when syncing
+    verify_code_hashes: bool,
+    /// Whether the handle holds state from a previous execution
+    dirty: bool,
+    /// Accounts whose current state already lives in guillotine-mini
+    synced_accounts: HashSet<Address>,
+    /// Storage slots whose current value already lives in guillotine-mini
+    synced_slots: HashSet<(Address, U256)>,
+    /// Block number whose BLOCKHASH window was last synced to guillotine-mini
+    synced_block_hashes: Option<u64>,
+    /// Hash of the code guillotine-mini holds for each account, to skip re-sending unchanged code
+    synced_code: HashMap<Address, B256>,
+    /// Bytecode analysis cache shared by every instance this EVM creates
+    code_cache: Option<CodeCache>,
+    /// Chooses the storage slots synced before each execution
+    sync_strategy: Box<dyn SyncStrategy>,
+    /// Also sync fixed slots found by scanning the called contract's bytecode
+    scan_bytecode: bool,
+    /// Sync counters for the last execution, when enabled
+    bridge_stats: Option<BridgeStats>,
+    /// Load state missing from guillotine-mini on demand during execution
+    host_callbacks: bool,
+    /// Record a struct log of every execution in guillotine-mini
+    native_tracing: bool,
+    /// Also record memory with every struct log, within these limits
+    deep_tracing: Option<DeepTraceLimits>,
+    /// ORIGIN reported instead of the transaction's caller
+    tx_origin: Option<Address>,
+    /// Top up senders that cannot cover the value and maximum gas fee instead of rejecting
+    disable_balance_check: bool,
+    /// Accept transactions whose nonce does not match the sender's
+    disable_nonce_check: bool,
+    /// Aborts executions when cancelled from another thread
+    cancellation: CancellationToken,
+    /// Memory limit of the transaction being executed, from its `TransactOptions`
+    memory_limit: Option<u64>,
+    /// Deadline of the transaction being executed, from its `TransactOptions`
+    deadline: Option<Instant>,
+}
+
+impl<CTX> GuillotineMiniEvm<CTX> {
+    /// Report for the most recent `transact` call
+    ///
+    /// Check `has_anomalies()` to detect impossible gas values reported by the engine
+    /// (in debug builds these also trigger a `debug_assert!`).
+    pub fn last_report(&self) -> &ExecutionReport {
+        &self.last_report
+    }
+
+    /// Hardfork guillotine-mini executes under, derived from the context's spec
+    pub fn hardfork(&self) -> Hardfork {
+        self.hardfork
+    }
+
+    /// Whether guillotine-mini state is carried over between `transact` calls
+    pub fn persist_state(&self) -> bool {
+        self.persist_state
+    }
+
+    /// Choose between fresh and carried-over guillotine-mini state
+    ///
+    /// By default (`false`) every `transact` starts from a fresh guillotine-mini instance and
+    /// sees only the pre-state synced from the REVM database. With `true`, storage, balances and
+    /// code written by previous transactions remain visible in guillotine-mini, even when they
+    /// were never committed to the database. Use [`reset_state`](Self::reset_state) to discard
+    /// carried-over state explicitly.
+    pub fn set_persist_state(&mut self, persist: bool) {
+        self.persist_state = persist;
+    }
+
+    /// Whether `transact` runs a pre-state discovery pass before executing
+    pub fn discovery_mode(&self) -> bool {
+        self.discovery_mode
+    }
+
+    /// Enable or disable pre-state discovery
+    ///
+    /// Host callbacks already load missing state during execution; discovery is useful when
+    /// they are disabled (see `set_host_callbacks`), where only the caller, the target and the
+    /// slots chosen by the sync strategy are synced to guillotine-mini. With discovery enabled,
+    /// `transact` first dry-runs the transaction on a scratch instance to record every account
+    /// and storage slot it touches, syncs those from the REVM database and repeats until no new
+    /// state is discovered (at most [`MAX_DISCOVERY_PASSES`] dry runs), then executes for real.
+    /// This makes transactions that fan out to many contracts (routers, proxies) correct at the
+    /// cost of extra executions.
+    pub fn set_discovery_mode(&mut self, enabled: bool) {
+        self.discovery_mode = enabled;
+    }
+
+    /// Replace the storage sync strategy (the access list slots by default)
+    ///
+    /// See [`sync_strategy`](super::sync_strategy) for the built-in strategies; closures
+    /// `Fn(&TxEnv, Address) -> SlotPlan` can be used for custom ones. With host callbacks
+    /// enabled the strategy only prefetches slots; without them it decides which slots are
+    /// visible to execution, and `CommonSlots` or `FullScan` are the usual choices.
+    pub fn set_sync_strategy(&mut self, strategy: impl SyncStrategy + 'static) {
+        self.sync_strategy = Box::new(strategy);
+    }
+
+    /// Whether fixed storage slots found in the called contract's bytecode are synced
+    pub fn scan_bytecode(&self) -> bool {
+        self.scan_bytecode
+    }
+
+    /// Enable or disable syncing the fixed slots found by scanning bytecode (enabled by default)
+    ///
+    /// Slots pushed as immediates right before SLOAD/SSTORE are synced in addition to those
+    /// chosen by the sync strategy; see [`slot_scan`](super::slot_scan).
+    pub fn set_scan_bytecode(&mut self, enabled: bool) {
+        self.scan_bytecode = enabled;
+    }
+
+    /// Whether missing state is loaded on demand through host callbacks
+    pub fn host_callbacks(&self) -> bool {
+        self.host_callbacks
+    }
+
+    /// Enable or disable loading missing state on demand (enabled by default)
+    ///
+    /// When enabled, guillotine-mini asks the REVM database for every account, code and storage
+    /// slot it reads that was not synced before execution. When disabled, unsynced accounts
+    /// appear empty and unsynced slots read as zero.
+    pub fn set_host_callbacks(&mut self, enabled: bool) {
+        self.host_callbacks = enabled;
+    }
+
+    /// Whether guillotine-mini records a native trace of every execution
+    pub fn native_tracing(&self) -> bool {
+        self.native_tracing
+    }
+
+    /// Enable or disable native tracing (disabled by default)
+    ///
+    /// When enabled, guillotine-mini records a struct log of every executed opcode, available in
+    /// [`ExecutionReport::struct_logs`] after execution. Unlike a step observer this costs no FFI
+    /// call per opcode.
+    pub fn set_native_tracing(&mut self, enabled: bool) {
+        self.native_tracing = enabled;
+    }
+
+    /// Limits of deep tracing, if enabled
+    pub fn deep_tracing(&self) -> Option<DeepTraceLimits> {
+        self.deep_tracing
+    }
+
+    /// Enable deep tracing within `limits`, or disable it with `None` (disabled by default)
+    ///
+    /// Deep tracing records native traces like [`set_native_tracing`](Self::set_native_tracing)
+    /// does, whether or not it is enabled, with a window of memory from offset 0 in
+    /// [`StructLog::memory`](super::StructLog::memory) and the stack cut to its top-most items.
+    /// Recording memory at every opcode is costly; enable it to debug a failing fixture.
+    pub fn set_deep_tracing(&mut self, limits: Option<DeepTraceLimits>) {
+        self.deep_tracing = limits;
+    }
+
+    /// Whether guillotine-mini records struct logs of the next execution
+    fn records_struct_logs(&self) -> bool {
+        self.native_tracing || self.deep_tracing.is_some()
+    }
+
+    /// Address reported by ORIGIN, if overridden
+    pub fn tx_origin(&self) -> Option<Address> {
+        self.tx_origin
+    }
+
+    /// Override the address reported by ORIGIN (`None`, the default, reports the caller)
+    ///
+    /// For executing an inner frame or an impersonated call on its own, where the caller is not
+    /// the account that originated the transaction. Validation, fees and nonces still apply to
+    /// the caller.
+    pub fn set_tx_origin(&mut self, origin: Option<Address>) {
+        self.tx_origin = origin;
+    }
+
+    /// Whether `transact` tops up senders instead of checking their balance
+    pub fn balance_check_disabled(&self) -> bool {
+        self.disable_balance_check
+    }
+
+    /// Skip the sender balance check in `transact`, like REVM's `disable_balance_check`
+    /// (disabled by default)
+    ///
+    /// A sender that cannot cover the value and maximum gas fee is topped up to exactly that
+    /// amount before executing, as [`call`](GuillotineMiniEvm::call) does, so simulations from
+    /// arbitrary senders succeed without funding them first. The top-up is part of the
+    /// post-state. The context's `disable_balance_check` has the same effect.
+    pub fn set_disable_balance_check(&mut self, disabled: bool) {
+        self.disable_balance_check = disabled;
+    }
+
+    /// Whether `transact` accepts any sender nonce
+    pub fn nonce_check_disabled(&self) -> bool {
+        self.disable_nonce_check
+    }
+
+    /// Skip the sender nonce check in `transact`, like REVM's `disable_nonce_check` (disabled
+    /// by default)
+    ///
+    /// The transaction's nonce is ignored: contracts it creates are derived from, and the sender
+    /// ends with, its current nonce plus one. The context's `disable_nonce_check` has the same
+    /// effect.
+    pub fn set_disable_nonce_check(&mut self, disabled: bool) {
+        self.disable_nonce_check = disabled;
+    }
+
+    /// Token cancelling this EVM's executions, to hand to another thread
+    ///
+    /// Once cancelled, the running transaction and every later one fail with
+    /// `EvmAdapterError::Cancelled`.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Replace the cancellation token, e.g. with a fresh one after cancelling or with one shared
+    /// by several EVMs
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    /// Sync counters for the most recent `transact` or `call`, if enabled
+    pub fn bridge_stats(&self) -> Option<&BridgeStats> {
+        self.bridge_stats.as_ref()
+    }
+
+    /// Enable or disable collecting [`BridgeStats`] (disabled by default)
+    pub fn set_bridge_stats(&mut self, enabled: bool) {
+        self.bridge_stats = enabled.then(BridgeStats::default);
+    }
+
+    /// Wrap an existing guillotine-mini instance created for `hardfork`, with default settings
+    pub(crate) fn from_handle(ctx: CTX, handle: ffi::OwnedEvmHandle, hardfork: Hardfork) -> Self {
+        Self {
+            ctx,
+            handle,
+            #[cfg(feature = "config-api")]
+            config: None,
+            #[cfg(feature = "config-api")]
+            runtime_config: None,
+            last_report: ExecutionReport::default(),
+            hardfork,
+            log_level: None,
+            persist_state: false,
+            discovery_mode: false,
+            verify_code_hashes: false,
+            dirty: false,
+            synced_accounts: HashSet::new(),
+            synced_slots: HashSet::new(),
+            synced_block_hashes: None,
+            synced_code: HashMap::new(),
+            code_cache: None,
+            sync_strategy: Box::new(AccessListSlots),
+            scan_bytecode: true,
+            bridge_stats: None,
+            host_callbacks: true,
+            native_tracing: false,
+            deep_tracing: None,
+            tx_origin: None,
+            disable_balance_check: false,
+            disable_nonce_check: false,
+            cancellation: CancellationToken::new(),
+            memory_limit: None,
+            deadline: None,
+        }
+    }
+
+    /// Log level the handle was created with, reused for instances created internally later
+    pub(crate) fn set_log_level(&mut self, log_level: Option<u8>) {
+        self.log_level = log_level;
+    }
+
+    /// Replace the sync strategy with an already boxed one
+    pub(crate) fn set_boxed_sync_strategy(&mut self, strategy: Box<dyn SyncStrategy>) {
+        self.sync_strategy = strategy;
+    }
+
+    /// Clear the guillotine-mini instance and hand it back for reuse by another EVM
+    pub(crate) fn into_handle(mut self) -> Result<ffi::OwnedEvmHandle, &'static str> {
+        self.clear_handle()?;
+        Ok(self.handle)
+    }
+
+    /// Custom configuration the EVM creates its instances from, if any
+    #[cfg(feature = "config-api")]
+    pub fn config(&self) -> Option<&Arc<super::config::EvmConfig>> {
+        self.config.as_ref()
+    }
+
+    /// Create later instances from `config`; the current instance must have been created from
+    /// it too
+    #[cfg(feature = "config-api")]
+    pub(crate) fn set_config(&mut self, config: Option<Arc<super::config::EvmConfig>>) {
+        self.config = config;
+    }
+
+    /// Engine parameters of the EVM's instances, as changed with `update_config`
+    #[cfg(feature = "config-api")]
+    pub fn runtime_config(&self) -> super::config::RuntimeConfig {
+        self.runtime_config.unwrap_or_else(|| {
+            let settings = self.config.as_ref().map(|config| config.settings().clone()).unwrap_or_default();
+            super::config::RuntimeConfig::from_settings(&settings, self.hardfork)
+        })
+    }
+
+    /// Fresh guillotine-mini instance, attached to the code cache when enabled
+    fn new_handle(&self) -> Result<ffi::OwnedEvmHandle, &'static str> {
+        #[cfg(feature = "config-api")]
+        let handle = match &self.config {
+            Some(config) => ffi::OwnedEvmHandle::with_config(config.as_raw(), self.log_level)?,
+            None => ffi::OwnedEvmHandle::with_log_level(self.hardfork, self.log_level)?,
+        };
+        #[cfg(feature = "config-api")]
+        if let Some(runtime) = &self.runtime_config {
+            // Only set by `update_config`, which checked for the config ABI
+            let api = ffi::config_api().ok_or("evm_set_block_gas_limit")?;
+            runtime.apply(api, handle.as_ptr())?;
+        }
+        #[cfg(not(feature = "config-api"))]
+        let handle = ffi::OwnedEvmHandle::with_log_level(self.hardfork, self.log_level)?;
+        if let Some(cache) = &self.code_cache {
+            cache.attach(handle.as_ptr())?;
+        }
+        Ok(handle)
+    }
+
+    /// Clear guillotine-mini state in place and forget what was synced to it
+    ///
+    /// Cheaper than creating a new instance; the hardfork and attached code cache are kept.
+    fn clear_handle(&mut self) -> Result<(), &'static str> {
+        if !unsafe { ffi::evm_reset(self.handle.as_ptr()) } {
+            return Err("evm_reset");
+        }
+        self.dirty = false;
+        self.synced_accounts.clear();
+        self.synced_slots.clear();
+        self.synced_block_hashes = None;
+        self.synced_code.clear();
+        Ok(())
+    }
+
+    /// Swap in a fresh guillotine-mini instance and forget what was synced to the old one
+    fn replace_handle(&mut self) -> Result<(), &'static str> {
+        self.handle = self.new_handle()?;
+        self.dirty = false;
+        self.synced_accounts.clear();
+        self.synced_slots.clear();
+        self.synced_block_hashes = None;
+        self.synced_code.clear();
+        Ok(())
+    }
+
+    /// Execute under `hardfork` from now on, swapping in a fresh instance if it changes
+    pub(crate) fn switch_hardfork(&mut self, hardfork: Hardfork) -> Result<(), &'static str> {
+        if hardfork != self.hardfork {
+            self.hardfork = hardfork;
+            self.replace_handle()?;
+        }
+        Ok(())
+    }
+
+    /// Whether calls to `address` run a precompile of the EVM's instances rather than its code,
+    /// taking the configuration's precompile changes into account
+    fn is_precompile(&self, address: Address) -> bool {
+        #[cfg(feature = "config-api")]
+        if let Some(config) = &self.config {
+            return config.is_precompile(address, self.hardfork);
+        }
+        Precompiles::new(PrecompileSpecId::from_spec_id(self.hardfork.spec())).contains(&address)
+    }
+
+    /// Update the sync counters when collecting them
+    fn record(&mut self, update: impl FnOnce(&mut BridgeStats)) {
+        if let Some(stats) = &mut self.bridge_stats {
+            update(stats);
+        }
+    }
+
+    /// Bytecode analysis cache, if enabled with `enable_code_cache`
+    pub fn code_cache(&self) -> Option<&CodeCache> {
+        self.code_cache.as_ref()
+    }
+
+    /// Whether synced accounts have their code checked against their code hash
+    pub fn verify_code_hashes(&self) -> bool {
+        self.verify_code_hashes
+    }
+
+    /// Enable or disable code hash verification during account sync
+    ///
+    /// When enabled, syncing an account whose code does not hash to its `code_hash` fails with
+    /// [`EvmAdapterError::CodeHashMismatch`] instead of executing against inconsistent state.
+    pub fn set_verify_code_hashes(&mut self, verify: bool) {
+        self.verify_code_hashes = verify;
+    }
 }
 
-impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN> GuillotineMiniEvm<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>>
+/// Maximum number of dry runs performed by discovery mode before executing a transaction
+pub const MAX_DISCOVERY_PASSES: usize = 4;
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> GuillotineMiniEvm<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>
 where
     BLOCK: revm::context_interface::Block,
     TX: revm::context_interface::Transaction,
     CFG: Cfg<Spec = SpecId>,
     DB: Database,
     JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+    LOCAL: revm::context_interface::LocalContextTr,
 {
     /// Create new GuillotineMiniEvm from REVM context
-    pub fn new(ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>) -> Self {
-        // Map REVM SpecId to hardfork name
-        let hardfork_name = match ctx.cfg.spec() {
-            SpecId::FRONTIER | SpecId::FRONTIER_THAWING => "Frontier",
-            SpecId::HOMESTEAD | SpecId::DAO_FORK => "Homestead",
-            SpecId::TANGERINE => "Tangerine",
-            SpecId::SPURIOUS_DRAGON => "Spurious",
-            SpecId::BYZANTIUM => "Byzantium",
-            SpecId::CONSTANTINOPLE | SpecId::PETERSBURG => "Constantinople",
-            SpecId::ISTANBUL | SpecId::MUIR_GLACIER => "Istanbul",
-            SpecId::BERLIN => "Berlin",
-            SpecId::LONDON | SpecId::ARROW_GLACIER | SpecId::GRAY_GLACIER => "London",
-            SpecId::MERGE => "Merge",
-            SpecId::SHANGHAI => "Shanghai",
-            SpecId::CANCUN => "Cancun",
-            SpecId::PRAGUE => "Prague",
-            SpecId::OSAKA => "Osaka",
-            _ => "Cancun", // Default to Cancun
+    ///
+    /// Panics if the linked libguillotine_mini has a different ABI version or the instance
+    /// cannot be created; see [`try_new`](Self::try_new).
+    pub fn new(ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>) -> Self {
+        match Self::try_new(ctx) {
+            Ok(evm) => evm,
+            Err(EvmAdapterError::AbiMismatch { expected, found }) => {
+                panic!("libguillotine_mini ABI version {} does not match bindings version {}", found, expected)
+            }
+            Err(e) => panic!("Failed to create guillotine-mini EVM: {}", e),
+        }
+    }
+
+    /// Fallible constructor that returns a proper error instead of panicking
+    ///
+    /// Fails with [`EvmAdapterError::AbiMismatch`] if the linked libguillotine_mini implements a
+    /// different C ABI version than these bindings. Shorthand for
+    /// `GuillotineMiniEvm::builder().ctx(ctx).build()`.
+    pub fn try_new(
+        ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>,
+    ) -> Result<Self, EvmAdapterError<DB::Error>> {
+        EvmBuilder::new().ctx(ctx).build()
+    }
+
+    /// Create new GuillotineMiniEvm with custom configuration
+    ///
+    /// Requires the `config-api` feature. Fails with `EvmAdapterError::Unsupported("config")` if
+    /// the linked libguillotine_mini does not export the config ABI. Instances created internally
+    /// later (e.g. the scratch instance of [`Self::estimate_gas`]) use the same configuration.
+    /// Shorthand for `GuillotineMiniEvm::builder().ctx(ctx).config(config).build()`.
+    ///
+    /// # Arguments
+    /// * `ctx` - REVM context
+    /// * `config` - Custom EVM configuration, owned or an `Arc` shared with other EVMs
+    ///
+    /// # Example
+    /// ```ignore
+    /// use guillotine_rs::guillotine_mini::{GuillotineMiniEvm, EvmConfigBuilder};
+    /// use revm::{primitives::hardfork::SpecId, Context};
+    ///
+    /// let config = EvmConfigBuilder::new()
+    ///     .hardfork(SpecId::CANCUN)
+    ///     .stack_size(512)
+    ///     .build();
+    ///
+    /// let evm = GuillotineMiniEvm::with_config(ctx, config).unwrap();
+    /// ```
+    #[cfg(feature = "config-api")]
+    pub fn with_config(
+        ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>,
+        config: impl Into<Arc<super::config::EvmConfig>>,
+    ) -> Result<Self, EvmAdapterError<DB::Error>> {
+        EvmBuilder::new().ctx(ctx).config(config).build()
+    }
+
+    /// Discard all guillotine-mini state accumulated by previous executions
+    ///
+    /// The underlying instance is cleared in place and reused, keeping its hardfork and code
+    /// cache.
+    pub fn reset_state(&mut self) -> Result<(), EvmAdapterError<DB::Error>> {
+        self.clear_handle().map_err(EvmAdapterError::ffi)
+    }
+
+    /// Enable the bytecode analysis cache
+    ///
+    /// Contract code is then registered once per code hash and reused by every later
+    /// transaction, instead of being copied and analyzed again each time it is synced.
+    pub fn enable_code_cache(&mut self) -> Result<(), EvmAdapterError<DB::Error>> {
+        if self.code_cache.is_none() {
+            let cache = CodeCache::new().map_err(EvmAdapterError::ffi)?;
+            cache.attach(self.handle.as_ptr()).map_err(EvmAdapterError::ffi)?;
+            self.code_cache = Some(cache);
+        }
+        Ok(())
+    }
+
+    /// Execute a sequence of transactions with cumulative state
+    ///
+    /// The block starts from fresh guillotine-mini state which is then carried over between
+    /// transactions, so each transaction observes the effects of the previous ones without them
+    /// being committed to the database. Accounts and storage slots are synced from the database
+    /// at most once per block and the FFI handle is reused throughout.
+    ///
+    /// Storage `original_value`s come from guillotine-mini's journal and reflect the value at
+    /// the start of each transaction, as under revm.
+    pub fn transact_block(&mut self, txs: Vec<TxEnv>) -> Result<Vec<ResultAndState>, EvmAdapterError<DB::Error>> {
+        let persist_state = self.persist_state;
+        self.reset_state()?;
+        self.persist_state = true;
+
+        let results = txs.into_iter().map(|tx| self.transact(tx)).collect();
+
+        self.persist_state = persist_state;
+        results
+    }
+
+    /// Execute a transaction using guillotine-mini
+    ///
+    /// Unless [`set_persist_state(true)`](Self::set_persist_state) was called, execution starts
+    /// from fresh guillotine-mini state populated only from the REVM database.
+    pub fn transact(&mut self, tx: TxEnv) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        self.transact_sharing_ctx(tx, None)
+    }
+
+    /// [`transact`](Self::transact), publishing the context in `ctx_slot` while execution runs
+    fn transact_sharing_ctx(
+        &mut self,
+        tx: TxEnv,
+        ctx_slot: Option<&ContextSlot>,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        let _span = spans::transact(&tx);
+        self.begin_transact(&tx)?;
+        let result_and_state = self.execute_tx(tx, ctx_slot)?;
+        self.persist_post_state(&result_and_state)?;
+        Ok(result_and_state)
+    }
+
+    /// Execute a transaction with `options` overriding the EVM's settings for this call only
+    ///
+    /// Behaves like [`transact`](Self::transact) with the memory limit, native tracing and sync
+    /// strategy of `options` in place of the EVM's own; the EVM's settings are restored
+    /// afterwards, whatever the outcome. Execution running past the deadline of `options` is
+    /// interrupted and fails with [`EvmAdapterError::DeadlineExceeded`].
+    pub fn transact_with_options(
+        &mut self,
+        tx: TxEnv,
+        options: TransactOptions,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        let TransactOptions { memory_limit, tracing, sync_strategy, deadline } = options;
+        let native_tracing = tracing.map(|enabled| std::mem::replace(&mut self.native_tracing, enabled));
+        let sync_strategy = sync_strategy.map(|strategy| std::mem::replace(&mut self.sync_strategy, strategy));
+        self.memory_limit = memory_limit;
+        self.deadline = deadline;
+
+        let result = self.transact(tx);
+
+        self.memory_limit = None;
+        self.deadline = None;
+        if let Some(native_tracing) = native_tracing {
+            self.native_tracing = native_tracing;
+        }
+        if let Some(sync_strategy) = sync_strategy {
+            self.sync_strategy = sync_strategy;
+        }
+        result
+    }
+
+    /// Change engine parameters between blocks without recreating the guillotine-mini instance
+    ///
+    /// `update` edits the current [`RuntimeConfig`](super::config::RuntimeConfig), which applies
+    /// to the current instance in place and to every instance the EVM creates later, so state
+    /// kept by `set_persist_state` and the code cache survive. Switching hardfork keeps it too.
+    ///
+    /// Fails with `EvmAdapterError::Unsupported("config")` if the linked libguillotine_mini does
+    /// not export the config ABI.
+    #[cfg(feature = "config-api")]
+    pub fn update_config(
+        &mut self,
+        update: impl FnOnce(&mut super::config::RuntimeConfig),
+    ) -> Result<(), EvmAdapterError<DB::Error>> {
+        let api = ffi::config_api().ok_or(EvmAdapterError::Unsupported("config"))?;
+        let mut runtime = self.runtime_config();
+        update(&mut runtime);
+        runtime.apply(api, self.handle.as_ptr()).map_err(EvmAdapterError::ffi)?;
+        self.runtime_config = Some(runtime);
+        Ok(())
+    }
+
+    /// Prepare a transaction to be executed one opcode at a time
+    ///
+    /// The transaction is validated and its pre-state synced exactly like
+    /// [`transact`](Self::transact), then nothing runs until [`Stepper::step`] is called. Use
+    /// [`Stepper::finish`] to run the remaining opcodes and obtain the same `ResultAndState`
+    /// `transact` would return. Plain value transfers execute no code: their stepper is
+    /// finished from the start.
+    pub fn stepper(&mut self, tx: TxEnv) -> StepperResult<'_, BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> {
+        self.begin_transact(&tx)?;
+        let prepared = self.prepare_tx(tx)?;
+        let finished = matches!(prepared, Prepared::Done(_));
+        Ok(Stepper { evm: self, prepared: Some(prepared), finished })
+    }
+
+    /// Reset, validate and discover the pre-state of a transaction about to be executed
+    fn begin_transact(&mut self, tx: &TxEnv) -> Result<(), EvmAdapterError<DB::Error>> {
+        self.record(|stats| *stats = BridgeStats::default());
+        if self.dirty && !self.persist_state {
+            self.reset_state()?;
+        }
+
+        let mut caller_info = self.load_account_info(tx.caller)?;
+        if self.disable_nonce_check {
+            caller_info.nonce = tx.nonce;
+        }
+        let skip_balance_check = self.disable_balance_check || self.ctx.cfg.is_balance_check_disabled();
+        if skip_balance_check {
+            caller_info.balance = caller_info.balance.max(validation::max_tx_cost(tx));
+        }
+        let check_base_fee = !self.ctx.cfg.is_base_fee_check_disabled();
+        #[cfg(feature = "config-api")]
+        let check_base_fee = check_base_fee && !self.runtime_config.is_some_and(|runtime| runtime.disable_base_fee);
+        validation::validate_tx_with_base_fee_check(&self.ctx.cfg, &self.ctx.block, tx, &caller_info, check_base_fee)
+            .map_err(EvmAdapterError::Transaction)?;
+
+        if self.discovery_mode {
+            self.discover_pre_state(tx)?;
+        }
+        if skip_balance_check {
+            self.top_up_caller(tx)?;
+        }
+        Ok(())
+    }
+
+    /// Mirror the settled post-state (fees, nonces, transfers) into guillotine-mini so the
+    /// next transaction continues from it
+    fn persist_post_state(&mut self, result_and_state: &ResultAndState) -> Result<(), EvmAdapterError<DB::Error>> {
+        if self.persist_state {
+            for (addr, account) in &result_and_state.state {
+                self.write_account_info(*addr, account.info.clone())?;
+                self.synced_accounts.insert(*addr);
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute a transaction, reporting every opcode step to `observer`
+    ///
+    /// Behaves exactly like [`transact`](Self::transact) but invokes `observer` before each
+    /// opcode is executed. Intended for ad-hoc debugging (e.g. locating divergences from REVM)
+    /// without the native tracing [`inspect`](Self::inspect) relies on. Plain value transfers
+    /// execute no code and produce no steps.
+    ///
+    /// The observer is called across the FFI boundary and must not panic.
+    pub fn transact_with_observer<F>(
+        &mut self,
+        tx: TxEnv,
+        mut observer: F,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>>
+    where
+        F: FnMut(StepInfo),
+    {
+        let mut observer: StepObserver<'_> = &mut observer;
+        let observer = &mut observer as *mut StepObserver<'_> as *mut c_void;
+        self.transact_with_step_callback(tx, step::step_trampoline, observer, None)
+    }
+
+    /// Execute a transaction with `handler` registered as the step callback, receiving `data`,
+    /// and the context published in `ctx_slot` while execution runs
+    fn transact_with_step_callback(
+        &mut self,
+        tx: TxEnv,
+        handler: ffi::FfiStepHandler,
+        data: *mut c_void,
+        ctx_slot: Option<&ContextSlot>,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        // Reset up front so the callback is registered on the handle that executes
+        if self.dirty && !self.persist_state {
+            self.reset_state()?;
+        }
+
+        unsafe {
+            ffi::evm_set_step_callback(self.handle.as_ptr(), Some(handler), data);
+        }
+
+        let result = self.transact_sharing_ctx(tx, ctx_slot);
+
+        unsafe {
+            ffi::evm_set_step_callback(self.handle.as_ptr(), None, std::ptr::null_mut());
+        }
+        result
+    }
+
+    /// Execute a transaction, streaming its [`ExecutionEvent`]s to `events` as it runs
+    ///
+    /// Behaves like [`transact_with_observer`](Self::transact_with_observer), sending every step
+    /// and call boundary while execution runs and the logs and outcome once it completes; see the
+    /// [`events`](super::events) module for what is sent when. A receiver that hung up does not
+    /// interrupt execution. Transactions failing validation send no events; the channel
+    /// disconnects when this returns unless `events` was cloned.
+    pub fn transact_with_events(
+        &mut self,
+        tx: TxEnv,
+        events: Sender<ExecutionEvent>,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        let mut emitter = EventEmitter::new(events);
+        let handle = self.handle.as_ptr();
+        let result_and_state = self.transact_with_observer(tx, |step| {
+            // SAFETY: inside the step callback the current frame stays valid until it returns
+            let frame = unsafe { FrameView::from_raw(ffi::evm_get_current_frame(handle)) };
+            emitter.step(step, frame.as_ref())
+        })?;
+        emitter.finish(&result_and_state.result);
+        Ok(result_and_state)
+    }
+
+    /// Execute a transaction, driving a REVM `Inspector` while it runs
+    ///
+    /// Behaves like [`transact`](Self::transact), invoking the hooks of `inspector` (`call`,
+    /// `create`, `initialize_interp`, `step`, `step_end`, `log`, `selfdestruct` and their `_end`
+    /// counterparts) from the step callback in the order REVM would. Changes `step` and
+    /// `step_end` make to the interpreter's stack, memory and gas apply to execution; see the
+    /// [`inspector`](super::inspector) module for what the interpreters carry and which results
+    /// are ignored. Transactions failing validation invoke no hooks, and a panicking inspector
+    /// stops execution before the panic resumes.
+    pub fn inspect<I>(&mut self, tx: TxEnv, inspector: &mut I) -> Result<ResultAndState, EvmAdapterError<DB::Error>>
+    where
+        I: Inspector<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>,
+    {
+        // Reset up front so the hooks follow the handle that executes
+        if self.dirty && !self.persist_state {
+            self.reset_state()?;
+        }
+
+        let spec = self.ctx.cfg.spec();
+        // Execution publishes the context here while it runs, and the host callbacks reach the
+        // database through the same pointer, so no borrow of the EVM is held across a hook
+        let ctx_slot: ContextSlot = Cell::new(std::ptr::null_mut());
+        let mut hooks = unsafe { InspectorHooks::new(inspector, &ctx_slot, self.handle.as_ptr(), spec, &tx) };
+        let trampoline = inspector::inspector_trampoline::<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>, I>;
+        let data = &mut hooks as *mut InspectorHooks<'_, _, I> as *mut c_void;
+        let result = self.transact_with_step_callback(tx, trampoline, data, Some(&ctx_slot));
+
+        if let Some(payload) = hooks.panic.take() {
+            std::panic::resume_unwind(payload);
+        }
+        let result_and_state = result?;
+        // Hooks due after execution run on a pointer from the EVM's borrow that is current now
+        ctx_slot.set(std::ptr::addr_of_mut!(self.ctx).cast());
+        hooks.finish(&result_and_state.result);
+        Ok(result_and_state)
+    }
+
+    /// Execute a read-only call (`eth_call` semantics)
+    ///
+    /// The transaction runs on a scratch guillotine-mini instance against the database
+    /// pre-state, so neither the database nor state carried over in persist mode is touched.
+    /// The sender's nonce is not checked and its balance is topped up if it cannot cover the
+    /// value and maximum gas fee, which makes this suitable for view calls and simulations.
+    pub fn call(&mut self, tx: TxEnv) -> Result<ExecutionResult, EvmAdapterError<DB::Error>> {
+        self.record(|stats| *stats = BridgeStats::default());
+        self.with_scratch_handle(|evm| evm.call_inner(tx))
+    }
+
+    /// Generate an EIP-2930 access list for `tx` (`eth_createAccessList` semantics)
+    ///
+    /// Runs the transaction as a read-only [`call`](Self::call) while guillotine-mini tracks
+    /// every address and storage slot accessed, then runs it again with the resulting access
+    /// list attached to report the gas it would use. The sender, the recipient (or created
+    /// contract), the coinbase and precompiles are only listed when their storage was accessed,
+    /// since they are warm anyway.
+    pub fn create_access_list(&mut self, tx: TxEnv) -> Result<AccessListResult, EvmAdapterError<DB::Error>> {
+        let precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(self.ctx.cfg.spec()));
+
+        let traced = tx.clone();
+        let access_list = self.with_scratch_handle(|evm| {
+            let caller_nonce = evm.load_account_info(traced.caller)?.nonce;
+            let recipient = match traced.kind {
+                TxKind::Call(addr) => addr,
+                TxKind::Create => traced.caller.create(caller_nonce),
+            };
+            let caller = traced.caller;
+            let coinbase = evm.ctx.block.beneficiary();
+
+            evm.call_inner(traced)?;
+            let (addresses, slots) =
+                access_list::read_accessed_from_ffi(evm.handle.as_ptr()).map_err(EvmAdapterError::ffi)?;
+            Ok(access_list::build_access_list(&addresses, &slots, |addr| {
+                *addr == caller || *addr == recipient || *addr == coinbase || precompiles.contains(addr)
+            }))
+        })?;
+
+        let mut tx = tx;
+        tx.access_list = access_list.clone();
+        let result = self.call(tx)?;
+
+        Ok(AccessListResult { access_list, gas_used: result.gas_used(), result })
+    }
+
+    /// Discover the accounts and storage slots `tx` touches and sync them from the database
+    ///
+    /// Each dry run starts from the database pre-state plus everything discovered so far, since
+    /// newly synced code or storage can lead execution to further state.
+    fn discover_pre_state(&mut self, tx: &TxEnv) -> Result<(), EvmAdapterError<DB::Error>> {
+        let mut accounts: HashSet<Address> = HashSet::new();
+        let mut slots: HashSet<(Address, U256)> = HashSet::new();
+
+        for _ in 0..MAX_DISCOVERY_PASSES {
+            let (found_accounts, found_slots) = self.with_scratch_handle(|evm| {
+                for address in &accounts {
+                    evm.sync_account(*address)?;
+                }
+                for (address, slot) in &slots {
+                    evm.sync_storage_slots(*address, &[*slot])?;
+                }
+                evm.execute_tx(tx.clone(), None)?;
+                access_list::read_accessed_from_ffi(evm.handle.as_ptr()).map_err(EvmAdapterError::ffi)
+            })?;
+
+            let known = accounts.len() + slots.len();
+            accounts.extend(found_accounts);
+            slots.extend(found_slots.into_iter().map(|(address, slot)| (address, U256::from_be_bytes(slot.0))));
+            if accounts.len() + slots.len() == known {
+                break;
+            }
+        }
+
+        self.dirty = true;
+        for address in accounts {
+            self.sync_account(address)?;
+        }
+        for (address, slot) in slots {
+            self.sync_storage_slots(address, &[slot])?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` against a scratch guillotine-mini instance, leaving the current one untouched
+    fn with_scratch_handle<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, EvmAdapterError<DB::Error>>,
+    ) -> Result<T, EvmAdapterError<DB::Error>> {
+        let scratch = self.create_handle()?;
+
+        let handle = std::mem::replace(&mut self.handle, scratch);
+        let dirty = std::mem::replace(&mut self.dirty, false);
+        let synced_accounts = std::mem::take(&mut self.synced_accounts);
+        let synced_slots = std::mem::take(&mut self.synced_slots);
+        let synced_block_hashes = self.synced_block_hashes.take();
+        let synced_code = std::mem::take(&mut self.synced_code);
+
+        let result = f(self);
+
+        self.handle = handle;
+        self.dirty = dirty;
+        self.synced_accounts = synced_accounts;
+        self.synced_slots = synced_slots;
+        self.synced_block_hashes = synced_block_hashes;
+        self.synced_code = synced_code;
+        result
+    }
+
+    fn call_inner(&mut self, mut tx: TxEnv) -> Result<ExecutionResult, EvmAdapterError<DB::Error>> {
+        tx.nonce = self.load_account_info(tx.caller)?.nonce;
+        self.top_up_caller(&tx)?;
+        self.execute_tx(tx, None).map(|result_and_state| result_and_state.result)
+    }
+
+    /// Raise the sender's balance in guillotine-mini to the value plus maximum gas fee of `tx`
+    /// if it holds less
+    fn top_up_caller(&mut self, tx: &TxEnv) -> Result<(), EvmAdapterError<DB::Error>> {
+        let mut caller_info = self.load_account_info(tx.caller)?;
+        let required = validation::max_tx_cost(tx);
+        if caller_info.balance < required {
+            caller_info.balance = required;
+            self.write_account_info(tx.caller, caller_info)?;
+            self.synced_accounts.insert(tx.caller);
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Estimate the minimal gas limit with which `tx` succeeds
+    ///
+    /// Performs a binary search between the intrinsic gas cost and `tx.gas_limit` (the cap),
+    /// executing the transaction as a read-only [`call`](Self::call) on every attempt, so each
+    /// attempt starts from the database pre-state and nonce and balance are not checked.
+    ///
+    /// Returns `Ok(Err(result))` with the execution result at the cap if the transaction does
+    /// not succeed even with the full gas limit (e.g. it reverts), so callers can inspect the
+    /// revert data.
+    pub fn estimate_gas(&mut self, tx: TxEnv) -> Result<Result<u64, ExecutionResult>, EvmAdapterError<DB::Error>> {
+        let intrinsic = gas::tx_intrinsic_gas(self.ctx.cfg.spec(), &tx);
+        self.record(|stats| *stats = BridgeStats::default());
+
+        // Every attempt runs on the same scratch instance, cleared in between
+        let attempt = |evm: &mut Self, gas_limit: u64| {
+            if evm.dirty {
+                evm.reset_state()?;
+            }
+            let mut tx = tx.clone();
+            tx.gas_limit = gas_limit;
+            evm.call_inner(tx)
         };
 
-        // Create guillotine-mini EVM instance
-        let handle = unsafe {
-            ffi::evm_create(
-                hardfork_name.as_ptr(),
-                hardfork_name.len(),
-                0, // log_level: 0 = none
-            )
+        self.with_scratch_handle(|evm| {
+            let at_cap = attempt(evm, tx.gas_limit)?;
+            if !at_cap.is_success() {
+                return Ok(Err(at_cap));
+            }
+
+            // Highest known failing limit and lowest known succeeding limit. The limit has to
+            // cover the gas spent before refunds, so nothing below the reported gas used can succeed.
+            let mut lo = (intrinsic.initial.max(intrinsic.floor) - 1).max(at_cap.gas_used().saturating_sub(1));
+            let mut hi = tx.gas_limit;
+
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if attempt(evm, mid)?.is_success() {
+                    hi = mid;
+                } else {
+                    lo = mid;
+                }
+            }
+
+            Ok(Ok(hi))
+        })
+    }
+
+    /// Run an executing FFI call, with host callbacks registered for its duration when enabled
+    /// and the context published in `ctx_slot` meanwhile
+    ///
+    /// The host callbacks and whoever reads `ctx_slot` share one pointer to the context, derived
+    /// here, and nothing else borrows it until `run` returns. State loaded by the callbacks is
+    /// marked as synced, so later syncs in persist mode do not overwrite values guillotine-mini
+    /// has modified since.
+    fn with_host<T>(
+        &mut self,
+        ctx_slot: Option<&ContextSlot>,
+        run: impl FnOnce(*mut ffi::EvmHandle) -> T,
+    ) -> Result<T, EvmAdapterError<DB::Error>> {
+        let handle = self.handle.as_ptr();
+        let ctx = std::ptr::addr_of_mut!(self.ctx);
+        let publish = |ptr: *mut c_void| {
+            if let Some(slot) = ctx_slot {
+                slot.set(ptr);
+            }
+        };
+        if !self.host_callbacks {
+            publish(ctx.cast());
+            let executed = run(handle);
+            publish(std::ptr::null_mut());
+            return Ok(executed);
+        }
+        let mut host = unsafe { HostContext::new(ctx) };
+        let callbacks = HostContext::<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>::callbacks();
+        let executed = unsafe {
+            ffi::evm_set_host_callbacks(handle, &callbacks, &mut host as *mut HostContext<_> as *mut c_void);
+            publish(ctx.cast());
+            let executed = run(handle);
+            publish(std::ptr::null_mut());
+            ffi::evm_set_host_callbacks(handle, std::ptr::null(), std::ptr::null_mut());
+            executed
         };
 
-        assert!(!handle.is_null(), "Failed to create guillotine-mini EVM");
+        if let Some(payload) = host.panic {
+            std::panic::resume_unwind(payload);
+        }
+        if let Some(e) = host.error {
+            return Err(EvmAdapterError::Db(e));
+        }
 
-        Self { ctx, handle }
+        let (accounts, slots, code_bytes) = (host.accounts, host.slots, host.code_bytes);
+        self.record(|stats| {
+            stats.accounts_synced += accounts.len() as u64;
+            stats.slots_synced += slots.len() as u64;
+            stats.ffi_calls += (accounts.len() + slots.len()) as u64;
+            stats.bytes_copied += code_bytes;
+        });
+        for (address, code_hash) in accounts {
+            self.synced_accounts.insert(address);
+            self.synced_code.insert(address, code_hash);
+        }
+        self.synced_slots.extend(slots);
+        Ok(executed)
     }
 
-    /// Fallible constructor that returns a proper error instead of panicking
-    pub fn try_new(
-        ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>,
-    ) -> Result<Self, EvmAdapterError<DB::Error>> {
-        // Map REVM SpecId to hardfork name
-        let hardfork_name = match ctx.cfg.spec() {
-            SpecId::FRONTIER | SpecId::FRONTIER_THAWING => "Frontier",
-            SpecId::HOMESTEAD | SpecId::DAO_FORK => "Homestead",
-            SpecId::TANGERINE => "Tangerine",
-            SpecId::SPURIOUS_DRAGON => "Spurious",
-            SpecId::BYZANTIUM => "Byzantium",
-            SpecId::CONSTANTINOPLE | SpecId::PETERSBURG => "Constantinople",
-            SpecId::ISTANBUL | SpecId::MUIR_GLACIER => "Istanbul",
-            SpecId::BERLIN => "Berlin",
-            SpecId::LONDON | SpecId::ARROW_GLACIER | SpecId::GRAY_GLACIER => "London",
-            SpecId::MERGE => "Merge",
-            SpecId::SHANGHAI => "Shanghai",
-            SpecId::CANCUN => "Cancun",
-            SpecId::PRAGUE => "Prague",
-            SpecId::OSAKA => "Osaka",
-            _ => "Cancun",
+    /// Run a single transaction against the current guillotine-mini state
+    ///
+    /// The context is published in `ctx_slot` while guillotine-mini executes.
+    fn execute_tx(
+        &mut self,
+        tx: TxEnv,
+        ctx_slot: Option<&ContextSlot>,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        if self.cancellation.is_cancelled() {
+            return Err(EvmAdapterError::Cancelled);
+        }
+        let prepared = match self.prepare_tx(tx)? {
+            Prepared::Ready(prepared) => prepared,
+            Prepared::Done(result_and_state) => return Ok(result_and_state),
         };
 
-        let handle = unsafe { ffi::evm_create(hardfork_name.as_ptr(), hardfork_name.len(), 0) };
-        if handle.is_null() {
-            return Err(EvmAdapterError::Ffi("evm_create"));
-        }
-        Ok(Self { ctx, handle })
-    }
-
-    // TODO: Re-enable once guillotine-mini upstream adds config FFI functions
-    // /// Create new GuillotineMiniEvm with custom configuration
-    // ///
-    // /// # Arguments
-    // /// * `ctx` - REVM context
-    // /// * `config` - Custom EVM configuration (consumed)
-    // ///
-    // /// # Example
-    // /// ```ignore
-    // /// use guillotine_rs::guillotine_mini::{GuillotineMiniEvm, EvmConfigBuilder};
-    // /// use revm::Context;
-    // ///
-    // /// let config = EvmConfigBuilder::new()
-    // ///     .hardfork("Cancun")
-    // ///     .stack_size(512)
-    // ///     .build();
-    // ///
-    // /// let evm = GuillotineMiniEvm::with_config(ctx, config).unwrap();
-    // /// ```
-    // pub fn with_config(
-    //     ctx: Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN>,
-    //     config: EvmConfig,
-    // ) -> Result<Self, EvmAdapterError<DB::Error>> {
-    //     let config_handle = config.into_raw();
-    //
-    //     let handle = unsafe { ffi::evm_create_with_config(config_handle, 0) };
-    //     if handle.is_null() {
-    //         return Err(EvmAdapterError::Ffi("evm_create_with_config"));
-    //     }
-    //     Ok(Self { ctx, handle })
-    // }
+        // Execute transaction, loading state that was not synced through the host callbacks
+        let cancellation = self.cancellation.clone();
+        let deadline = self.deadline;
+        let execute = spans::phase(Phase::Execute);
+        let (execute_success, timed_out) = self.with_host(ctx_slot, |handle| {
+            cancellation.run(handle, || cancel::run_until(handle, deadline, || unsafe { ffi::evm_execute(handle) }))
+        })?;
+        drop(execute);
+        if cancellation.is_cancelled() || timed_out {
+            // Drop the partial execution and any interrupt still pending on the handle
+            self.clear_handle().map_err(EvmAdapterError::ffi)?;
+            return Err(if timed_out { EvmAdapterError::DeadlineExceeded } else { EvmAdapterError::Cancelled });
+        }
+        if !execute_success {
+            return Err(EvmAdapterError::ffi("evm_execute failed - execution did not complete"));
+        }
 
-    /// Execute a transaction using guillotine-mini
-    pub fn transact(&mut self, tx: TxEnv) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
-        // Extract contract address and bytecode
-        let (contract_addr, bytecode) = match tx.kind {
+        self.finish_tx(prepared)
+    }
+
+    /// Sync the pre-state of a transaction and set it up in guillotine-mini, ready to execute
+    ///
+    /// Transactions that run no code (plain value transfers) are completed directly.
+    fn prepare_tx(&mut self, tx: TxEnv) -> Result<Prepared, EvmAdapterError<DB::Error>> {
+        let pre_sync = spans::phase(Phase::PreSync);
+        // Load caller pre-state (needed for CREATE address derivation and value refunds)
+        let caller_info = self.load_account_info(tx.caller)?;
+
+        let is_create = tx.kind.is_create();
+
+        // Charge intrinsic gas up front; guillotine-mini only meters bytecode execution
+        let intrinsic = gas::tx_intrinsic_gas(self.ctx.cfg.spec(), &tx);
+        if tx.gas_limit < intrinsic.initial {
+            return Err(EvmAdapterError::Transaction(InvalidTransaction::CallGasCostMoreThanGasLimit {
+                initial_gas: intrinsic.initial,
+                gas_limit: tx.gas_limit,
+            }));
+        }
+        if tx.gas_limit < intrinsic.floor {
+            return Err(EvmAdapterError::Transaction(InvalidTransaction::GasFloorMoreThanGasLimit {
+                gas_floor: intrinsic.floor,
+                gas_limit: tx.gas_limit,
+            }));
+        }
+        let execution_gas_limit = tx.gas_limit - intrinsic.initial;
+
+        // Extract contract address, bytecode and (for deployed code) its hash
+        let mut delegate = None;
+        let (contract_addr, bytecode, code_hash) = match tx.kind {
             TxKind::Call(addr) => {
-                // Get code from database
-                let acc = self
-                    .ctx
-                    .journaled_state
-                    .db_mut()
-                    .basic(addr)
-                    .map_err(EvmAdapterError::Db)?;
-                let code = acc
-                    .and_then(|a| a.code)
-                    .map(|c| c.bytecode().to_vec())
-                    .unwrap_or_default();
-                (addr, code)
+                // Get code from the current pre-state, following an EIP-7702 delegation so the
+                // delegate's code runs in the context of the delegating account
+                let mut info = self.load_account_info(addr)?;
+                if self.ctx.cfg.spec().is_enabled_in(SpecId::PRAGUE) {
+                    if let Some(target) = info.code.as_ref().and_then(delegation_target) {
+                        delegate = Some(target);
+                        info = self.load_account_info(target)?;
+                    }
+                }
+                let code = info.code.map(|c| c.bytecode().to_vec()).unwrap_or_default();
+                (addr, code, Some(info.code_hash))
             }
             TxKind::Create => {
-                // For CREATE, use provided data as bytecode
-                (Address::ZERO, tx.data.to_vec())
+                // For CREATE, tx.data is the init code and the new contract address
+                // is derived from the caller's current nonce
+                (tx.caller.create(caller_info.nonce), tx.data.to_vec(), None)
             }
         };
 
+        // Plain value transfer: nothing to execute when the target has no code and is no precompile
+        if !is_create && bytecode.is_empty() && !self.is_precompile(contract_addr) {
+            let gas_used = intrinsic.initial.max(intrinsic.floor);
+            return self.transact_value_transfer(&tx, &caller_info, contract_addr, gas_used).map(Prepared::Done);
+        }
+
         // Sync account pre-state from REVM database to guillotine-mini
-        database_bridge::sync_account_to_ffi(self.handle, self.ctx.journaled_state.db_mut(), tx.caller)?;
-        database_bridge::sync_account_to_ffi(self.handle, self.ctx.journaled_state.db_mut(), contract_addr)?;
-
-        // Sync storage pre-state for the contract
-        // TODO: Improve storage sync strategy using one of these approaches:
-        //   1. EIP-2930 access lists to know exactly which slots to sync
-        //   2. On-demand loading via FFI callback mechanism (requires Zig changes)
-        //   3. Sync all non-zero slots (expensive for large contracts)
-        //   4. Use heuristics based on contract patterns
-        //
-        // For now, we pre-sync common storage slots (0-9) that are frequently used by:
-        //   - Slot 0: Often used for contract state flags or counters
-        //   - Slot 1-9: Common for mappings, arrays, and state variables
-        //
-        // This covers most simple contracts (ERC20, ERC721, etc.) but may miss
-        // complex contracts with dynamic storage layouts or high-slot mappings.
-        let common_slots: [U256; 10] = [
-            U256::from(0),
-            U256::from(1),
-            U256::from(2),
-            U256::from(3),
-            U256::from(4),
-            U256::from(5),
-            U256::from(6),
-            U256::from(7),
-            U256::from(8),
-            U256::from(9),
-        ];
-        database_bridge::sync_storage_slots_to_ffi(
-            self.handle,
-            self.ctx.journaled_state.db_mut(),
-            contract_addr,
-            &common_slots,
-        )?;
+        self.dirty = true;
+        self.sync_account(tx.caller)?;
+        self.sync_account(contract_addr)?;
+
+        // Init code runs as a call on the new account, so it gets the starting nonce of a new
+        // contract (1 from Spurious Dragon, EIP-161) that the addresses it creates derive from
+        let created_nonce = if is_create && self.ctx.cfg.spec().is_enabled_in(SpecId::SPURIOUS_DRAGON) {
+            let mut created = self.load_account_info(contract_addr)?;
+            let nonce = std::mem::replace(&mut created.nonce, 1);
+            self.write_account_info(contract_addr, created)?;
+            Some(nonce)
+        } else {
+            None
+        };
+
+        // Prefetch storage pre-state chosen by the sync strategy (for a delegated account, the
+        // delegate's own state too, in case the delegated code calls back into it)
+        for address in std::iter::once(contract_addr).chain(delegate) {
+            for (address, slots) in self.sync_strategy.storage_slots(&tx, address) {
+                self.sync_account(address)?;
+                self.sync_storage_slots(address, &slots)?;
+            }
+        }
+        if self.scan_bytecode && !is_create {
+            self.sync_storage_slots(contract_addr, &slot_scan::scan_storage_slots(&bytecode))?;
+        }
+
+        // System contracts of the hardfork are made known so calls into them see their code
+        for address in system_contracts(self.ctx.cfg.spec()) {
+            self.sync_account(*address)?;
+        }
 
-        // Set bytecode
-        let bytecode_set = unsafe { ffi::evm_set_bytecode(self.handle, bytecode.as_ptr(), bytecode.len()) };
-        if !bytecode_set {
-            return Err(EvmAdapterError::Ffi("evm_set_bytecode"));
+        // EIP-2929/2930: precompiles, the coinbase and the access list start warm
+        let (warm_addresses, warm_slots) =
+            access_list::initially_warm(self.ctx.cfg.spec(), self.ctx.block.beneficiary(), &tx.access_list);
+        access_list::write_warm_to_ffi(self.handle.as_ptr(), &warm_addresses, &warm_slots)
+            .map_err(EvmAdapterError::ffi)?;
+
+        drop(pre_sync);
+        let _setup = spans::phase(Phase::Setup);
+
+        // Set bytecode, by hash when the code cache is enabled (init code is never cached)
+        match (&mut self.code_cache, code_hash) {
+            (Some(cache), Some(hash)) => {
+                cache.register(hash, &bytecode).map_err(EvmAdapterError::ffi)?;
+                let bytecode_set = unsafe { ffi::evm_set_bytecode_by_hash(self.handle.as_ptr(), hash.as_ptr()) };
+                if !bytecode_set {
+                    return Err(EvmAdapterError::ffi("evm_set_bytecode_by_hash"));
+                }
+            }
+            _ => {
+                let bytecode_set =
+                    unsafe { ffi::evm_set_bytecode(self.handle.as_ptr(), bytecode.as_ptr(), bytecode.len()) };
+                if !bytecode_set {
+                    return Err(EvmAdapterError::ffi("evm_set_bytecode"));
+                }
+            }
         }
 
         // Convert addresses and values to FFI format
         let caller_bytes = types::address_to_bytes(&tx.caller);
         let address_bytes = types::address_to_bytes(&contract_addr);
         let value_bytes = types::u256_to_be_bytes(&tx.value);
-        let calldata = types::bytes_to_slice(&tx.data);
+        // Init code runs with empty calldata; tx.data was already passed as bytecode
+        let calldata: &[u8] = if is_create { &[] } else { types::bytes_to_slice(&tx.data) };
 
         // Set execution context
         let ctx_set = unsafe {
             ffi::evm_set_execution_context(
-                self.handle,
-                tx.gas_limit as i64,
+                self.handle.as_ptr(),
+                execution_gas_limit as i64,
                 caller_bytes.as_ptr(),
                 address_bytes.as_ptr(),
                 value_bytes.as_ptr(),
@@ -303,188 +1330,663 @@ where
             )
         };
         if !ctx_set {
-            return Err(EvmAdapterError::Ffi("evm_set_execution_context"));
+            return Err(EvmAdapterError::ffi("evm_set_execution_context"));
+        }
+
+        // ORIGIN is the sender unless overridden
+        let origin_bytes = types::address_to_bytes(&self.tx_origin.unwrap_or(tx.caller));
+        unsafe {
+            ffi::evm_set_tx_origin(self.handle.as_ptr(), origin_bytes.as_ptr());
+        }
+
+        // BLOCKHASH window for the current block (synced once per block number)
+        let block_number = self.ctx.block.number().to::<u64>();
+        if self.synced_block_hashes != Some(block_number) {
+            let synced = unsafe {
+                database_bridge::sync_block_hashes_to_ffi(
+                    self.handle.as_ptr(),
+                    self.ctx.journaled_state.db_mut(),
+                    block_number,
+                )
+            }?;
+            self.record(|stats| stats.ffi_calls += synced as u64);
+            self.synced_block_hashes = Some(block_number);
         }
 
         // Set blockchain context
+        unsafe { database_bridge::sync_block_env(self.handle.as_ptr(), &self.ctx.block, &self.ctx.cfg) }
+            .map_err(EvmAdapterError::ffi)?;
         let block = &self.ctx.block;
         let cfg = &self.ctx.cfg;
 
-        let chain_id_bytes = types::u256_to_be_bytes(&U256::from(cfg.chain_id()));
-        let difficulty_bytes = types::u256_to_be_bytes(&block.difficulty());
-        let prevrandao = block.prevrandao().unwrap_or_default();
-        let prevrandao_bytes: [u8; 32] = prevrandao.into();
-        let coinbase_bytes = types::address_to_bytes(&block.beneficiary());
-        let base_fee_bytes = types::u256_to_be_bytes(&U256::from(block.basefee()));
+        // EIP-4844: versioned hashes for BLOBHASH (an empty list clears previous hashes)
+        unsafe { database_bridge::set_blob_hashes(self.handle.as_ptr(), cfg.spec(), &tx.blob_hashes) }?;
 
-        // EIP-4844: blob_base_fee
-        let blob_base_fee = U256::from(block.blob_gasprice().unwrap_or_default());
-        let blob_base_fee_bytes = types::u256_to_be_bytes(&blob_base_fee);
+        // GASPRICE reports the effective gas price (EIP-1559)
+        let gas_price = gas::effective_gas_price(cfg.spec(), &tx, block.basefee());
+        let gas_price_bytes = types::u256_to_be_bytes(&U256::from(gas_price));
+        unsafe {
+            ffi::evm_set_gas_price(self.handle.as_ptr(), gas_price_bytes.as_ptr());
+        }
 
+        if !unsafe { ffi::evm_enable_tracing(self.handle.as_ptr(), self.records_struct_logs()) } {
+            return Err(EvmAdapterError::ffi("evm_enable_tracing"));
+        }
+        let limits = self.deep_tracing.unwrap_or(DeepTraceLimits { max_memory: 0, ..Default::default() });
         unsafe {
-            ffi::evm_set_blockchain_context(
-                self.handle,
-                chain_id_bytes.as_ptr(),
-                block.number().to::<u64>(),
-                block.timestamp().to::<u64>(),
-                difficulty_bytes.as_ptr(),
-                prevrandao_bytes.as_ptr(),
-                coinbase_bytes.as_ptr(),
-                block.gas_limit(),
-                base_fee_bytes.as_ptr(),
-                blob_base_fee_bytes.as_ptr(),
-            );
-        }
-
-        // Execute transaction
-        let execute_success = unsafe { ffi::evm_execute(self.handle) };
-        if !execute_success {
-            return Err(EvmAdapterError::Ffi("evm_execute failed - execution did not complete"));
+            ffi::evm_set_struct_log_limits(self.handle.as_ptr(), limits.max_stack_items, limits.max_memory);
+            ffi::evm_set_memory_limit(self.handle.as_ptr(), self.memory_limit.unwrap_or(0));
         }
 
+        Ok(Prepared::Ready(Box::new(PreparedTx {
+            tx,
+            caller_info,
+            intrinsic,
+            execution_gas_limit,
+            contract_addr,
+            is_create,
+            created_nonce,
+        })))
+    }
+
+    /// Collect the result and post-state of a prepared transaction once execution completed
+    fn finish_tx(&mut self, prepared: Box<PreparedTx>) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        let _readback = spans::phase(Phase::Readback);
+        let PreparedTx { tx, caller_info, intrinsic, execution_gas_limit, contract_addr, is_create, created_nonce } =
+            *prepared;
+
         // Get results
-        let gas_used = unsafe { ffi::evm_get_gas_used(self.handle) };
-        let is_success = unsafe { ffi::evm_is_success(self.handle) };
-
-        // Get output data
-        let output_len = unsafe { ffi::evm_get_output_len(self.handle) };
-        let mut output_buf = vec![0u8; output_len];
-        if output_len > 0 {
-            unsafe {
-                ffi::evm_get_output(self.handle, output_buf.as_mut_ptr(), output_len);
+        let gas_used = unsafe { ffi::evm_get_gas_used(self.handle.as_ptr()) };
+        let is_success = unsafe { ffi::evm_is_success(self.handle.as_ptr()) };
+        let halt_reason = if is_success {
+            None
+        } else {
+            types::halt_reason_from_status(unsafe { ffi::evm_get_status(self.handle.as_ptr()) })
+        };
+
+        // Get output data; a REVERT payload is read separately so it is never taken for RETURN data
+        let output_bytes = if is_success {
+            let output_len = unsafe { ffi::evm_get_output_len(self.handle.as_ptr()) };
+            let mut output_buf = vec![0u8; output_len];
+            if output_len > 0 {
+                unsafe {
+                    ffi::evm_get_output(self.handle.as_ptr(), output_buf.as_mut_ptr(), output_len);
+                }
             }
-        }
+            Bytes::from(output_buf)
+        } else if halt_reason.is_none() {
+            unsafe { database_bridge::read_revert_data_from_ffi(self.handle.as_ptr()) }.map_err(EvmAdapterError::ffi)?
+        } else {
+            Bytes::new()
+        };
 
         // Extract gas refund from guillotine-mini
-        let gas_refund = unsafe { ffi::evm_get_gas_refund(self.handle) };
-
-        // Extract logs from guillotine-mini
-        let log_count = unsafe { ffi::evm_get_log_count(self.handle) };
-        let mut logs: Vec<RevmLog> = Vec::with_capacity(log_count);
-        for i in 0..log_count {
-            let mut log_address = [0u8; 20];
-            let mut topics_count: usize = 0;
-            let mut topics_buf = [0u8; 128]; // 4 topics * 32 bytes
-            let mut data_len: usize = 0;
-            let mut data_buf = vec![0u8; 4096];
-
-            let ok = unsafe {
-                ffi::evm_get_log(
-                    self.handle,
-                    i,
-                    log_address.as_mut_ptr(),
-                    &mut topics_count,
-                    topics_buf.as_mut_ptr(),
-                    &mut data_len,
-                    data_buf.as_mut_ptr(),
-                    data_buf.len(),
-                )
-            };
+        let gas_refund = unsafe { ffi::evm_get_gas_refund(self.handle.as_ptr()) };
 
-            if ok {
-                let address = types::address_from_bytes(&log_address);
-                let mut topics = Vec::with_capacity(topics_count);
-                for t in 0..topics_count {
-                    let start = t * 32;
-                    let end = start + 32;
-                    let mut topic_bytes = [0u8; 32];
-                    topic_bytes.copy_from_slice(&topics_buf[start..end]);
-                    topics.push(B256::from(topic_bytes));
+        // Catch impossible engine outputs before they reach downstream accounting
+        let gas_anomalies =
+            gas::check_gas_invariants(self.ctx.cfg.spec(), gas_used, intrinsic.initial, gas_refund, execution_gas_limit);
+        debug_assert!(
+            !gas_anomalies.iter().any(gas::GasAnomaly::is_impossible),
+            "guillotine-mini reported inconsistent gas counters: {:?}",
+            gas_anomalies
+        );
+        let transient_storage = if self.ctx.cfg.spec().is_enabled_in(SpecId::CANCUN) {
+            let transient_storage = unsafe { database_bridge::read_transient_storage_from_ffi(self.handle.as_ptr()) };
+            Some(transient_storage.map_err(EvmAdapterError::ffi)?)
+        } else {
+            None
+        };
+        self.last_report = ExecutionReport {
+            gas_anomalies,
+            transient_storage,
+            blob_gas_used: unsafe { ffi::evm_get_blob_gas_used(self.handle.as_ptr()) },
+            excess_blob_gas: self.ctx.block.blob_excess_gas(),
+            calls: call_trace::read_calls_from_ffi(self.handle.as_ptr()).map_err(EvmAdapterError::ffi)?,
+            gas_breakdown: {
+                let mut breakdown = ffi::FfiGasBreakdown::default();
+                unsafe { ffi::evm_get_gas_breakdown(self.handle.as_ptr(), &mut breakdown) }
+                    .then(|| gas::GasBreakdown::from(breakdown))
+            },
+            struct_logs: if self.records_struct_logs() {
+                let mut logs =
+                    struct_log::read_struct_logs_from_ffi(self.handle.as_ptr()).map_err(EvmAdapterError::ffi)?;
+                if self.deep_tracing.is_some() {
+                    struct_log::read_struct_log_memory_from_ffi(self.handle.as_ptr(), &mut logs)
+                        .map_err(EvmAdapterError::ffi)?;
                 }
-                data_buf.truncate(data_len);
-                let log_data = LogData::new(topics, Bytes::from(data_buf)).expect("valid log data");
-                logs.push(RevmLog { address, data: log_data });
-            }
-        }
+                logs
+            } else {
+                Vec::new()
+            },
+            ..Default::default()
+        };
 
-        let gas_used_u = types::i64_to_u64_gas(gas_used);
+        // Extract logs from guillotine-mini (all at once)
+        let logs = unsafe { database_bridge::read_logs_from_ffi(self.handle.as_ptr()) }.map_err(EvmAdapterError::ffi)?;
+
+        // Total gas includes intrinsic gas; successful executions get the capped refund back
+        let gas_spent = intrinsic.initial + types::i64_to_u64_gas(gas_used);
+        let raw_refund = if is_success { gas_refund } else { 0 };
+        let final_gas = gas::final_gas(self.ctx.cfg.spec(), gas_spent, raw_refund, intrinsic.floor);
+        let gas_used_u = final_gas.gas_used;
         let result = if is_success {
-            let output = Output::Call(Bytes::from(output_buf));
+            let output = if is_create {
+                Output::Create(output_bytes.clone(), Some(contract_addr))
+            } else {
+                Output::Call(output_bytes.clone())
+            };
             ExecutionResult::Success {
                 reason: SuccessReason::Return,
                 gas_used: gas_used_u,
-                gas_refunded: gas_refund,
+                gas_refunded: final_gas.gas_refunded,
                 logs,
                 output,
             }
+        } else if let Some(reason) = halt_reason {
+            // Exceptional halts consume all gas, like revm
+            ExecutionResult::Halt {
+                reason,
+                gas_used: tx.gas_limit,
+            }
         } else {
+            self.last_report.revert_data = Some(output_bytes.clone());
             ExecutionResult::Revert {
                 gas_used: gas_used_u,
-                output: Bytes::from(output_buf),
+                output: output_bytes.clone(),
             }
         };
 
-        // Collect state changes by reading back from guillotine-mini
-        // For now, we'll extract storage changes for the contract address
-        let mut state = EvmState::default();
+        // Collect the post-state of every account touched by the execution
+        let mut state =
+            unsafe { database_bridge::read_state_from_ffi(self.handle.as_ptr()) }.map_err(EvmAdapterError::ffi)?;
 
-        // Extract all storage changes from guillotine-mini
-        let change_count = unsafe { ffi::evm_get_storage_change_count(self.handle) };
-        let mut changes_by_address: HashMap<Address, HashMap<U256, U256>> = HashMap::new();
-
-        for i in 0..change_count {
-            let mut addr_bytes = [0u8; 20];
-            let mut slot_bytes = [0u8; 32];
-            let mut value_bytes = [0u8; 32];
-            let ok = unsafe {
-                ffi::evm_get_storage_change(
-                    self.handle,
-                    i,
-                    addr_bytes.as_mut_ptr(),
-                    slot_bytes.as_mut_ptr(),
-                    value_bytes.as_mut_ptr(),
-                )
-            };
-            if ok {
-                let addr = types::address_from_bytes(&addr_bytes);
-                let slot = types::u256_from_be_bytes(&slot_bytes);
-                let value = types::u256_from_be_bytes(&value_bytes);
-                changes_by_address
-                    .entry(addr)
-                    .or_insert_with(HashMap::new)
-                    .insert(slot, value);
+        // Mark self-destructed accounts; their balance was moved to the beneficiary
+        if is_success {
+            let selfdestructs = unsafe { database_bridge::read_selfdestructs_from_ffi(self.handle.as_ptr()) }
+                .map_err(EvmAdapterError::ffi)?;
+            for selfdestruct in &selfdestructs {
+                touched_account_from_ffi(&mut state, self.handle.as_ptr(), selfdestruct.beneficiary)
+                    .map_err(EvmAdapterError::ffi)?;
+                let destroyed = touched_account_from_ffi(&mut state, self.handle.as_ptr(), selfdestruct.address)
+                    .map_err(EvmAdapterError::ffi)?;
+                destroyed.status |= AccountStatus::SelfDestructed;
+            }
+            self.last_report.selfdestructs = selfdestructs;
+
+            // Contracts deployed by CREATE/CREATE2 inside calls (the top-level create included)
+            let created_contracts = unsafe { database_bridge::read_created_contracts_from_ffi(self.handle.as_ptr()) }
+                .map_err(EvmAdapterError::ffi)?;
+            for contract in &created_contracts {
+                let created = touched_account_from_ffi(&mut state, self.handle.as_ptr(), contract.address)
+                    .map_err(EvmAdapterError::ffi)?;
+                created.info.code_hash = keccak256(&contract.code);
+                created.info.code = Some(Bytecode::new_raw(contract.code.clone()));
+                created.status |= AccountStatus::Created;
             }
+            self.last_report.created_contracts = created_contracts;
         }
 
-        // Build account states with actual storage changes
-        for (addr, slots) in changes_by_address {
-            let mut account = Account {
-                info: AccountInfo::default(),
-                storage: HashMap::default(),
-                status: AccountStatus::Touched,
-                transaction_id: 0,
-            };
+        let caller_balance = if is_success {
+            unsafe { database_bridge::read_balance_from_ffi(self.handle.as_ptr(), tx.caller) }
+                .map_err(EvmAdapterError::ffi)?
+        } else {
+            // Failed execution reverts any value transfer, so the caller keeps its pre-state balance
+            caller_info.balance
+        };
+        self.settle_fees(&mut state, &tx, &caller_info, caller_balance, result.gas_used())?;
 
-            for (slot, value) in slots {
-                account.storage.insert(
-                    slot,
-                    EvmStorageSlot {
-                        original_value: U256::ZERO,
-                        present_value: value,
-                        transaction_id: 0,
-                        is_cold: false,
-                    },
-                );
+        // Materialize the contract created by a successful CREATE transaction
+        if is_create && is_success {
+            // The nonce read back is the starting nonce set up before execution plus the
+            // contracts the init code created
+            let created = touched_account_from_ffi(&mut state, self.handle.as_ptr(), contract_addr)
+                .map_err(EvmAdapterError::ffi)?;
+            created.info.code_hash = keccak256(&output_bytes);
+            created.info.code = Some(Bytecode::new_raw(output_bytes));
+            created.status |= AccountStatus::Created;
+        } else if let Some(nonce) = created_nonce {
+            // A failed creation leaves the account's nonce as it was
+            let mut info = self.load_account_info(contract_addr)?;
+            info.nonce = nonce;
+            self.write_account_info(contract_addr, info)?;
+            if let Some(account) = state.get_mut(&contract_addr) {
+                account.info.nonce = nonce;
             }
+        }
+
+        Ok(ResultAndState { result, state })
+    }
+
+    /// Execute a plain value transfer to an account without code
+    ///
+    /// No bytecode runs, so the interpreter is skipped and the transfer is applied directly.
+    fn transact_value_transfer(
+        &mut self,
+        tx: &TxEnv,
+        caller_info: &AccountInfo,
+        target: Address,
+        gas_used: u64,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        self.last_report = ExecutionReport {
+            blob_gas_used: tx.blob_hashes.len() as u64 * gas::GAS_PER_BLOB,
+            excess_blob_gas: self.ctx.block.blob_excess_gas(),
+            ..Default::default()
+        };
 
-            state.insert(addr, account);
+        let mut state = EvmState::default();
+        let gas_price = gas::effective_gas_price(self.ctx.cfg.spec(), tx, self.ctx.block.basefee());
+        let gas_fee = U256::from(gas_used) * U256::from(gas_price);
+        let has_funds = caller_info.balance >= tx.value.saturating_add(gas_fee);
+
+        let caller_balance = if has_funds { caller_info.balance - tx.value } else { caller_info.balance };
+        self.settle_fees(&mut state, tx, caller_info, caller_balance, gas_used)?;
+
+        if !has_funds {
+            let result = ExecutionResult::Halt { reason: HaltReason::OutOfFunds, gas_used };
+            return Ok(ResultAndState { result, state });
         }
 
+        let target_account = self.touched_account_loaded(&mut state, target)?;
+        target_account.info.balance = target_account.info.balance.saturating_add(tx.value);
+
+        let result = ExecutionResult::Success {
+            reason: SuccessReason::Stop,
+            gas_used,
+            gas_refunded: 0,
+            logs: Vec::new(),
+            output: Output::Call(Bytes::new()),
+        };
         Ok(ResultAndState { result, state })
     }
+
+    /// Apply sender and beneficiary fee accounting to the post-state
+    ///
+    /// The sender bumps its nonce and pays the effective gas price for the gas used regardless of
+    /// the execution outcome, plus the blob gas of blob transactions at the block's blob gas
+    /// price, and the block beneficiary is credited with the priority fee (from London the base
+    /// fee is burned, as are blob fees). The effective gas price is recorded in the report.
+    fn settle_fees(
+        &mut self,
+        state: &mut EvmState,
+        tx: &TxEnv,
+        caller_info: &AccountInfo,
+        caller_balance: U256,
+        gas_used: u64,
+    ) -> Result<(), EvmAdapterError<DB::Error>> {
+        let basefee = self.ctx.block.basefee();
+        let gas_price = gas::effective_gas_price(self.ctx.cfg.spec(), tx, basefee);
+        self.last_report.effective_gas_price = gas_price;
+
+        let gas_fee = U256::from(gas_used) * U256::from(gas_price);
+        let blob_gas = tx.blob_hashes.len() as u64 * gas::GAS_PER_BLOB;
+        let blob_fee = U256::from(blob_gas) * U256::from(self.ctx.block.blob_gasprice().unwrap_or_default());
+        let caller_account = touched_account(state, tx.caller);
+        caller_account.info = AccountInfo {
+            balance: caller_balance.saturating_sub(gas_fee).saturating_sub(blob_fee),
+            nonce: caller_info.nonce.saturating_add(1),
+            ..caller_info.clone()
+        };
+
+        let coinbase_gas_price = if self.ctx.cfg.spec().is_enabled_in(SpecId::LONDON) {
+            gas_price.saturating_sub(basefee as u128)
+        } else {
+            gas_price
+        };
+        let coinbase_reward = U256::from(gas_used) * U256::from(coinbase_gas_price);
+        let beneficiary = self.ctx.block.beneficiary();
+        let coinbase_account = self.touched_account_loaded(state, beneficiary)?;
+        coinbase_account.info.balance = coinbase_account.info.balance.saturating_add(coinbase_reward);
+
+        Ok(())
+    }
+
+    /// Diff a post-state returned by `transact` against the database
+    ///
+    /// The pre-state of each account is read from the database, so diff results before
+    /// committing them (in persist mode, earlier uncommitted transactions count as changes).
+    pub fn state_diff(&mut self, state: &EvmState) -> Result<StateDiff, EvmAdapterError<DB::Error>> {
+        let db = self.ctx.journaled_state.db_mut();
+        let mut pre_state = HashMap::new();
+        for address in state.keys() {
+            pre_state.insert(*address, db.basic(*address).map_err(EvmAdapterError::Db)?);
+        }
+        Ok(StateDiff::new(state, |address| pre_state.get(address).cloned().flatten()))
+    }
+
+    /// Create a fresh guillotine-mini instance for the hardfork, attached to the code cache
+    fn create_handle(&self) -> Result<ffi::OwnedEvmHandle, EvmAdapterError<DB::Error>> {
+        self.new_handle().map_err(EvmAdapterError::ffi)
+    }
+
+    /// Load the current pre-state of an account
+    ///
+    /// Accounts already synced to guillotine-mini are read back from it (they may carry state
+    /// from earlier transactions in persist mode); all others come from the database.
+    fn load_account_info(&mut self, address: Address) -> Result<AccountInfo, EvmAdapterError<DB::Error>> {
+        if self.synced_accounts.contains(&address) {
+            return unsafe { database_bridge::read_account_info_from_ffi(self.handle.as_ptr(), address) }
+                .map_err(EvmAdapterError::ffi);
+        }
+        Ok(self
+            .ctx
+            .journaled_state
+            .db_mut()
+            .basic(address)
+            .map_err(EvmAdapterError::Db)?
+            .unwrap_or_default())
+    }
+
+    /// Get a touched account entry in the post-state, loading its pre-state info on first access
+    fn touched_account_loaded<'a>(
+        &mut self,
+        state: &'a mut EvmState,
+        address: Address,
+    ) -> Result<&'a mut Account, EvmAdapterError<DB::Error>> {
+        if !state.contains_key(&address) {
+            let info = self.load_account_info(address)?;
+            touched_account(state, address).info = info;
+        }
+        Ok(touched_account(state, address))
+    }
+
+    /// Sync an account from the database unless guillotine-mini already holds its state
+    fn sync_account(&mut self, address: Address) -> Result<(), EvmAdapterError<DB::Error>> {
+        if self.synced_accounts.insert(address) {
+            let db = self.ctx.journaled_state.db_mut();
+            if let Some(info) = database_bridge::load_account_with_code(db, address)? {
+                if self.verify_code_hashes {
+                    database_bridge::verify_code_hash(address, &info)?;
+                }
+                self.write_account_info(address, info)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write account info to guillotine-mini, leaving out code it already holds for the account
+    ///
+    /// With the code cache enabled, new code is registered once and assigned by hash.
+    fn write_account_info(&mut self, address: Address, mut info: AccountInfo) -> Result<(), EvmAdapterError<DB::Error>> {
+        if info.code.is_some() {
+            if self.synced_code.get(&address) == Some(&info.code_hash) {
+                info.code = None;
+            } else {
+                self.synced_code.insert(address, info.code_hash);
+            }
+        }
+
+        // Balance, nonce and code hash, plus the code itself when present
+        let code_len = info.code.as_ref().map(|code| code.bytecode().len() as u64);
+        self.record(|stats| {
+            stats.accounts_synced += 1;
+            stats.ffi_calls += 3 + u64::from(code_len.is_some());
+            stats.bytes_copied += code_len.unwrap_or_default();
+        });
+
+        if let Some(cache) = &mut self.code_cache {
+            if let Some(code) = info.code.take() {
+                cache.register(info.code_hash, code.bytecode()).map_err(EvmAdapterError::ffi)?;
+                let addr_bytes = types::address_to_bytes(&address);
+                let code_set = unsafe {
+                    ffi::evm_set_code_by_hash(self.handle.as_ptr(), addr_bytes.as_ptr(), info.code_hash.as_ptr())
+                };
+                if !code_set {
+                    return Err(EvmAdapterError::ffi("evm_set_code_by_hash"));
+                }
+            }
+        }
+        unsafe { database_bridge::write_account_info_to_ffi(self.handle.as_ptr(), address, &info) }
+            .map_err(EvmAdapterError::ffi)
+    }
+
+    /// Sync storage slots from the database, skipping those guillotine-mini already holds
+    fn sync_storage_slots(&mut self, address: Address, slots: &[U256]) -> Result<(), EvmAdapterError<DB::Error>> {
+        let pending: Vec<U256> = slots
+            .iter()
+            .copied()
+            .filter(|slot| self.synced_slots.insert((address, *slot)))
+            .collect();
+        self.record(|stats| {
+            stats.slots_synced += pending.len() as u64;
+            stats.ffi_calls += pending.len() as u64;
+        });
+        unsafe {
+            database_bridge::sync_storage_slots_to_ffi(
+                self.handle.as_ptr(),
+                self.ctx.journaled_state.db_mut(),
+                address,
+                &pending,
+            )
+        }
+    }
+}
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> GuillotineMiniEvm<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>
+where
+    BLOCK: revm::context_interface::Block,
+    TX: revm::context_interface::Transaction,
+    CFG: Cfg<Spec = SpecId>,
+    DB: Database + DatabaseCommit,
+    JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+    LOCAL: revm::context_interface::LocalContextTr,
+{
+    /// Execute a transaction and commit the resulting state to the context's database
+    ///
+    /// Equivalent to calling `transact` and then `DatabaseCommit::commit` with the returned
+    /// state, so subsequent transactions observe this transaction's effects.
+    pub fn transact_commit(&mut self, tx: TxEnv) -> Result<ExecutionResult, EvmAdapterError<DB::Error>> {
+        let ResultAndState { result, state } = self.transact(tx)?;
+        self.ctx.journaled_state.db_mut().commit(state);
+        Ok(result)
+    }
+
+    /// Execute a sequence of transactions, committing each post-state to the database
+    ///
+    /// Like [`transact_block`](Self::transact_block), but every transaction's state is committed
+    /// as soon as it executes, so transaction N+1 reads transaction N's effects from the
+    /// database exactly as under revm's journaled execution.
+    ///
+    /// Transactions before a failing one stay committed.
+    pub fn transact_block_commit(&mut self, txs: Vec<TxEnv>) -> Result<Vec<ResultAndState>, EvmAdapterError<DB::Error>> {
+        let persist_state = self.persist_state;
+        self.reset_state()?;
+        self.persist_state = true;
+
+        let results = txs
+            .into_iter()
+            .map(|tx| {
+                let result_and_state = self.transact(tx)?;
+                self.ctx.journaled_state.db_mut().commit(result_and_state.state.clone());
+                Ok(result_and_state)
+            })
+            .collect();
+
+        self.persist_state = persist_state;
+        results
+    }
+}
+
+/// Transaction set up in guillotine-mini, waiting to be executed
+struct PreparedTx {
+    tx: TxEnv,
+    caller_info: AccountInfo,
+    intrinsic: gas::IntrinsicGas,
+    execution_gas_limit: u64,
+    contract_addr: Address,
+    is_create: bool,
+    /// Nonce the created account held before it was given the starting nonce of a new contract
+    created_nonce: Option<u64>,
+}
+
+/// Outcome of preparing a transaction
+enum Prepared {
+    /// Code to execute is set up
+    Ready(Box<PreparedTx>),
+    /// Nothing to execute; the transaction is already complete
+    Done(ResultAndState),
+}
+
+/// Stepper returned by [`GuillotineMiniEvm::stepper`]
+type StepperResult<'a, BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> =
+    Result<Stepper<'a, Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>, EvmAdapterError<<DB as Database>::Error>>;
+
+/// Transaction executed one opcode at a time, created by [`GuillotineMiniEvm::stepper`]
+///
+/// Dropping an unfinished stepper abandons the transaction and resets guillotine-mini state.
+pub struct Stepper<'a, CTX> {
+    evm: &'a mut GuillotineMiniEvm<CTX>,
+    prepared: Option<Prepared>,
+    finished: bool,
+}
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> Stepper<'_, Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>
+where
+    BLOCK: revm::context_interface::Block,
+    TX: revm::context_interface::Transaction,
+    CFG: Cfg<Spec = SpecId>,
+    DB: Database,
+    JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+    LOCAL: revm::context_interface::LocalContextTr,
+{
+    /// Execute the next opcode
+    ///
+    /// Returns [`StepResult::Finished`] once execution has completed, including on every call
+    /// after that.
+    pub fn step(&mut self) -> Result<StepResult, EvmAdapterError<DB::Error>> {
+        if self.finished {
+            return Ok(StepResult::Finished);
+        }
+        if self.evm.cancellation.is_cancelled() {
+            return Err(EvmAdapterError::Cancelled);
+        }
+        let status = self.evm.with_host(None, |handle| unsafe { ffi::evm_step(handle) })?;
+        let result = StepResult::from_status(status).ok_or(EvmAdapterError::ffi("evm_step"))?;
+        self.finished = result == StepResult::Finished;
+        Ok(result)
+    }
+
+    /// Whether execution has completed
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Memory of the frame executing the next opcode
+    ///
+    /// Empty before the first step and once execution has finished.
+    pub fn memory(&self) -> MemoryView<'_> {
+        MemoryView::from_handle(self.evm.handle.as_ptr())
+    }
+
+    /// Run the remaining opcodes and return the transaction's result and post-state
+    pub fn finish(mut self) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        while self.step()? == StepResult::Continue {}
+        let result_and_state = match self.prepared.take() {
+            Some(Prepared::Ready(prepared)) => self.evm.finish_tx(prepared)?,
+            Some(Prepared::Done(result_and_state)) => result_and_state,
+            None => unreachable!("prepared transaction is only taken by finish"),
+        };
+        self.evm.persist_post_state(&result_and_state)?;
+        Ok(result_and_state)
+    }
 }
 
-impl<CTX> Drop for GuillotineMiniEvm<CTX> {
+impl<CTX> Drop for Stepper<'_, CTX> {
     fn drop(&mut self) {
-        unsafe {
-            ffi::evm_destroy(self.handle);
+        if !self.finished {
+            // Abandoned mid-execution: the handle cannot be resumed, start from a fresh one
+            let _ = self.evm.replace_handle();
         }
     }
 }
 
-// Safety: The handle is only used from the same thread
-unsafe impl<CTX> Send for GuillotineMiniEvm<CTX> where CTX: Send {}
-unsafe impl<CTX> Sync for GuillotineMiniEvm<CTX> where CTX: Sync {}
+/// Get a touched account entry in the post-state, reading it back from guillotine-mini on first access
+fn touched_account_from_ffi(
+    state: &mut EvmState,
+    handle: *mut ffi::EvmHandle,
+    address: Address,
+) -> Result<&mut Account, &'static str> {
+    if !state.contains_key(&address) {
+        let info = unsafe { database_bridge::read_account_info_from_ffi(handle, address) }?;
+        touched_account(state, address).info = info;
+    }
+    Ok(touched_account(state, address))
+}
+
+/// Beacon roots contract (EIP-4788, Cancun)
+const BEACON_ROOTS_ADDRESS: Address = address!("000f3df6d732807ef1319fb7b8bb8522d0beac02");
+/// Historical block hashes contract (EIP-2935, Prague)
+const HISTORY_STORAGE_ADDRESS: Address = address!("0000f90827f1c53a10cb7a02335b175320002935");
+/// Withdrawal requests contract (EIP-7002, Prague)
+const WITHDRAWAL_REQUEST_ADDRESS: Address = address!("00000961ef480eb55e80d19ad83579a64c007002");
+/// Consolidation requests contract (EIP-7251, Prague)
+const CONSOLIDATION_REQUEST_ADDRESS: Address = address!("0000bbddc7ce488642fb579f8b00f3a590007251");
+
+/// System contracts deployed as of `spec`
+///
+/// Unlike precompiles these are ordinary accounts and start cold; they only need syncing.
+fn system_contracts(spec: SpecId) -> &'static [Address] {
+    if spec.is_enabled_in(SpecId::PRAGUE) {
+        &[BEACON_ROOTS_ADDRESS, HISTORY_STORAGE_ADDRESS, WITHDRAWAL_REQUEST_ADDRESS, CONSOLIDATION_REQUEST_ADDRESS]
+    } else if spec.is_enabled_in(SpecId::CANCUN) {
+        &[BEACON_ROOTS_ADDRESS]
+    } else {
+        &[]
+    }
+}
+
+/// Address an EIP-7702 delegation designator (`0xef0100 || address`) points to
+fn delegation_target(code: &Bytecode) -> Option<Address> {
+    match code {
+        Bytecode::Eip7702(eip7702) => Some(eip7702.address()),
+        _ => None,
+    }
+}
+
+/// Get or insert a touched account entry in the post-state
+fn touched_account(state: &mut EvmState, address: Address) -> &mut Account {
+    state.entry(address).or_insert_with(|| Account {
+        info: AccountInfo::default(),
+        storage: HashMap::default(),
+        status: AccountStatus::Touched,
+        transaction_id: 0,
+    })
+}
+
+impl<BLOCK, TX, CFG, ExtDB, JOURNAL, CHAIN, LOCAL> GuillotineMiniEvm<Context<BLOCK, TX, CFG, CacheDB<ExtDB>, JOURNAL, CHAIN, LOCAL>>
+where
+    BLOCK: revm::context_interface::Block,
+    TX: revm::context_interface::Transaction,
+    CFG: Cfg<Spec = SpecId>,
+    ExtDB: DatabaseRef,
+    JOURNAL: revm::context_interface::JournalTr<Database = CacheDB<ExtDB>>,
+    LOCAL: revm::context_interface::LocalContextTr,
+{
+    /// Sync every non-zero storage slot cached in the `CacheDB` for `address`
+    ///
+    /// Prefetches state that host callbacks would otherwise load one slot at a time. The slots
+    /// stay synced for the next transaction; in persist mode slots already holding carried-over
+    /// state are left untouched. Returns the number of cached non-zero slots.
+    pub fn sync_all_storage(&mut self, address: Address) -> Result<usize, EvmAdapterError<ExtDB::Error>> {
+        if self.dirty && !self.persist_state {
+            self.reset_state()?;
+        }
+
+        let slots: Vec<U256> = match self.ctx.journaled_state.db().cache.accounts.get(&address) {
+            Some(account) => account.storage.iter().filter(|(_, value)| !value.is_zero()).map(|(slot, _)| *slot).collect(),
+            None => Vec::new(),
+        };
+        self.sync_storage_slots(address, &slots)?;
+        Ok(slots.len())
+    }
+
+    /// Build a [`FullScan`] strategy from the storage currently cached in the `CacheDB`
+    ///
+    /// Install it with `set_sync_strategy` to sync every cached slot before each transaction.
+    pub fn full_scan_strategy(&self) -> FullScan {
+        FullScan::from_cache_db(self.ctx.journaled_state.db())
+    }
+}
 
 #[cfg(test)]
 mod tests {
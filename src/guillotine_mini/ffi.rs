@@ -2,12 +2,112 @@
 //!
 //! Bindings to lib/guillotine-mini/src/root_c.zig
 
+use super::hardfork::Hardfork;
+
 /// Opaque handle to EVM instance (maps to ExecutionContext in Zig)
 #[repr(C)]
 pub struct EvmHandle {
     _private: [u8; 0],
 }
 
+/// C ABI version of libguillotine_mini these bindings were written against
+///
+/// Bumped by guillotine-mini whenever an exported signature or struct layout changes.
+pub const ABI_VERSION: u32 = 1;
+
+/// Check that the linked libguillotine_mini implements [`ABI_VERSION`]
+///
+/// Returns the library's version on mismatch.
+pub fn check_abi_version() -> Result<(), u32> {
+    let found = unsafe { evm_abi_version() };
+    if found == ABI_VERSION {
+        Ok(())
+    } else {
+        Err(found)
+    }
+}
+
+/// Owned guillotine-mini EVM instance, destroyed on drop
+///
+/// Guarantees the wrapped pointer is non-null and destroyed exactly once.
+/// `Send` but not `Sync`: an instance can be handed to another thread, never shared between
+/// threads.
+#[derive(Debug)]
+pub struct OwnedEvmHandle(std::ptr::NonNull<EvmHandle>);
+
+impl OwnedEvmHandle {
+    /// Create an EVM instance for `hardfork`
+    ///
+    /// Logging is disabled unless the `tracing` feature routes it to the current subscriber.
+    pub fn new(hardfork: Hardfork) -> Result<Self, &'static str> {
+        Self::with_log_level(hardfork, None)
+    }
+
+    /// Create an EVM instance for `hardfork` logging up to `log_level` (see `LOG_LEVEL_*`)
+    ///
+    /// `None` uses the default of [`new`](Self::new). Without the `tracing` feature,
+    /// guillotine-mini writes enabled messages to stderr.
+    pub fn with_log_level(hardfork: Hardfork, log_level: Option<u8>) -> Result<Self, &'static str> {
+        let handle = unsafe { evm_create_with_hardfork(hardfork.id(), resolve_log_level(log_level)) };
+        let handle = std::ptr::NonNull::new(handle).ok_or("evm_create_with_hardfork")?;
+        super::ffi_safe::register_handle(handle.as_ptr());
+        Ok(Self(handle))
+    }
+
+    /// Create an EVM instance from a built configuration, which stays owned by the caller
+    ///
+    /// The caller must pass a non-null handle created by `evm_config_create` and keep it, and
+    /// the handlers registered on it, alive until the instance is destroyed. `log_level` is as
+    /// for [`with_log_level`](Self::with_log_level).
+    #[cfg(feature = "config-api")]
+    pub(crate) fn with_config(config: *const EvmConfigHandle, log_level: Option<u8>) -> Result<Self, &'static str> {
+        let handle = unsafe { evm_create_from_config(config, resolve_log_level(log_level)) };
+        let handle = std::ptr::NonNull::new(handle).ok_or("evm_create_from_config")?;
+        super::ffi_safe::register_handle(handle.as_ptr());
+        Ok(Self(handle))
+    }
+
+    /// Raw pointer for FFI calls; valid for as long as `self` is alive
+    pub fn as_ptr(&self) -> *mut EvmHandle {
+        self.0.as_ptr()
+    }
+}
+
+/// Log level new instances are created with, `requested` or the default
+#[cfg(feature = "tracing")]
+fn resolve_log_level(requested: Option<u8>) -> u8 {
+    // Registers the log callback even when the level is explicit
+    let default = super::logging::log_level();
+    requested.unwrap_or(default)
+}
+
+/// Log level new instances are created with, `requested` or the default
+#[cfg(not(feature = "tracing"))]
+fn resolve_log_level(requested: Option<u8>) -> u8 {
+    requested.unwrap_or(LOG_LEVEL_OFF)
+}
+
+impl Drop for OwnedEvmHandle {
+    fn drop(&mut self) {
+        super::ffi_safe::unregister_handle(self.0.as_ptr());
+        unsafe {
+            evm_destroy(self.0.as_ptr());
+        }
+    }
+}
+
+// Safety: guillotine-mini keeps all instance state behind the handle (diagnostics are per
+// thread and read right after the failing call), so an instance may move between threads.
+// It is not internally synchronized, so `OwnedEvmHandle` is deliberately not `Sync`: every call
+// goes through its single owner.
+unsafe impl Send for OwnedEvmHandle {}
+
+/// Opaque handle to a bytecode analysis cache shared between EVM instances
+#[repr(C)]
+pub struct CodeCacheHandle {
+    _private: [u8; 0],
+}
+
 /// Opaque handle to EVM configuration
 #[repr(C)]
 pub struct EvmConfigHandle {
@@ -23,7 +123,7 @@ pub type FfiOpcodeHandler = extern "C" fn(
 ) -> bool;
 
 /// FFI-compatible precompile handler callback
-/// Returns true on success, false on failure
+/// Returns a `PRECOMPILE_*` status; output and gas used are only set on success
 pub type FfiPrecompileHandler = extern "C" fn(
     ctx: *mut std::ffi::c_void,
     address: *const u8, // 20 bytes
@@ -33,12 +133,247 @@ pub type FfiPrecompileHandler = extern "C" fn(
     output_ptr: *mut *mut u8, // Handler sets this to allocated output
     output_len: *mut usize,   // Handler sets output length
     gas_used: *mut u64,       // Handler sets gas consumed
-) -> bool;
+) -> u8;
 
-#[link(name = "guillotine_mini")]
-extern "C" {
-    // ===== Config Builder API =====
+// ===== Precompile handler status codes (returned by `FfiPrecompileHandler`) =====
+
+/// Precompile succeeded; output and gas used are set
+pub const PRECOMPILE_SUCCESS: u8 = 0;
+/// Precompile ran out of gas; the call halts with `EVM_STATUS_OUT_OF_GAS`, consuming all its gas
+pub const PRECOMPILE_OUT_OF_GAS: u8 = 1;
+/// Precompile failed; the call halts with `EVM_STATUS_PRECOMPILE_FAILURE`, consuming all its gas
+pub const PRECOMPILE_FAILURE: u8 = 2;
+
+/// FFI-compatible step observer callback
+/// Invoked before each opcode is executed
+pub type FfiStepHandler = extern "C" fn(
+    ctx: *mut std::ffi::c_void,
+    pc: u32,
+    opcode: u8,
+    gas_remaining: i64,
+    depth: u16,
+    stack_len: usize,
+);
 
+/// Host-interface callbacks invoked when execution reads state guillotine-mini does not hold
+///
+/// Each callback returns `false` if the state could not be loaded, which aborts execution
+/// (`evm_execute` returns `false`). Loaded values are kept as pre-state, so every account and
+/// slot is requested at most once per instance.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FfiHostCallbacks {
+    /// Load an account (BALANCE, EXTCODE*, calls); a missing account is reported as empty
+    pub load_account: extern "C" fn(
+        ctx: *mut std::ffi::c_void,
+        address: *const u8,     // 20 bytes
+        balance_out: *mut u8,   // 32 bytes, big-endian
+        nonce_out: *mut u64,
+        code_hash_out: *mut u8, // 32 bytes
+        code_len_out: *mut usize,
+    ) -> bool,
+    /// Copy the code of an account just loaded with `load_account` (`code_len` bytes)
+    pub load_code: extern "C" fn(
+        ctx: *mut std::ffi::c_void,
+        address: *const u8, // 20 bytes
+        code_out: *mut u8,
+        code_len: usize,
+    ) -> bool,
+    /// Load a storage slot (SLOAD, and the original value for SSTORE gas)
+    pub load_storage: extern "C" fn(
+        ctx: *mut std::ffi::c_void,
+        address: *const u8, // 20 bytes
+        slot: *const u8,    // 32 bytes, big-endian
+        value_out: *mut u8, // 32 bytes, big-endian
+    ) -> bool,
+}
+
+// ===== Execution status codes (returned by `evm_get_status`) =====
+
+/// Execution completed successfully (STOP/RETURN)
+pub const EVM_STATUS_SUCCESS: u8 = 0;
+/// Execution reverted via the REVERT opcode
+pub const EVM_STATUS_REVERT: u8 = 1;
+/// Execution ran out of gas
+pub const EVM_STATUS_OUT_OF_GAS: u8 = 2;
+/// Undefined or not-yet-activated opcode encountered
+pub const EVM_STATUS_INVALID_OPCODE: u8 = 3;
+/// Stack exceeded its maximum size
+pub const EVM_STATUS_STACK_OVERFLOW: u8 = 4;
+/// Opcode popped more items than the stack held
+pub const EVM_STATUS_STACK_UNDERFLOW: u8 = 5;
+/// JUMP/JUMPI targeted a non-JUMPDEST location
+pub const EVM_STATUS_INVALID_JUMP: u8 = 6;
+/// Call depth limit exceeded
+pub const EVM_STATUS_CALL_DEPTH_EXCEEDED: u8 = 7;
+/// Execution stopped by `evm_request_interrupt`
+pub const EVM_STATUS_INTERRUPTED: u8 = 8;
+/// A precompile rejected its input or failed
+pub const EVM_STATUS_PRECOMPILE_FAILURE: u8 = 9;
+
+// ===== Internal call kinds (reported by `evm_get_call`) =====
+
+/// Message call via the CALL opcode
+pub const EVM_CALL_CALL: u8 = 0;
+/// Call running the target's code in the caller's context via CALLCODE
+pub const EVM_CALL_CALLCODE: u8 = 1;
+/// Call keeping the caller's context, sender and value via DELEGATECALL
+pub const EVM_CALL_DELEGATECALL: u8 = 2;
+/// Read-only call via STATICCALL
+pub const EVM_CALL_STATICCALL: u8 = 3;
+/// Contract creation via the CREATE opcode
+pub const EVM_CALL_CREATE: u8 = 4;
+/// Contract creation at a salted address via CREATE2
+pub const EVM_CALL_CREATE2: u8 = 5;
+
+/// Internal call record filled by `evm_get_call`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FfiCallInfo {
+    /// Call kind (`EVM_CALL_*`)
+    pub kind: u8,
+    /// Execution status code (`EVM_STATUS_*`)
+    pub status: u8,
+    /// Call depth (1 for calls made by the transaction's own code)
+    pub depth: u16,
+    pub from: [u8; 20],
+    pub to: [u8; 20],
+    /// Value, big-endian
+    pub value: [u8; 32],
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    /// Length of the call's output (see `evm_get_call_output`)
+    pub output_len: usize,
+}
+
+/// Execution gas by category, filled by `evm_get_gas_breakdown`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FfiGasBreakdown {
+    pub execution: u64,
+    pub memory: u64,
+    pub storage: u64,
+    pub access: u64,
+    pub refund: u64,
+}
+
+/// Opcode record captured by native tracing, filled by `evm_get_struct_log`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FfiStructLog {
+    pub pc: u32,
+    pub opcode: u8,
+    /// Call depth (0 for the top-level frame)
+    pub depth: u16,
+    /// Gas remaining before the opcode is charged
+    pub gas: i64,
+    /// Gas charged for the opcode
+    pub gas_cost: i64,
+    /// Refund counter before the opcode executes
+    pub refund: u64,
+    /// Number of stack items (see `evm_get_struct_log_stack`)
+    pub stack_len: usize,
+    /// Memory size in bytes
+    pub memory_size: usize,
+}
+
+// ===== Log levels (passed at creation and to the log callback) =====
+
+/// No messages
+pub const LOG_LEVEL_OFF: u8 = 0;
+/// Errors only
+pub const LOG_LEVEL_ERR: u8 = 1;
+/// Warnings and errors
+pub const LOG_LEVEL_WARN: u8 = 2;
+/// Informational messages and above
+pub const LOG_LEVEL_INFO: u8 = 3;
+/// Every message
+pub const LOG_LEVEL_DEBUG: u8 = 4;
+
+/// Receives guillotine-mini log messages: level, scope and message (UTF-8, not NUL-terminated)
+pub type LogCallback =
+    extern "C" fn(level: u8, scope: *const u8, scope_len: usize, message: *const u8, message_len: usize);
+
+// ===== Step status codes (returned by `evm_step`) =====
+
+/// An opcode was executed and execution continues
+pub const EVM_STEP_CONTINUE: u8 = 0;
+/// Execution has completed; results can be read as after `evm_execute`
+pub const EVM_STEP_FINISHED: u8 = 1;
+/// The step failed (e.g. execution context not set)
+pub const EVM_STEP_ERROR: u8 = 2;
+
+// ===== Config Builder API =====
+// Only exported by guillotine-mini builds with the config ABI. With the `config-api` feature the
+// functions are only referenced weakly and resolved on first use (see [`config_api`]), so a
+// library without them still links and `GuillotineMiniEvm::with_config` reports
+// `EvmAdapterError::Unsupported`.
+
+/// Declare the config ABI: a [`ConfigApi`] table resolved at runtime, and a function of the same
+/// name calling through it for each entry outside the trailing `instance` block
+#[cfg(feature = "config-api")]
+macro_rules! config_abi {
+    (
+        $($(#[$meta:meta])* pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*
+        instance {
+            $($(#[$imeta:meta])* pub fn $iname:ident($($iarg:ident: $ity:ty),* $(,)?) $(-> $iret:ty)?;)*
+        }
+    ) => {
+        /// Config builder functions exported by libguillotine_mini
+        pub struct ConfigApi {
+            $($(#[$meta])* pub $name: unsafe extern "C" fn($($ty),*) $(-> $ret)?,)*
+            $($(#[$imeta])* pub $iname: unsafe extern "C" fn($($ity),*) $(-> $iret)?,)*
+        }
+
+        impl ConfigApi {
+            /// Resolve every config function, or `None` if any of them is not exported
+            fn resolve() -> Option<Self> {
+                Some(Self {
+                    $($name: {
+                        let name = concat!(stringify!($name), "\0");
+                        let symbol = super::symbols::lookup(std::ffi::CStr::from_bytes_with_nul(name.as_bytes()).ok()?)?;
+                        // SAFETY: the exported symbol has the signature declared for it
+                        unsafe {
+                            std::mem::transmute::<*mut std::ffi::c_void, unsafe extern "C" fn($($ty),*) $(-> $ret)?>(symbol)
+                        }
+                    },)*
+                    $($iname: {
+                        let name = concat!(stringify!($iname), "\0");
+                        let symbol = super::symbols::lookup(std::ffi::CStr::from_bytes_with_nul(name.as_bytes()).ok()?)?;
+                        // SAFETY: the exported symbol has the signature declared for it
+                        unsafe {
+                            std::mem::transmute::<*mut std::ffi::c_void, unsafe extern "C" fn($($ity),*) $(-> $iret)?>(symbol)
+                        }
+                    },)*
+                })
+            }
+        }
+
+        $(
+            $(#[$meta])*
+            ///
+            /// # Safety
+            /// As for the C function. Panics if libguillotine_mini does not export the config ABI;
+            /// check [`config_api`] first.
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                let api = config_api().expect("libguillotine_mini does not export the config ABI");
+                (api.$name)($($arg),*)
+            }
+        )*
+    };
+}
+
+/// Config builder functions, if the linked libguillotine_mini exports them
+///
+/// Looked up once per process.
+#[cfg(feature = "config-api")]
+pub fn config_api() -> Option<&'static ConfigApi> {
+    static API: std::sync::OnceLock<Option<ConfigApi>> = std::sync::OnceLock::new();
+    API.get_or_init(ConfigApi::resolve).as_ref()
+}
+
+#[cfg(feature = "config-api")]
+config_abi! {
     /// Create a new EVM configuration with default values
     pub fn evm_config_create() -> *mut EvmConfigHandle;
 
@@ -81,6 +416,10 @@ extern "C" {
         withdrawals: bool,
     );
 
+    /// Enable or disable an EIP independently of the hardfork (e.g. 1153, 3855, 6780)
+    /// Returns false if guillotine-mini does not implement the EIP as a toggle
+    pub fn evm_config_set_eip(handle: *mut EvmConfigHandle, eip: u16, enabled: bool) -> bool;
+
     /// Add a custom opcode handler override
     /// Returns true on success, false on allocation failure
     pub fn evm_config_add_opcode_override(
@@ -99,33 +438,149 @@ extern "C" {
         ctx: *mut std::ffi::c_void,
     ) -> bool;
 
-    // ===== EVM Creation =====
+    /// Remove the built-in precompile at an address; calls to it then run the account's code
+    /// Returns false if no built-in precompile lives at the address
+    pub fn evm_config_disable_precompile(handle: *mut EvmConfigHandle, address_bytes: *const u8) -> bool;
+
+    /// Keep only the built-in precompiles at `count` packed 20-byte addresses, removing every other
+    /// Returns false if one of the addresses holds no built-in precompile
+    pub fn evm_config_set_precompiles(handle: *mut EvmConfigHandle, addresses: *const u8, count: usize) -> bool;
+
+    /// Create a new EVM instance with custom configuration
+    /// Config handle is consumed (ownership transferred) and will be freed on evm_destroy;
+    /// returns null on failure
+    pub fn evm_create_with_config(config_handle: *mut EvmConfigHandle, log_level: u8) -> *mut EvmHandle;
+
+    /// Create a new EVM instance with custom configuration, leaving the config to the caller
+    /// The config is only read, so instances can be created from it concurrently; it must
+    /// outlive every instance created from it. Returns null on failure
+    pub fn evm_create_from_config(config_handle: *const EvmConfigHandle, log_level: u8) -> *mut EvmHandle;
+
+    // Per-instance parameters, only reachable through [`ConfigApi`] so that callers handle a
+    // library without them
+    instance {
+        /// Set the block gas limit of an existing instance, in place of its config's
+        /// Kept by `evm_reset`; returns false if the handle is invalid
+        pub fn evm_set_block_gas_limit(handle: *mut EvmHandle, limit: u64) -> bool;
+
+        /// Enable or disable system contract features of an existing instance, in place of its config's
+        /// Kept by `evm_reset`; returns false if the handle is invalid
+        pub fn evm_set_system_contracts(
+            handle: *mut EvmHandle,
+            beacon_roots: bool,
+            block_hashes: bool,
+            deposits: bool,
+            withdrawals: bool,
+        ) -> bool;
+    }
+}
+
+#[link(name = "guillotine_mini")]
+extern "C" {
+    // ===== Frame Introspection and Mutation =====
+    // Valid only on the frame pointer passed to an opcode handler, while the handler runs, or
+    // returned by `evm_get_current_frame`, until execution resumes
 
-    /// Create a new EVM instance
+    /// Call depth of the frame (0 for the top-level frame)
+    pub fn evm_get_call_depth(frame_ptr: usize) -> u16;
+
+    /// Program counter of the frame's current opcode
+    pub fn evm_get_pc(frame_ptr: usize) -> u32;
+
+    /// Opcode the frame is executing
+    pub fn evm_get_current_opcode(frame_ptr: usize) -> u8;
+
+    /// Gas remaining in the frame
+    pub fn evm_get_frame_gas_remaining(frame_ptr: usize) -> i64;
+
+    /// Memory size of the frame in bytes
+    pub fn evm_get_frame_memory_size(frame_ptr: usize) -> usize;
+
+    /// Copy `len` bytes of the frame's memory starting at `offset` into `buf`
     ///
-    /// # Parameters
-    /// - `hardfork_name`: Hardfork name as C string (e.g., "Cancun")
-    /// - `hardfork_len`: Length of hardfork name
-    /// - `log_level`: 0=none, 1=err, 2=warn, 3=info, 4=debug
+    /// Returns the number of bytes copied (fewer than `len` past the end of memory).
+    pub fn evm_get_frame_memory(frame_ptr: usize, offset: usize, len: usize, buf: *mut u8) -> usize;
+
+    /// Number of items on the frame's stack
+    pub fn evm_get_frame_stack_size(frame_ptr: usize) -> usize;
+
+    /// Copy the stack item `index` positions below the top (0 = top) as 32 big-endian bytes
+    /// Returns false if the stack holds fewer items
+    pub fn evm_get_frame_stack_item(frame_ptr: usize, index: usize, out: *mut u8) -> bool;
+
+    /// Copy the address the frame executes in (ADDRESS) into `address_out` (20 bytes)
+    pub fn evm_get_frame_address(frame_ptr: usize, address_out: *mut u8);
+
+    /// Size in bytes of the code the frame executes
+    pub fn evm_get_frame_code_len(frame_ptr: usize) -> usize;
+
+    /// Copy up to `len` bytes of the code the frame executes into `buf`
     ///
-    /// # Returns
-    /// Opaque handle to EVM instance, or null on failure
-    pub fn evm_create(
-        hardfork_name: *const u8,
-        hardfork_len: usize,
-        log_level: u8,
-    ) -> *mut EvmHandle;
+    /// Returns the number of bytes copied.
+    pub fn evm_get_frame_code(frame_ptr: usize, buf: *mut u8, len: usize) -> usize;
 
-    /// Create a new EVM instance with custom configuration
-    /// Config handle is consumed (ownership transferred) and will be freed on evm_destroy
+    /// Size in bytes of the data returned by the last call or creation the frame made
+    pub fn evm_get_frame_return_data_len(frame_ptr: usize) -> usize;
+
+    /// Copy up to `len` bytes of the data returned to the frame (RETURNDATACOPY's source) into `buf`
     ///
-    /// # Parameters
-    /// - `config_handle`: Configuration handle (ownership transferred)
-    /// - `log_level`: 0=none, 1=err, 2=warn, 3=info, 4=debug
+    /// Returns the number of bytes copied.
+    pub fn evm_get_frame_return_data(frame_ptr: usize, buf: *mut u8, len: usize) -> usize;
+
+    /// Pop the top stack item into `out` (32 big-endian bytes)
+    /// Returns false on stack underflow
+    pub fn evm_frame_pop(frame_ptr: usize, out: *mut u8) -> bool;
+
+    /// Push 32 big-endian bytes onto the stack
+    /// Returns false on stack overflow
+    pub fn evm_frame_push(frame_ptr: usize, value: *const u8) -> bool;
+
+    /// Write `len` bytes at `offset` in the frame's memory, expanding it without charging gas
+    /// Returns false past the configured memory limit
+    pub fn evm_set_frame_memory(frame_ptr: usize, offset: usize, data: *const u8, len: usize) -> bool;
+
+    /// Continue execution at `pc` once the handler returns (otherwise at the next opcode)
+    /// Returns false if `pc` is past the end of the code
+    pub fn evm_set_frame_pc(frame_ptr: usize, pc: u32) -> bool;
+
+    /// Charge `amount` gas to the frame
+    /// Returns false, charging nothing, if the frame has less gas remaining
+    pub fn evm_frame_consume_gas(frame_ptr: usize, amount: u64) -> bool;
+
+    // ===== ABI Version =====
+
+    /// C ABI version implemented by the library (see [`ABI_VERSION`])
+    pub fn evm_abi_version() -> u32;
+
+    // ===== Diagnostics =====
+
+    /// Get the error code of the most recent failed call on this thread
+    ///
+    /// 0 = none, 1 = invalid input, 2 = out of memory, 3 = size limit exceeded. Every API call
+    /// resets the diagnostic before running.
+    pub fn evm_get_last_error_code() -> i32;
+
+    /// Copy the message of the most recent failed call on this thread into `buf_out`
+    ///
+    /// Returns the full message length in bytes (0 if there is none); pass a null buffer with
+    /// `buf_len` 0 to query the length.
+    pub fn evm_get_last_error(buf_out: *mut u8, buf_len: usize) -> usize;
+
+    /// Register a process-wide callback receiving log messages instead of stderr
+    ///
+    /// Instances only emit messages at or below the `log_level` they were created with (see
+    /// `LOG_LEVEL_*`). Pass `None` to restore stderr output.
+    pub fn evm_set_log_callback(callback: Option<LogCallback>);
+
+    // ===== EVM Creation =====
+
+    /// Create a new EVM instance for a hardfork id (see [`Hardfork`])
+    ///
+    /// `log_level` is one of the `LOG_LEVEL_*` constants.
     ///
     /// # Returns
-    /// Opaque handle to EVM instance, or null on failure
-    pub fn evm_create_with_config(config_handle: *mut EvmConfigHandle, log_level: u8) -> *mut EvmHandle;
+    /// Opaque handle to EVM instance, or null on failure (including an unknown id)
+    pub fn evm_create_with_hardfork(hardfork: u8, log_level: u8) -> *mut EvmHandle;
 
     /// Destroy an EVM instance
     pub fn evm_destroy(handle: *mut EvmHandle);
@@ -175,6 +630,33 @@ extern "C" {
         blob_base_fee_bytes: *const u8,
     );
 
+    /// Set the excess blob gas of the block (EIP-4844)
+    ///
+    /// guillotine-mini derives BLOBBASEFEE from the excess blob gas and the fork's update
+    /// fraction, replacing the blob base fee passed to `evm_set_blockchain_context`.
+    /// Must be called after `evm_set_blockchain_context`.
+    /// - `update_fraction`: BLOB_BASE_FEE_UPDATE_FRACTION of the fork (EIP-4844, EIP-7691)
+    pub fn evm_set_blob_excess_gas(handle: *mut EvmHandle, excess_blob_gas: u64, update_fraction: u64);
+
+    /// Set the hash returned by BLOCKHASH for a block number
+    ///
+    /// # Parameters
+    /// - `block_number`: Block number the hash belongs to
+    /// - `hash_bytes`: 32-byte block hash
+    ///
+    /// # Returns
+    /// true on success, false on failure
+    pub fn evm_set_block_hash(handle: *mut EvmHandle, block_number: u64, hash_bytes: *const u8) -> bool;
+
+    /// Set the transaction gas price returned by GASPRICE
+    /// - `gas_price_bytes`: 32-byte big-endian u256 (effective gas price)
+    pub fn evm_set_gas_price(handle: *mut EvmHandle, gas_price_bytes: *const u8);
+
+    /// Set the transaction origin returned by ORIGIN
+    /// Must be called after `evm_set_execution_context`, which resets the origin to the caller
+    /// - `origin_bytes`: 20-byte address
+    pub fn evm_set_tx_origin(handle: *mut EvmHandle, origin_bytes: *const u8);
+
     /// Set access list addresses (EIP-2930)
     pub fn evm_set_access_list_addresses(
         handle: *mut EvmHandle,
@@ -196,12 +678,100 @@ extern "C" {
         count: usize,
     ) -> bool;
 
+    /// Register an observer invoked before each opcode is executed
+    /// Pass `None` to remove a previously registered observer
+    /// The context pointer must remain valid until the observer is removed
+    pub fn evm_set_step_callback(
+        handle: *mut EvmHandle,
+        handler: Option<FfiStepHandler>,
+        ctx: *mut std::ffi::c_void,
+    );
+
+    /// Record a struct-log entry for every executed opcode, without per-step callbacks
+    /// The recorded entries are replaced by the next execution
+    /// Returns false if the library was built without native tracing
+    pub fn evm_enable_tracing(handle: *mut EvmHandle, enabled: bool) -> bool;
+
+    /// Set the memory limit in bytes of every frame of later executions
+    /// 0 restores the limit the instance was created with
+    pub fn evm_set_memory_limit(handle: *mut EvmHandle, limit: u64);
+
+    /// Get the number of struct-log entries recorded by the last execution
+    pub fn evm_get_struct_log_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get a struct-log entry by index. Returns true on success.
+    pub fn evm_get_struct_log(handle: *mut EvmHandle, index: usize, log_out: *mut FfiStructLog) -> bool;
+
+    /// Copy the stack of a struct-log entry into `stack_out` (32-byte big-endian words, bottom first)
+    ///
+    /// Returns the number of items copied (at most `max_items`).
+    pub fn evm_get_struct_log_stack(handle: *mut EvmHandle, index: usize, stack_out: *mut u8, max_items: usize)
+        -> usize;
+
+    /// Bound what native tracing records per opcode: the `max_stack_items` top-most stack items
+    /// and the first `max_memory` bytes of memory (no memory with 0, the default)
+    pub fn evm_set_struct_log_limits(handle: *mut EvmHandle, max_stack_items: usize, max_memory: usize);
+
+    /// Copy the memory recorded with a struct-log entry into `buffer_out`
+    ///
+    /// Returns the length of the recorded memory; at most `capacity` bytes are copied, so a null
+    /// buffer with zero capacity queries the length.
+    pub fn evm_get_struct_log_memory(handle: *mut EvmHandle, index: usize, buffer_out: *mut u8, capacity: usize)
+        -> usize;
+
+    /// Register callbacks that load missing accounts, code and storage during execution
+    /// Pass a null `callbacks` pointer to remove previously registered callbacks
+    /// The callbacks table and context pointer must remain valid until they are removed
+    pub fn evm_set_host_callbacks(
+        handle: *mut EvmHandle,
+        callbacks: *const FfiHostCallbacks,
+        ctx: *mut std::ffi::c_void,
+    );
+
+    /// Get blob gas used by the transaction (EIP-4844, blob count * GAS_PER_BLOB)
+    pub fn evm_get_blob_gas_used(handle: *mut EvmHandle) -> u64;
+
     /// Execute the transaction
     ///
     /// # Returns
     /// true if execution completed (success or revert), false on error
     pub fn evm_execute(handle: *mut EvmHandle) -> bool;
 
+    /// Clear bytecode, state, logs, access lists and gas counters for reuse of the instance
+    ///
+    /// The hardfork, attached code cache and registered callbacks are kept; a pending interrupt
+    /// is cleared. Returns false if the handle is invalid.
+    pub fn evm_reset(handle: *mut EvmHandle) -> bool;
+
+    /// Ask a running execution to stop before its next opcode (`EVM_STATUS_INTERRUPTED`)
+    ///
+    /// The only call that may be made from another thread while the handle is executing. An
+    /// interrupt requested while nothing runs stays pending and stops the next execution
+    /// until `evm_reset` clears it.
+    pub fn evm_request_interrupt(handle: *mut EvmHandle);
+
+    /// Execute the next opcode of the transaction (see `EVM_STEP_*`)
+    ///
+    /// The first call starts execution from the configured context, like `evm_execute`; once
+    /// `EVM_STEP_FINISHED` is returned the results are available and further calls do nothing.
+    pub fn evm_step(handle: *mut EvmHandle) -> u8;
+
+    /// Frame executing the next opcode, for the frame introspection functions
+    ///
+    /// Valid from a step callback or between `evm_step` calls, until execution resumes; 0 before
+    /// the first step and once execution finished.
+    pub fn evm_get_current_frame(handle: *mut EvmHandle) -> usize;
+
+    /// Memory size in bytes of the frame executing the next opcode
+    ///
+    /// Meaningful between `evm_step` calls; 0 before the first step and once execution finished.
+    pub fn evm_get_memory_size(handle: *mut EvmHandle) -> usize;
+
+    /// Copy `len` bytes of the memory of the frame executing the next opcode, starting at `offset`
+    ///
+    /// Returns the number of bytes copied (fewer than `len` past the end of memory).
+    pub fn evm_get_memory(handle: *mut EvmHandle, offset: usize, len: usize, buf: *mut u8) -> usize;
+
     /// Get remaining gas after execution
     pub fn evm_get_gas_remaining(handle: *mut EvmHandle) -> i64;
 
@@ -211,6 +781,9 @@ extern "C" {
     /// Check if execution was successful (not reverted)
     pub fn evm_is_success(handle: *mut EvmHandle) -> bool;
 
+    /// Get the execution status code (one of the `EVM_STATUS_*` constants)
+    pub fn evm_get_status(handle: *mut EvmHandle) -> u8;
+
     /// Get length of output data
     pub fn evm_get_output_len(handle: *mut EvmHandle) -> usize;
 
@@ -224,6 +797,17 @@ extern "C" {
         buffer_len: usize,
     ) -> usize;
 
+    /// Get length of the data passed to REVERT by the top-level frame (0 unless it reverted)
+    pub fn evm_get_revert_data_len(handle: *mut EvmHandle) -> usize;
+
+    /// Copy the revert data of the top-level frame to buffer
+    ///
+    /// Kept apart from the output so a REVERT payload is never mistaken for RETURN data.
+    ///
+    /// # Returns
+    /// Number of bytes copied (min of buffer_len and actual revert data length)
+    pub fn evm_get_revert_data(handle: *mut EvmHandle, buffer: *mut u8, buffer_len: usize) -> usize;
+
     /// Set storage value (for pre-state setup)
     ///
     /// # Parameters
@@ -250,6 +834,21 @@ extern "C" {
         value_bytes: *mut u8,
     ) -> bool;
 
+    /// Get the original (pre-transaction) value of a storage slot, as recorded by the journal
+    ///
+    /// This is the value EIP-2200/EIP-3529 SSTORE gas and refunds are computed against.
+    ///
+    /// # Parameters
+    /// - `address_bytes`: 20-byte contract address
+    /// - `key_bytes`: 32-byte storage key (big-endian u256)
+    /// - `value_bytes`: Output buffer for 32-byte storage value (big-endian u256)
+    pub fn evm_get_original_storage(
+        handle: *mut EvmHandle,
+        address_bytes: *const u8,
+        key_bytes: *const u8,
+        value_bytes: *mut u8,
+    ) -> bool;
+
     /// Set account balance (for pre-state setup)
     ///
     /// # Parameters
@@ -261,6 +860,48 @@ extern "C" {
         balance_bytes: *const u8,
     ) -> bool;
 
+    /// Get account balance (for post-state readback)
+    ///
+    /// # Parameters
+    /// - `address_bytes`: 20-byte account address
+    /// - `balance_out`: Output buffer for 32-byte balance (big-endian u256)
+    ///
+    /// # Returns
+    /// true on success, false on failure
+    pub fn evm_get_balance(
+        handle: *mut EvmHandle,
+        address_bytes: *const u8,
+        balance_out: *mut u8,
+    ) -> bool;
+
+    /// Get account nonce (for post-state readback)
+    ///
+    /// # Parameters
+    /// - `address_bytes`: 20-byte account address
+    /// - `nonce_out`: Output for the nonce value
+    ///
+    /// # Returns
+    /// true on success, false on failure
+    pub fn evm_get_nonce(
+        handle: *mut EvmHandle,
+        address_bytes: *const u8,
+        nonce_out: *mut u64,
+    ) -> bool;
+
+    /// Get length of account code (for post-state readback)
+    pub fn evm_get_code_len(handle: *mut EvmHandle, address_bytes: *const u8) -> usize;
+
+    /// Copy account code to buffer
+    ///
+    /// # Returns
+    /// Number of bytes copied (min of buffer_len and actual code length)
+    pub fn evm_get_code(
+        handle: *mut EvmHandle,
+        address_bytes: *const u8,
+        buffer: *mut u8,
+        buffer_len: usize,
+    ) -> usize;
+
     /// Set account code (for pre-state setup)
     ///
     /// # Parameters
@@ -274,6 +915,16 @@ extern "C" {
         code_len: usize,
     ) -> bool;
 
+    /// Set account code hash returned by EXTCODEHASH (for pre-state setup)
+    ///
+    /// # Parameters
+    /// - `address_bytes`: 20-byte account address
+    /// - `hash_bytes`: 32-byte code hash
+    ///
+    /// # Returns
+    /// true on success, false on failure
+    pub fn evm_set_code_hash(handle: *mut EvmHandle, address_bytes: *const u8, hash_bytes: *const u8) -> bool;
+
     /// Set account nonce (for pre-state setup)
     ///
     /// # Parameters
@@ -293,10 +944,16 @@ extern "C" {
     /// Get number of log entries in the last execution
     pub fn evm_get_log_count(handle: *mut EvmHandle) -> usize;
 
+    /// Get the data length of a log entry by index (0 if the index is out of range)
+    pub fn evm_get_log_data_len(handle: *mut EvmHandle, index: usize) -> usize;
+
+    /// Get the number of topics of a log entry by index (0 if the index is out of range)
+    pub fn evm_get_log_topic_count(handle: *mut EvmHandle, index: usize) -> usize;
+
     /// Get a log entry by index. Returns true on success.
     /// - `address_out`: 20-byte buffer
     /// - `topics_count_out`: number of topics returned
-    /// - `topics_out`: buffer for up to 4 topics (4 * 32 bytes)
+    /// - `topics_out`: buffer for `evm_get_log_topic_count` topics (32 bytes each)
     /// - `data_len_out`: actual data length
     /// - `data_out`: data buffer
     /// - `data_max_len`: capacity of `data_out`
@@ -311,12 +968,147 @@ extern "C" {
         data_max_len: usize,
     ) -> bool;
 
+    /// Copy every log entry of the last execution into `buffer_out` in one call
+    ///
+    /// Layout: log count (u32 LE), then per log: address (20 bytes), topic count (u8), topics
+    /// (32 bytes each), data length (u32 LE), data. Returns the total packed length; nothing is
+    /// copied when it exceeds `capacity` (pass a null buffer with capacity 0 to query it).
+    pub fn evm_get_logs_packed(handle: *mut EvmHandle, buffer_out: *mut u8, capacity: usize) -> usize;
+
+    /// Get number of internal calls (message calls and creations) of the last execution
+    pub fn evm_get_call_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get an internal call by index, in the order the calls started
+    pub fn evm_get_call(handle: *mut EvmHandle, index: usize, info_out: *mut FfiCallInfo) -> bool;
+
+    /// Copy the output of an internal call into `buffer_out`
+    ///
+    /// Returns the number of bytes copied (at most `capacity`).
+    pub fn evm_get_call_output(handle: *mut EvmHandle, index: usize, buffer_out: *mut u8, capacity: usize) -> usize;
+
+    /// Copy the input of an internal call (calldata, or the init code of a creation) into `buffer_out`
+    ///
+    /// Returns the length of the input; at most `capacity` bytes are copied, so a null buffer
+    /// with zero capacity queries the length.
+    pub fn evm_get_call_input(handle: *mut EvmHandle, index: usize, buffer_out: *mut u8, capacity: usize) -> usize;
+
+    /// Get the gas used by the last execution split by category
+    ///
+    /// Returns false if the breakdown is not available (e.g. the library was built without it).
+    pub fn evm_get_gas_breakdown(handle: *mut EvmHandle, breakdown_out: *mut FfiGasBreakdown) -> bool;
+
     /// Get gas refund counter after execution
     pub fn evm_get_gas_refund(handle: *mut EvmHandle) -> u64;
 
     /// Get number of storage changes (entries present in storage map)
     pub fn evm_get_storage_change_count(handle: *mut EvmHandle) -> usize;
 
+    /// Get number of non-zero transient storage entries (EIP-1153) at the end of the last execution
+    pub fn evm_get_transient_storage_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get a transient storage entry by index. Returns true on success.
+    /// - `address_out`: 20-byte buffer
+    /// - `slot_out`: 32-byte buffer (big-endian u256)
+    /// - `value_out`: 32-byte buffer (big-endian u256)
+    pub fn evm_get_transient_storage(
+        handle: *mut EvmHandle,
+        index: usize,
+        address_out: *mut u8,
+        slot_out: *mut u8,
+        value_out: *mut u8,
+    ) -> bool;
+
+    /// Get number of accounts self-destructed during the last execution
+    pub fn evm_get_selfdestruct_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get a self-destructed account by index. Returns true on success.
+    /// - `address_out`: 20-byte buffer for the destroyed account
+    /// - `beneficiary_out`: 20-byte buffer for the account that received its balance
+    /// - `balance_out`: 32-byte big-endian buffer for the balance moved to the beneficiary
+    pub fn evm_get_selfdestruct(
+        handle: *mut EvmHandle,
+        index: usize,
+        address_out: *mut u8,
+        beneficiary_out: *mut u8,
+        balance_out: *mut u8,
+    ) -> bool;
+
+    /// Get number of contracts created during the last execution (at any call depth)
+    pub fn evm_get_created_contract_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get a created contract by index, in creation order. Returns true on success.
+    /// - `address_out`: 20-byte buffer for the new contract
+    /// - `deployer_out`: 20-byte buffer for the account that executed CREATE/CREATE2
+    /// - `init_code_hash_out`: 32-byte buffer for the keccak256 of the init code
+    /// - `code_len_out`: length of the deployed runtime code
+    pub fn evm_get_created_contract(
+        handle: *mut EvmHandle,
+        index: usize,
+        address_out: *mut u8,
+        deployer_out: *mut u8,
+        init_code_hash_out: *mut u8,
+        code_len_out: *mut usize,
+    ) -> bool;
+
+    /// Copy the runtime code of a created contract into `code_out`
+    ///
+    /// Returns the number of bytes copied (at most `capacity`).
+    pub fn evm_get_created_contract_code(
+        handle: *mut EvmHandle,
+        index: usize,
+        code_out: *mut u8,
+        capacity: usize,
+    ) -> usize;
+
+    /// Get number of accounts whose balance, nonce or code changed during the last execution
+    pub fn evm_get_touched_account_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get a touched account by index. Returns true on success.
+    /// - `address_out`: 20-byte buffer
+    pub fn evm_get_touched_account(handle: *mut EvmHandle, index: usize, address_out: *mut u8) -> bool;
+
+    /// Start enumerating every account touched by the last execution
+    ///
+    /// The enumeration covers accounts that were accessed, had their balance, nonce or code
+    /// changed, or hold a storage change, each reported once in first-touch order. Calling it
+    /// again restarts the enumeration. Returns false if the handle is invalid.
+    pub fn evm_touched_accounts_begin(handle: *mut EvmHandle) -> bool;
+
+    /// Get the next touched account of the enumeration started by `evm_touched_accounts_begin`
+    ///
+    /// Returns false once every account has been reported.
+    /// - `address_out`: 20-byte buffer
+    pub fn evm_touched_accounts_next(handle: *mut EvmHandle, address_out: *mut u8) -> bool;
+
+    /// Get number of distinct addresses accessed during the last execution
+    pub fn evm_get_accessed_address_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get an accessed address by index (in first-access order). Returns true on success.
+    /// - `address_out`: 20-byte buffer
+    pub fn evm_get_accessed_address(handle: *mut EvmHandle, index: usize, address_out: *mut u8) -> bool;
+
+    /// Whether an address is warm (EIP-2929) in the access tracker at the end of the last execution
+    /// - `address_bytes`: 20-byte address
+    pub fn evm_is_address_warm(handle: *mut EvmHandle, address_bytes: *const u8) -> bool;
+
+    /// Whether a storage slot is warm (EIP-2929) in the access tracker at the end of the last execution
+    /// - `address_bytes`: 20-byte address
+    /// - `slot_bytes`: 32-byte storage key (big-endian u256)
+    pub fn evm_is_storage_warm(handle: *mut EvmHandle, address_bytes: *const u8, slot_bytes: *const u8) -> bool;
+
+    /// Get number of distinct storage slots accessed during the last execution
+    pub fn evm_get_accessed_storage_count(handle: *mut EvmHandle) -> usize;
+
+    /// Get an accessed storage slot by index (in first-access order). Returns true on success.
+    /// - `address_out`: 20-byte buffer
+    /// - `slot_out`: 32-byte buffer (big-endian u256)
+    pub fn evm_get_accessed_storage(
+        handle: *mut EvmHandle,
+        index: usize,
+        address_out: *mut u8,
+        slot_out: *mut u8,
+    ) -> bool;
+
     /// Get storage change by index. Returns true on success.
     /// - `address_out`: 20-byte buffer
     /// - `slot_out`: 32-byte buffer (big-endian u256)
@@ -328,6 +1120,65 @@ extern "C" {
         slot_out: *mut u8,
         value_out: *mut u8,
     ) -> bool;
+
+    // ===== Code Cache =====
+
+    /// Create an empty bytecode analysis cache
+    ///
+    /// The cache is independent of any EVM instance and may outlive the instances it is
+    /// attached to.
+    ///
+    /// # Returns
+    /// Opaque handle to the cache, or null on allocation failure
+    pub fn evm_code_cache_create() -> *mut CodeCacheHandle;
+
+    /// Destroy a bytecode analysis cache
+    ///
+    /// All EVM instances it was attached to must have been destroyed first.
+    pub fn evm_code_cache_destroy(cache: *mut CodeCacheHandle);
+
+    /// Register bytecode under its code hash, running jumpdest analysis once
+    ///
+    /// Registering a hash that is already present is a no-op.
+    ///
+    /// # Parameters
+    /// - `hash_bytes`: 32-byte code hash
+    /// - `code`: Bytecode
+    /// - `code_len`: Length of bytecode
+    ///
+    /// # Returns
+    /// true on success, false on allocation failure
+    pub fn evm_code_cache_register(
+        cache: *mut CodeCacheHandle,
+        hash_bytes: *const u8,
+        code: *const u8,
+        code_len: usize,
+    ) -> bool;
+
+    /// Attach a bytecode analysis cache to an EVM instance
+    ///
+    /// # Returns
+    /// true on success, false on failure
+    pub fn evm_attach_code_cache(handle: *mut EvmHandle, cache: *mut CodeCacheHandle) -> bool;
+
+    /// Set the bytecode for execution from the attached cache
+    ///
+    /// # Parameters
+    /// - `hash_bytes`: 32-byte code hash of previously registered bytecode
+    ///
+    /// # Returns
+    /// true on success, false if no cache is attached or the hash is not registered
+    pub fn evm_set_bytecode_by_hash(handle: *mut EvmHandle, hash_bytes: *const u8) -> bool;
+
+    /// Set account code from the attached cache (for pre-state setup)
+    ///
+    /// # Parameters
+    /// - `address_bytes`: 20-byte account address
+    /// - `hash_bytes`: 32-byte code hash of previously registered bytecode
+    ///
+    /// # Returns
+    /// true on success, false if no cache is attached or the hash is not registered
+    pub fn evm_set_code_by_hash(handle: *mut EvmHandle, address_bytes: *const u8, hash_bytes: *const u8) -> bool;
 }
 
 #[cfg(test)]
@@ -337,7 +1188,7 @@ mod tests {
     #[test]
     fn test_ffi_create_destroy() {
         unsafe {
-            let handle = evm_create(b"Cancun".as_ptr(), 6, 0);
+            let handle = evm_create_with_hardfork(Hardfork::Cancun.id(), 0);
             assert!(!handle.is_null(), "Failed to create EVM handle");
             evm_destroy(handle);
         }
@@ -0,0 +1,84 @@
+//! Argument validation for FFI calls
+//!
+//! The safe wrappers check their arguments here before crossing into guillotine-mini, so invalid
+//! input is rejected with an error instead of reaching Zig code, where it would abort the
+//! process or corrupt memory.
+//!
+//! Handle liveness is tracked for instances owned by `OwnedEvmHandle`: once dropped, its
+//! pointer is rejected until a new instance is created at the same address. Handles created
+//! directly with `ffi::evm_create_with_hardfork` are only checked for null.
+
+use super::ffi::EvmHandle;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+/// Addresses of destroyed `OwnedEvmHandle` instances
+fn destroyed_handles() -> &'static Mutex<HashSet<usize>> {
+    static DESTROYED: OnceLock<Mutex<HashSet<usize>>> = OnceLock::new();
+    DESTROYED.get_or_init(Default::default)
+}
+
+/// Record that an instance was created at `handle`
+pub(crate) fn register_handle(handle: *mut EvmHandle) {
+    destroyed_handles().lock().unwrap_or_else(|e| e.into_inner()).remove(&(handle as usize));
+}
+
+/// Record that the instance at `handle` was destroyed
+pub(crate) fn unregister_handle(handle: *mut EvmHandle) {
+    destroyed_handles().lock().unwrap_or_else(|e| e.into_inner()).insert(handle as usize);
+}
+
+/// Reject null and destroyed EVM handles
+pub(crate) fn check_handle(handle: *mut EvmHandle) -> Result<(), &'static str> {
+    if handle.is_null() {
+        return Err("null handle");
+    }
+    if destroyed_handles().lock().unwrap_or_else(|e| e.into_inner()).contains(&(handle as usize)) {
+        return Err("destroyed handle");
+    }
+    Ok(())
+}
+
+/// Check that an output buffer of `capacity` bytes can hold `required` bytes
+pub(crate) fn check_buffer(capacity: usize, required: usize) -> Result<(), &'static str> {
+    if capacity < required {
+        return Err("output buffer too small");
+    }
+    Ok(())
+}
+
+/// Check that a packed array of `len` bytes holds exactly `count` items of `item_size` bytes
+pub(crate) fn check_packed_len(len: usize, count: usize, item_size: usize) -> Result<(), &'static str> {
+    if count.checked_mul(item_size) != Some(len) {
+        return Err("packed array length does not match item count");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_handle_rejects_null_and_destroyed() {
+        assert_eq!(check_handle(std::ptr::null_mut()), Err("null handle"));
+
+        // Never dereferenced: only the address is tracked
+        let handle = 0x1000 as *mut EvmHandle;
+        register_handle(handle);
+        assert_eq!(check_handle(handle), Ok(()));
+        unregister_handle(handle);
+        assert_eq!(check_handle(handle), Err("destroyed handle"));
+        register_handle(handle);
+        assert_eq!(check_handle(handle), Ok(()), "A new instance may reuse the address");
+    }
+
+    #[test]
+    fn test_check_lengths() {
+        assert_eq!(check_buffer(32, 32), Ok(()));
+        assert_eq!(check_buffer(31, 32), Err("output buffer too small"));
+        assert_eq!(check_packed_len(40, 2, 20), Ok(()));
+        assert_eq!(check_packed_len(41, 2, 20), Err("packed array length does not match item count"));
+        assert_eq!(check_packed_len(0, usize::MAX, 20), Err("packed array length does not match item count"));
+    }
+}
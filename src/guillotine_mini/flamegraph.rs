@@ -0,0 +1,109 @@
+//! Gas flamegraphs
+//!
+//! [`folded_stacks`] folds the call tree recorded by a
+//! [`CallTracer`](super::call_tracer::CallTracer) into the folded-stack format read by
+//! `inferno-flamegraph` and `flamegraph.pl`, weighting every call by the gas it used itself, so
+//! the width of a frame in the rendered flamegraph is the gas spent in it and its callees:
+//!
+//! ```text
+//! CALL 0x5FbDB2315678afecb367f032d93F642f64180aa3:0xa9059cbb 2412
+//! CALL 0x5FbDB2315678afecb367f032d93F642f64180aa3:0xa9059cbb;STATICCALL 0xe7f1...:0x70a08231 1093
+//! ```
+
+use super::{call_trace::CallKind, call_tracer::CallFrame};
+use std::fmt::Write;
+
+/// Folded stacks of a call tree, one line per call that used gas itself
+///
+/// Each line holds the semicolon-separated frames from the root down to a call, then the gas
+/// the call used outside the calls it made. A frame is labelled with the call kind, the callee
+/// and, for calls with calldata, the 4-byte selector. Pipe the output to
+/// `inferno-flamegraph --countname gas` to render it.
+pub fn folded_stacks(root: &CallFrame) -> String {
+    let mut out = String::new();
+    fold(root, &mut String::new(), &mut out);
+    out
+}
+
+fn fold(frame: &CallFrame, stack: &mut String, out: &mut String) {
+    let len = stack.len();
+    if !stack.is_empty() {
+        stack.push(';');
+    }
+    push_label(frame, stack);
+
+    let children: u64 = frame.calls.iter().map(|call| call.gas_used).sum();
+    let self_gas = frame.gas_used.saturating_sub(children);
+    if self_gas > 0 {
+        let _ = writeln!(out, "{stack} {self_gas}");
+    }
+    for call in &frame.calls {
+        fold(call, stack, out);
+    }
+    stack.truncate(len);
+}
+
+fn push_label(frame: &CallFrame, label: &mut String) {
+    label.push_str(match frame.kind {
+        CallKind::Call => "CALL",
+        CallKind::CallCode => "CALLCODE",
+        CallKind::DelegateCall => "DELEGATECALL",
+        CallKind::StaticCall => "STATICCALL",
+        CallKind::Create => "CREATE",
+        CallKind::Create2 => "CREATE2",
+    });
+    if let Some(to) = frame.to {
+        let _ = write!(label, " {to}");
+    }
+    if !frame.kind.is_create() && frame.input.len() >= 4 {
+        let _ = write!(label, ":0x{}", hex_selector(&frame.input[..4]));
+    }
+}
+
+fn hex_selector(selector: &[u8]) -> String {
+    selector.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{Address, Bytes};
+
+    fn frame(kind: CallKind, to: u8, input: &[u8], gas_used: u64, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            kind,
+            from: Address::ZERO,
+            to: Some(Address::with_last_byte(to)),
+            value: None,
+            gas: 100_000,
+            gas_used,
+            input: Bytes::copy_from_slice(input),
+            output: Bytes::new(),
+            error: None,
+            revert_reason: None,
+            logs: Vec::new(),
+            calls,
+        }
+    }
+
+    #[test]
+    fn test_folded_stacks_attribute_self_gas() {
+        let inner = frame(CallKind::StaticCall, 2, &[0x70, 0xa0, 0x82, 0x31], 1_000, Vec::new());
+        let idle = frame(CallKind::Call, 3, &[], 0, Vec::new());
+        let root = frame(CallKind::Call, 1, &[0xa9, 0x05, 0x9c, 0xbb, 0x00], 3_500, vec![inner, idle]);
+
+        let a = Address::with_last_byte(1);
+        let b = Address::with_last_byte(2);
+        assert_eq!(
+            folded_stacks(&root),
+            format!("CALL {a}:0xa9059cbb 2500\nCALL {a}:0xa9059cbb;STATICCALL {b}:0x70a08231 1000\n")
+        );
+    }
+
+    #[test]
+    fn test_folded_stacks_label_creations() {
+        let mut root = frame(CallKind::Create, 1, &[0x60, 0x80, 0x60, 0x40], 50_000, Vec::new());
+        root.to = None;
+        assert_eq!(folded_stacks(&root), "CREATE 50000\n");
+    }
+}
@@ -0,0 +1,135 @@
+//! Mainnet-fork simulation against a JSON-RPC node
+//!
+//! [`GuillotineMiniEvm::fork`] wires an `AlloyDB` (state read over RPC at a fixed block) behind a
+//! `CacheDB`, and configures the chain id, hardfork and block environment from the forked block.
+//! State fetched once stays cached, so repeated transactions only pay for the RPC round trips of
+//! accounts and slots they have not touched before.
+
+use super::{
+    error::EvmAdapterError,
+    evm::GuillotineMiniEvm,
+    sync_strategy::{AccessListSlots, CommonSlots, SlotPlan, SyncStrategy},
+    validation,
+};
+use alloy::{
+    eips::BlockId,
+    network::Ethereum,
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::Header,
+    transports::{TransportError, TransportErrorKind},
+};
+use revm::{
+    context::{BlockEnv, CfgEnv, Context, TxEnv},
+    database::{AlloyDB, CacheDB, WrapDatabaseAsync},
+    primitives::{
+        hardfork::SpecId,
+        Address, U256,
+    },
+    MainContext,
+};
+use std::future::Future;
+use tokio::runtime::{Handle, Runtime};
+
+/// Database of a forked EVM: RPC state at the fork block, cached locally
+pub type ForkDB = CacheDB<WrapDatabaseAsync<AlloyDB<Ethereum, DynProvider>>>;
+
+/// REVM context used by a forked EVM
+pub type ForkContext = Context<BlockEnv, TxEnv, CfgEnv, ForkDB>;
+
+impl GuillotineMiniEvm<ForkContext> {
+    /// Fork the chain served by the JSON-RPC endpoint at `url`, at `block_number`
+    ///
+    /// The block environment, chain id and hardfork are taken from the forked block, and account
+    /// and storage reads are served from the state after that block. Works both inside a
+    /// multi-threaded tokio runtime and outside any runtime (a private runtime is started).
+    ///
+    /// Before each transaction, the slots declared in its access list are synced alongside the
+    /// called contract's leading slots, so transactions carrying an access list (see
+    /// `create_access_list`) fetch their whole pre-state up front.
+    pub fn fork(url: &str, block_number: u64) -> Result<Self, EvmAdapterError<TransportError>> {
+        let url = url.parse().map_err(|_| EvmAdapterError::Db(TransportErrorKind::custom_str("invalid RPC url")))?;
+        let provider = ProviderBuilder::new().connect_http(url).erased();
+
+        let runtime = match Handle::try_current() {
+            Ok(_) => None,
+            Err(_) => Some(
+                Runtime::new().map_err(|_| EvmAdapterError::Db(TransportErrorKind::custom_str("tokio runtime")))?,
+            ),
+        };
+
+        // Header and chain id are fetched concurrently
+        let fetch = async {
+            tokio::try_join!(provider.get_block_by_number(block_number.into()), provider.get_chain_id())
+        };
+        let (block, chain_id) = block_on(runtime.as_ref(), fetch).map_err(EvmAdapterError::Db)?;
+        let header = block
+            .ok_or_else(|| EvmAdapterError::Db(TransportErrorKind::custom_str("fork block not found")))?
+            .header;
+
+        let alloy_db = AlloyDB::new(provider, BlockId::number(block_number));
+        let db = match runtime {
+            Some(runtime) => WrapDatabaseAsync::with_runtime(alloy_db, runtime),
+            None => WrapDatabaseAsync::with_handle(alloy_db, Handle::current()),
+        };
+
+        let spec = spec_for_header(&header);
+        let ctx = Context::mainnet()
+            .with_db(CacheDB::new(db))
+            .modify_cfg_chained(|cfg| {
+                cfg.chain_id = chain_id;
+                cfg.spec = spec;
+            })
+            .modify_block_chained(|block| *block = block_env(&header, spec));
+
+        let mut evm = Self::try_new(ctx)?;
+        evm.set_sync_strategy(|tx: &TxEnv, contract: Address| -> SlotPlan {
+            let mut plan = CommonSlots::default().storage_slots(tx, contract);
+            plan.extend(AccessListSlots.storage_slots(tx, contract));
+            plan
+        });
+        Ok(evm)
+    }
+}
+
+/// Run `fut` to completion on `runtime`, or on the ambient runtime when there is none
+fn block_on<F: Future>(runtime: Option<&Runtime>, fut: F) -> F::Output {
+    match runtime {
+        Some(runtime) => runtime.block_on(fut),
+        None => tokio::task::block_in_place(|| Handle::current().block_on(fut)),
+    }
+}
+
+/// Latest hardfork whose header fields are present in `header`
+fn spec_for_header(header: &Header) -> SpecId {
+    if header.requests_hash.is_some() {
+        SpecId::PRAGUE
+    } else if header.excess_blob_gas.is_some() {
+        SpecId::CANCUN
+    } else if header.withdrawals_root.is_some() {
+        SpecId::SHANGHAI
+    } else if header.difficulty.is_zero() {
+        SpecId::MERGE
+    } else if header.base_fee_per_gas.is_some() {
+        SpecId::LONDON
+    } else {
+        SpecId::BERLIN
+    }
+}
+
+/// Block environment of the forked block
+fn block_env(header: &Header, spec: SpecId) -> BlockEnv {
+    let mut block = BlockEnv {
+        number: U256::from(header.number),
+        beneficiary: header.beneficiary,
+        timestamp: U256::from(header.timestamp),
+        gas_limit: header.gas_limit,
+        basefee: header.base_fee_per_gas.unwrap_or_default(),
+        difficulty: header.difficulty,
+        prevrandao: spec.is_enabled_in(SpecId::MERGE).then_some(header.mix_hash),
+        blob_excess_gas_and_price: None,
+    };
+    if let Some(excess_blob_gas) = header.excess_blob_gas {
+        block.set_blob_excess_gas_and_price(excess_blob_gas, validation::blob_base_fee_update_fraction(spec));
+    }
+    block
+}
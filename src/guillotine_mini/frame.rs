@@ -0,0 +1,151 @@
+//! Call frame introspection and mutation
+//!
+//! guillotine-mini passes custom opcode handlers an opaque pointer to the executing call frame.
+//! [`FrameView`] wraps it: it reads the frame's execution context (call depth, program counter,
+//! current opcode, remaining gas, address, code, return data), stack and memory through the FFI, and lets
+//! a handler implement an opcode by popping and pushing stack items, writing memory, charging
+//! gas and jumping.
+
+use super::{ffi, memory::MemoryView, types};
+use revm::primitives::{Address, Bytes, U256};
+use std::marker::PhantomData;
+
+/// View of a call frame, valid while the callback that received it runs
+///
+/// Mutations apply to the frame immediately, so a handler checks what it needs (gas, stack
+/// items) before changing anything. Memory writes do not charge expansion gas; a handler charges
+/// what its opcode costs with [`consume_gas`](Self::consume_gas).
+pub struct FrameView<'a> {
+    frame_ptr: usize,
+    _frame: PhantomData<&'a ()>,
+}
+
+impl<'a> FrameView<'a> {
+    /// Wrap a frame pointer received from guillotine-mini; `None` if it is null
+    ///
+    /// # Safety
+    ///
+    /// `frame_ptr` must be null or point to a frame that stays live for `'a`.
+    pub unsafe fn from_raw(frame_ptr: usize) -> Option<Self> {
+        (frame_ptr != 0).then_some(Self { frame_ptr, _frame: PhantomData })
+    }
+
+    /// Raw frame pointer, as passed by guillotine-mini
+    pub fn as_raw(&self) -> usize {
+        self.frame_ptr
+    }
+
+    /// Call depth of the frame (0 for the top-level frame)
+    pub fn depth(&self) -> usize {
+        unsafe { ffi::evm_get_call_depth(self.frame_ptr) as usize }
+    }
+
+    /// Program counter of the current opcode
+    pub fn pc(&self) -> usize {
+        unsafe { ffi::evm_get_pc(self.frame_ptr) as usize }
+    }
+
+    /// Opcode being executed
+    pub fn opcode(&self) -> u8 {
+        unsafe { ffi::evm_get_current_opcode(self.frame_ptr) }
+    }
+
+    /// Gas remaining in the frame
+    pub fn gas_remaining(&self) -> u64 {
+        unsafe { ffi::evm_get_frame_gas_remaining(self.frame_ptr) }.max(0) as u64
+    }
+
+    /// Address the frame executes in (ADDRESS)
+    pub fn address(&self) -> Address {
+        let mut address = [0u8; 20];
+        unsafe { ffi::evm_get_frame_address(self.frame_ptr, address.as_mut_ptr()) };
+        Address::from(address)
+    }
+
+    /// Code the frame executes
+    pub fn code(&self) -> Bytes {
+        let len = unsafe { ffi::evm_get_frame_code_len(self.frame_ptr) };
+        let mut code = vec![0u8; len];
+        let copied = unsafe { ffi::evm_get_frame_code(self.frame_ptr, code.as_mut_ptr(), len) };
+        code.truncate(copied);
+        code.into()
+    }
+
+    /// Data returned by the last call or creation the frame made
+    pub fn return_data(&self) -> Bytes {
+        let len = unsafe { ffi::evm_get_frame_return_data_len(self.frame_ptr) };
+        let mut data = vec![0u8; len];
+        let copied = unsafe { ffi::evm_get_frame_return_data(self.frame_ptr, data.as_mut_ptr(), len) };
+        data.truncate(copied);
+        data.into()
+    }
+
+    /// Memory of the frame
+    pub fn memory(&self) -> MemoryView<'a> {
+        MemoryView::from_frame(self.frame_ptr)
+    }
+
+    /// Number of items on the stack
+    pub fn stack_len(&self) -> usize {
+        unsafe { ffi::evm_get_frame_stack_size(self.frame_ptr) }
+    }
+
+    /// Stack item `index` positions below the top (0 = top); `None` past the bottom
+    pub fn peek(&self, index: usize) -> Option<U256> {
+        let mut word = [0u8; 32];
+        unsafe { ffi::evm_get_frame_stack_item(self.frame_ptr, index, word.as_mut_ptr()) }
+            .then(|| types::u256_from_be_bytes(&word))
+    }
+
+    /// Pop the top stack item; `None` if the stack is empty
+    pub fn pop(&mut self) -> Option<U256> {
+        let mut word = [0u8; 32];
+        unsafe { ffi::evm_frame_pop(self.frame_ptr, word.as_mut_ptr()) }.then(|| types::u256_from_be_bytes(&word))
+    }
+
+    /// Push `value` onto the stack; fails if the stack is full
+    pub fn push(&mut self, value: U256) -> Result<(), &'static str> {
+        let word = types::u256_to_be_bytes(&value);
+        if !unsafe { ffi::evm_frame_push(self.frame_ptr, word.as_ptr()) } {
+            return Err("stack overflow");
+        }
+        Ok(())
+    }
+
+    /// Write `data` into memory at `offset`, expanding it as needed
+    pub fn write_memory(&mut self, offset: usize, data: &[u8]) -> Result<(), &'static str> {
+        if !unsafe { ffi::evm_set_frame_memory(self.frame_ptr, offset, data.as_ptr(), data.len()) } {
+            return Err("memory limit exceeded");
+        }
+        Ok(())
+    }
+
+    /// Continue at `pc` after the handler returns instead of at the next opcode
+    pub fn jump(&mut self, pc: usize) -> Result<(), &'static str> {
+        let pc = u32::try_from(pc).map_err(|_| "pc out of range")?;
+        if !unsafe { ffi::evm_set_frame_pc(self.frame_ptr, pc) } {
+            return Err("pc out of range");
+        }
+        Ok(())
+    }
+
+    /// Charge `amount` gas to the frame; fails without charging anything if less gas remains
+    pub fn consume_gas(&mut self, amount: u64) -> Result<(), &'static str> {
+        if !unsafe { ffi::evm_frame_consume_gas(self.frame_ptr, amount) } {
+            return Err("out of gas");
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FrameView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameView")
+            .field("depth", &self.depth())
+            .field("pc", &self.pc())
+            .field("opcode", &self.opcode())
+            .field("gas_remaining", &self.gas_remaining())
+            .field("stack_len", &self.stack_len())
+            .finish()
+    }
+}
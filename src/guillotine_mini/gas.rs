@@ -0,0 +1,342 @@
+//! Gas accounting helpers for the guillotine-mini adapter
+//!
+//! Computes the intrinsic gas charged before execution (guillotine-mini only meters
+//! bytecode execution) and validates the raw gas counters reported by guillotine-mini
+//! before they are converted into REVM result types.
+
+use super::ffi::FfiGasBreakdown;
+use revm::{context::TxEnv, primitives::hardfork::SpecId};
+
+/// Base cost of every transaction
+pub const TX_BASE_COST: u64 = 21_000;
+/// Additional cost of a contract creation transaction (Homestead+)
+pub const TX_CREATE_COST: u64 = 32_000;
+/// Cost per zero byte of calldata
+pub const TX_DATA_ZERO_COST: u64 = 4;
+/// Cost per non-zero byte of calldata before Istanbul
+pub const TX_DATA_NON_ZERO_COST_FRONTIER: u64 = 68;
+/// Cost per non-zero byte of calldata (EIP-2028, Istanbul+)
+pub const TX_DATA_NON_ZERO_COST: u64 = 16;
+/// Cost per access list address (EIP-2930, Berlin+)
+pub const ACCESS_LIST_ADDRESS_COST: u64 = 2_400;
+/// Cost per access list storage key (EIP-2930, Berlin+)
+pub const ACCESS_LIST_STORAGE_KEY_COST: u64 = 1_900;
+/// Cost per 32-byte word of initcode (EIP-3860, Shanghai+)
+pub const INITCODE_WORD_COST: u64 = 2;
+/// Cost per authorization tuple (EIP-7702, Prague+)
+pub const PER_AUTHORIZATION_COST: u64 = 25_000;
+/// Cost per calldata token for the calldata floor (EIP-7623, Prague+)
+pub const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+/// Blob gas consumed per blob (EIP-4844, Cancun+)
+pub const GAS_PER_BLOB: u64 = 131_072;
+
+/// Intrinsic gas of a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntrinsicGas {
+    /// Gas charged before execution (base cost, calldata, access list, initcode, authorizations)
+    pub initial: u64,
+    /// Minimum total gas used regardless of execution (EIP-7623, zero before Prague)
+    pub floor: u64,
+}
+
+/// Gas charged during execution, by category
+///
+/// Excludes intrinsic gas. The categories partition the gas charged by opcodes, so
+/// [`total`](Self::total) equals the execution gas used before refunds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GasBreakdown {
+    /// Static and dynamic opcode costs not covered by another category
+    pub execution: u64,
+    /// Memory expansion
+    pub memory: u64,
+    /// SSTORE costs (excluding cold access surcharges)
+    pub storage: u64,
+    /// EIP-2929 cold account and storage access surcharges
+    pub access: u64,
+    /// Refund counter granted (before the EIP-3529 cap)
+    pub refund: u64,
+}
+
+impl GasBreakdown {
+    /// Gas charged across all categories, before refunds
+    pub fn total(&self) -> u64 {
+        self.execution + self.memory + self.storage + self.access
+    }
+}
+
+impl From<FfiGasBreakdown> for GasBreakdown {
+    fn from(raw: FfiGasBreakdown) -> Self {
+        Self {
+            execution: raw.execution,
+            memory: raw.memory,
+            storage: raw.storage,
+            access: raw.access,
+            refund: raw.refund,
+        }
+    }
+}
+
+/// Compute the intrinsic gas of a transaction from its components
+pub fn intrinsic_gas(
+    spec: SpecId,
+    input: &[u8],
+    is_create: bool,
+    access_list_addresses: u64,
+    access_list_storage_keys: u64,
+    authorizations: u64,
+) -> IntrinsicGas {
+    let zero_bytes = input.iter().filter(|b| **b == 0).count() as u64;
+    let non_zero_bytes = input.len() as u64 - zero_bytes;
+
+    let non_zero_cost = if spec.is_enabled_in(SpecId::ISTANBUL) {
+        TX_DATA_NON_ZERO_COST
+    } else {
+        TX_DATA_NON_ZERO_COST_FRONTIER
+    };
+
+    let mut initial = TX_BASE_COST + zero_bytes * TX_DATA_ZERO_COST + non_zero_bytes * non_zero_cost;
+
+    if is_create {
+        if spec.is_enabled_in(SpecId::HOMESTEAD) {
+            initial += TX_CREATE_COST;
+        }
+        if spec.is_enabled_in(SpecId::SHANGHAI) {
+            initial += (input.len() as u64).div_ceil(32) * INITCODE_WORD_COST;
+        }
+    }
+
+    if spec.is_enabled_in(SpecId::BERLIN) {
+        initial += access_list_addresses * ACCESS_LIST_ADDRESS_COST;
+        initial += access_list_storage_keys * ACCESS_LIST_STORAGE_KEY_COST;
+    }
+
+    if spec.is_enabled_in(SpecId::PRAGUE) {
+        initial += authorizations * PER_AUTHORIZATION_COST;
+    }
+
+    let floor = if spec.is_enabled_in(SpecId::PRAGUE) {
+        // A non-zero byte counts as four tokens
+        let tokens = zero_bytes + non_zero_bytes * 4;
+        TX_BASE_COST + tokens * TOTAL_COST_FLOOR_PER_TOKEN
+    } else {
+        0
+    };
+
+    IntrinsicGas { initial, floor }
+}
+
+/// Compute the intrinsic gas of a REVM transaction
+pub fn tx_intrinsic_gas(spec: SpecId, tx: &TxEnv) -> IntrinsicGas {
+    let access_list_addresses = tx.access_list.0.len() as u64;
+    let access_list_storage_keys = tx.access_list.0.iter().map(|item| item.storage_keys.len() as u64).sum();
+
+    intrinsic_gas(
+        spec,
+        &tx.data,
+        tx.kind.is_create(),
+        access_list_addresses,
+        access_list_storage_keys,
+        tx.authorization_list.len() as u64,
+    )
+}
+
+/// Inconsistency detected in the gas counters reported by guillotine-mini
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasAnomaly {
+    /// Engine reported a negative gas used value
+    NegativeGasUsed(i64),
+    /// Engine reported more gas used than the transaction gas limit
+    GasUsedExceedsLimit { gas_used: u64, gas_limit: u64 },
+    /// Engine reported a refund larger than the hardfork refund cap allows
+    RefundExceedsCap { refund: u64, cap: u64 },
+}
+
+impl GasAnomaly {
+    /// Whether no correct engine could ever produce this anomaly
+    ///
+    /// `evm_get_gas_refund` exposes the raw refund counter, which may legitimately
+    /// exceed the cap until the cap is applied, so refund anomalies are only reported.
+    pub fn is_impossible(&self) -> bool {
+        !matches!(self, Self::RefundExceedsCap { .. })
+    }
+}
+
+/// Maximum gas refund for the given gas used
+///
+/// EIP-3529 lowered the cap from `gas_used / 2` to `gas_used / 5` in London.
+#[inline]
+pub fn max_refund(spec: SpecId, gas_used: u64) -> u64 {
+    let quotient = if spec.is_enabled_in(SpecId::LONDON) { 5 } else { 2 };
+    gas_used / quotient
+}
+
+/// Final gas accounting of a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FinalGas {
+    /// Gas charged to the sender after refunds
+    pub gas_used: u64,
+    /// Refund applied (after the hardfork refund cap)
+    pub gas_refunded: u64,
+}
+
+/// Combine gas spent and the raw refund counter into the consensus gas used
+///
+/// The refund is capped by [`max_refund`] of the total gas spent (EIP-3529 from London), and
+/// the result is never below the EIP-7623 floor, in which case no refund is applied.
+pub fn final_gas(spec: SpecId, gas_spent: u64, raw_refund: u64, floor: u64) -> FinalGas {
+    let gas_refunded = raw_refund.min(max_refund(spec, gas_spent));
+    let gas_used = gas_spent - gas_refunded;
+    if gas_used < floor {
+        return FinalGas { gas_used: floor, gas_refunded: 0 };
+    }
+    FinalGas { gas_used, gas_refunded }
+}
+
+/// Price per gas actually paid by the sender
+///
+/// From London, EIP-1559 transactions pay `min(max_fee_per_gas, basefee + max_priority_fee_per_gas)`;
+/// legacy and access list transactions (no priority fee) pay their gas price.
+pub fn effective_gas_price(spec: SpecId, tx: &TxEnv, basefee: u64) -> u128 {
+    match tx.gas_priority_fee {
+        Some(priority_fee) if spec.is_enabled_in(SpecId::LONDON) => {
+            tx.gas_price.min((basefee as u128).saturating_add(priority_fee))
+        }
+        _ => tx.gas_price,
+    }
+}
+
+/// Check raw engine gas counters against the transaction gas limit and refund cap
+///
+/// `gas_used` and `gas_limit` are the execution counters, excluding `intrinsic_gas`; the refund
+/// cap applies to the total gas spent, intrinsic gas included. Returns every anomaly found; an
+/// empty vector means the counters are consistent.
+pub fn check_gas_invariants(
+    spec: SpecId,
+    gas_used: i64,
+    intrinsic_gas: u64,
+    gas_refund: u64,
+    gas_limit: u64,
+) -> Vec<GasAnomaly> {
+    let mut anomalies = Vec::new();
+
+    if gas_used < 0 {
+        anomalies.push(GasAnomaly::NegativeGasUsed(gas_used));
+    }
+
+    let gas_used = gas_used.max(0) as u64;
+    if gas_used > gas_limit {
+        anomalies.push(GasAnomaly::GasUsedExceedsLimit { gas_used, gas_limit });
+    }
+
+    let cap = max_refund(spec, intrinsic_gas.saturating_add(gas_used));
+    if gas_refund > cap {
+        anomalies.push(GasAnomaly::RefundExceedsCap { refund: gas_refund, cap });
+    }
+
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_breakdown_total_excludes_refund() {
+        let raw = FfiGasBreakdown { execution: 12, memory: 3, storage: 2_900, access: 2_100, refund: 4_800 };
+        let breakdown = GasBreakdown::from(raw);
+        assert_eq!(breakdown.refund, 4_800);
+        assert_eq!(breakdown.total(), 5_015);
+    }
+
+    #[test]
+    fn test_final_gas_caps_refund() {
+        // Refund below the cap is applied in full
+        assert_eq!(final_gas(SpecId::CANCUN, 50_000, 4_800, 0), FinalGas { gas_used: 45_200, gas_refunded: 4_800 });
+        // EIP-3529: capped at gas_spent / 5
+        assert_eq!(final_gas(SpecId::CANCUN, 43_212, 19_900, 0), FinalGas { gas_used: 34_570, gas_refunded: 8_642 });
+        // Before London: capped at gas_spent / 2
+        assert_eq!(final_gas(SpecId::BERLIN, 43_212, 19_900, 0), FinalGas { gas_used: 23_312, gas_refunded: 19_900 });
+        // EIP-7623 floor wins and voids the refund
+        assert_eq!(final_gas(SpecId::PRAGUE, 30_000, 5_000, 28_000), FinalGas { gas_used: 28_000, gas_refunded: 0 });
+    }
+
+    #[test]
+    fn test_effective_gas_price() {
+        let legacy = TxEnv { gas_price: 30, ..Default::default() };
+        assert_eq!(effective_gas_price(SpecId::CANCUN, &legacy, 10), 30);
+
+        // Priority fee fits under the max fee
+        let eip1559 = TxEnv { gas_price: 30, gas_priority_fee: Some(2), ..Default::default() };
+        assert_eq!(effective_gas_price(SpecId::CANCUN, &eip1559, 10), 12);
+        // Capped by the max fee
+        assert_eq!(effective_gas_price(SpecId::CANCUN, &eip1559, 29), 30);
+        // No fee market before London
+        assert_eq!(effective_gas_price(SpecId::BERLIN, &eip1559, 10), 30);
+    }
+
+    #[test]
+    fn test_intrinsic_gas_plain_call() {
+        let gas = intrinsic_gas(SpecId::CANCUN, &[], false, 0, 0, 0);
+        assert_eq!(gas, IntrinsicGas { initial: 21_000, floor: 0 });
+    }
+
+    #[test]
+    fn test_intrinsic_gas_calldata_pricing() {
+        // 2 zero bytes + 2 non-zero bytes
+        let data = [0x00, 0x01, 0x00, 0xff];
+        assert_eq!(intrinsic_gas(SpecId::CANCUN, &data, false, 0, 0, 0).initial, 21_000 + 2 * 4 + 2 * 16);
+        assert_eq!(intrinsic_gas(SpecId::BYZANTIUM, &data, false, 0, 0, 0).initial, 21_000 + 2 * 4 + 2 * 68);
+    }
+
+    #[test]
+    fn test_intrinsic_gas_create_and_initcode_words() {
+        let initcode = [0x60u8; 33];
+        // 33 non-zero bytes, create cost, 2 initcode words
+        let expected = 21_000 + 33 * 16 + 32_000 + 2 * 2;
+        assert_eq!(intrinsic_gas(SpecId::CANCUN, &initcode, true, 0, 0, 0).initial, expected);
+        // No initcode word cost before Shanghai
+        assert_eq!(intrinsic_gas(SpecId::MERGE, &initcode, true, 0, 0, 0).initial, expected - 4);
+    }
+
+    #[test]
+    fn test_intrinsic_gas_access_list_and_floor() {
+        let gas = intrinsic_gas(SpecId::PRAGUE, &[0x01], false, 1, 2, 1);
+        assert_eq!(gas.initial, 21_000 + 16 + 2_400 + 2 * 1_900 + 25_000);
+        assert_eq!(gas.floor, 21_000 + 4 * 10);
+    }
+
+    #[test]
+    fn test_max_refund_by_spec() {
+        assert_eq!(max_refund(SpecId::BERLIN, 100_000), 50_000);
+        assert_eq!(max_refund(SpecId::LONDON, 100_000), 20_000);
+        assert_eq!(max_refund(SpecId::CANCUN, 100_000), 20_000);
+    }
+
+    #[test]
+    fn test_consistent_counters_have_no_anomalies() {
+        assert!(check_gas_invariants(SpecId::CANCUN, 50_000, 0, 10_000, 100_000).is_empty());
+    }
+
+    #[test]
+    fn test_slot_clearing_refund_is_within_cap() {
+        // Clearing a slot: ~5k of execution earns a 4.8k refund, within a fifth of the total
+        // including the 21k intrinsic gas
+        assert!(check_gas_invariants(SpecId::CANCUN, 5_000, 21_000, 4_800, 79_000).is_empty());
+    }
+
+    #[test]
+    fn test_detects_impossible_counters() {
+        let anomalies = check_gas_invariants(SpecId::CANCUN, -1, 0, 0, 100_000);
+        assert_eq!(anomalies, vec![GasAnomaly::NegativeGasUsed(-1)]);
+
+        let anomalies = check_gas_invariants(SpecId::CANCUN, 200_000, 0, 0, 100_000);
+        assert_eq!(
+            anomalies,
+            vec![GasAnomaly::GasUsedExceedsLimit { gas_used: 200_000, gas_limit: 100_000 }]
+        );
+
+        let anomalies = check_gas_invariants(SpecId::CANCUN, 50_000, 0, 20_000, 100_000);
+        assert_eq!(anomalies, vec![GasAnomaly::RefundExceedsCap { refund: 20_000, cap: 10_000 }]);
+        assert!(!anomalies[0].is_impossible());
+    }
+}
@@ -0,0 +1,147 @@
+//! Hardforks known to guillotine-mini
+//!
+//! Instances are created from the numeric hardfork id of guillotine-mini's C ABI. The ids and
+//! names are declared once in the table below, mirroring the `Hardfork` enum exported by
+//! `root_c.zig`.
+
+use revm::primitives::hardfork::SpecId;
+
+macro_rules! hardforks {
+    ($($variant:ident = $id:literal,)+) => {
+        /// Hardfork an instance executes under, passed to guillotine-mini by id
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[repr(u8)]
+        pub enum Hardfork {
+            $($variant = $id,)+
+        }
+
+        impl Hardfork {
+            /// Every hardfork, oldest first
+            pub const ALL: &'static [Hardfork] = &[$(Hardfork::$variant,)+];
+
+            /// Hardfork with the given C ABI id
+            pub fn from_id(id: u8) -> Option<Self> {
+                match id {
+                    $($id => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+
+            /// guillotine-mini's name for the hardfork (e.g. "Cancun")
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant),)+
+                }
+            }
+        }
+    };
+}
+
+hardforks! {
+    Frontier = 0,
+    Homestead = 1,
+    Tangerine = 2,
+    Spurious = 3,
+    Byzantium = 4,
+    Constantinople = 5,
+    Istanbul = 6,
+    Berlin = 7,
+    London = 8,
+    Merge = 9,
+    Shanghai = 10,
+    Cancun = 11,
+    Prague = 12,
+    Osaka = 13,
+}
+
+impl Hardfork {
+    /// C ABI id passed to guillotine-mini
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Hardfork guillotine-mini executes a REVM spec under
+    ///
+    /// Specs between hardforks map to the preceding one; specs newer than guillotine-mini
+    /// supports map to Cancun.
+    pub fn from_spec(spec: SpecId) -> Self {
+        match spec {
+            SpecId::FRONTIER | SpecId::FRONTIER_THAWING => Self::Frontier,
+            SpecId::HOMESTEAD | SpecId::DAO_FORK => Self::Homestead,
+            SpecId::TANGERINE => Self::Tangerine,
+            SpecId::SPURIOUS_DRAGON => Self::Spurious,
+            SpecId::BYZANTIUM => Self::Byzantium,
+            SpecId::CONSTANTINOPLE | SpecId::PETERSBURG => Self::Constantinople,
+            SpecId::ISTANBUL | SpecId::MUIR_GLACIER => Self::Istanbul,
+            SpecId::BERLIN => Self::Berlin,
+            SpecId::LONDON | SpecId::ARROW_GLACIER | SpecId::GRAY_GLACIER => Self::London,
+            SpecId::MERGE => Self::Merge,
+            SpecId::SHANGHAI => Self::Shanghai,
+            SpecId::CANCUN => Self::Cancun,
+            SpecId::PRAGUE => Self::Prague,
+            SpecId::OSAKA => Self::Osaka,
+            _ => Self::Cancun, // Default to Cancun
+        }
+    }
+
+    /// REVM spec of the hardfork (the latest spec it covers, e.g. Petersburg for Constantinople)
+    pub fn spec(&self) -> SpecId {
+        match self {
+            Self::Frontier => SpecId::FRONTIER,
+            Self::Homestead => SpecId::HOMESTEAD,
+            Self::Tangerine => SpecId::TANGERINE,
+            Self::Spurious => SpecId::SPURIOUS_DRAGON,
+            Self::Byzantium => SpecId::BYZANTIUM,
+            Self::Constantinople => SpecId::PETERSBURG,
+            Self::Istanbul => SpecId::ISTANBUL,
+            Self::Berlin => SpecId::BERLIN,
+            Self::London => SpecId::LONDON,
+            Self::Merge => SpecId::MERGE,
+            Self::Shanghai => SpecId::SHANGHAI,
+            Self::Cancun => SpecId::CANCUN,
+            Self::Prague => SpecId::PRAGUE,
+            Self::Osaka => SpecId::OSAKA,
+        }
+    }
+}
+
+impl std::str::FromStr for Hardfork {
+    type Err = &'static str;
+
+    /// Parse guillotine-mini's name for a hardfork (case-sensitive, e.g. "Cancun")
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL.iter().copied().find(|hardfork| hardfork.name() == name).ok_or("unknown hardfork name")
+    }
+}
+
+impl std::fmt::Display for Hardfork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hardfork_ids_and_names() {
+        for (index, hardfork) in Hardfork::ALL.iter().enumerate() {
+            assert_eq!(hardfork.id() as usize, index);
+            assert_eq!(Hardfork::from_id(hardfork.id()), Some(*hardfork));
+            assert_eq!(hardfork.name().parse(), Ok(*hardfork));
+        }
+        assert_eq!(Hardfork::from_id(Hardfork::ALL.len() as u8), None);
+        assert_eq!("cancun".parse::<Hardfork>(), Err("unknown hardfork name"));
+    }
+
+    #[test]
+    fn test_hardfork_from_spec() {
+        assert_eq!(Hardfork::from_spec(SpecId::PETERSBURG), Hardfork::Constantinople);
+        assert_eq!(Hardfork::from_spec(SpecId::GRAY_GLACIER), Hardfork::London);
+        assert_eq!(Hardfork::from_spec(SpecId::PRAGUE), Hardfork::Prague);
+        for hardfork in Hardfork::ALL {
+            assert_eq!(Hardfork::from_spec(hardfork.spec()), *hardfork);
+        }
+    }
+}
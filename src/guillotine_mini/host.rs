@@ -0,0 +1,269 @@
+//! On-demand state loading through host callbacks
+//!
+//! guillotine-mini calls back into Rust when execution reads an account, its code or a storage
+//! slot it does not hold yet (SLOAD, BALANCE, EXTCODESIZE/EXTCODECOPY/EXTCODEHASH, calls). The
+//! value is loaded from the REVM database and kept by guillotine-mini as pre-state, so state
+//! that was not synced up front is still read correctly.
+//!
+//! The callbacks reach the database through a raw pointer to the REVM context, borrowed afresh
+//! for each load, so inspector hooks running from step callbacks in between can use the same
+//! context.
+
+use super::ffi::FfiHostCallbacks;
+use super::types;
+use revm::{
+    context_interface::ContextTr,
+    database_interface::Database,
+    primitives::{Address, B256, KECCAK_EMPTY, U256},
+    state::Bytecode,
+};
+use std::any::Any;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Database access for one execution, shared with the callbacks through the FFI context pointer
+pub(crate) struct HostContext<CTX: ContextTr> {
+    /// REVM context holding the database, only dereferenced while a callback runs
+    ctx: *mut CTX,
+    /// Code of loaded accounts, until guillotine-mini copies it
+    code: HashMap<Address, Bytecode>,
+    /// First database error; the failing callback aborts execution
+    pub(crate) error: Option<<CTX::Db as Database>::Error>,
+    /// Panic raised by the database, resumed once execution has returned to Rust
+    pub(crate) panic: Option<Box<dyn Any + Send>>,
+    /// Accounts loaded on demand, with their code hash
+    pub(crate) accounts: Vec<(Address, B256)>,
+    /// Storage slots loaded on demand
+    pub(crate) slots: Vec<(Address, U256)>,
+    /// Bytes of code copied into guillotine-mini
+    pub(crate) code_bytes: u64,
+}
+
+impl<CTX: ContextTr> HostContext<CTX> {
+    /// Host context loading from the database of `ctx`
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must stay valid until the host context is dropped and must not be borrowed
+    /// elsewhere while a callback runs.
+    pub(crate) unsafe fn new(ctx: *mut CTX) -> Self {
+        Self {
+            ctx,
+            code: HashMap::new(),
+            error: None,
+            panic: None,
+            accounts: Vec::new(),
+            slots: Vec::new(),
+            code_bytes: 0,
+        }
+    }
+
+    /// Callback table forwarding to this context's database
+    pub(crate) fn callbacks() -> FfiHostCallbacks {
+        FfiHostCallbacks {
+            load_account: load_account_trampoline::<CTX>,
+            load_code: load_code_trampoline::<CTX>,
+            load_storage: load_storage_trampoline::<CTX>,
+        }
+    }
+
+    /// Run a database access, recording its error or panic; returns `None` on failure
+    fn guard<T>(&mut self, f: impl FnOnce(&mut CTX::Db) -> Result<T, <CTX::Db as Database>::Error>) -> Option<T> {
+        // SAFETY: `new` requires the context to be valid and unborrowed while a callback runs
+        let db = unsafe { (*self.ctx).db_mut() };
+        match panic::catch_unwind(AssertUnwindSafe(|| f(db))) {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                self.error.get_or_insert(e);
+                None
+            }
+            Err(payload) => {
+                self.panic.get_or_insert(payload);
+                None
+            }
+        }
+    }
+}
+
+/// Recover the host context from the FFI context pointer
+///
+/// # Safety
+///
+/// `ctx` must be null or point to a live `HostContext<CTX>` for the duration of execution.
+unsafe fn host<'a, CTX: ContextTr>(ctx: *mut c_void) -> Option<&'a mut HostContext<CTX>> {
+    (ctx as *mut HostContext<CTX>).as_mut()
+}
+
+extern "C" fn load_account_trampoline<CTX: ContextTr>(
+    ctx: *mut c_void,
+    address: *const u8,
+    balance_out: *mut u8,
+    nonce_out: *mut u64,
+    code_hash_out: *mut u8,
+    code_len_out: *mut usize,
+) -> bool {
+    let Some(host) = (unsafe { host::<CTX>(ctx) }) else {
+        return false;
+    };
+    let address = types::address_from_bytes(unsafe { &*(address as *const [u8; 20]) });
+
+    let Some(info) = host.guard(|db| {
+        let Some(mut info) = db.basic(address)? else {
+            return Ok(None);
+        };
+        if info.code.is_none() && info.code_hash != KECCAK_EMPTY {
+            info.code = Some(db.code_by_hash(info.code_hash)?);
+        }
+        Ok(Some(info))
+    }) else {
+        return false;
+    };
+
+    let info = info.unwrap_or_default();
+    let code = info.code.unwrap_or_default();
+    unsafe {
+        std::ptr::copy_nonoverlapping(types::u256_to_be_bytes(&info.balance).as_ptr(), balance_out, 32);
+        *nonce_out = info.nonce;
+        std::ptr::copy_nonoverlapping(info.code_hash.as_ptr(), code_hash_out, 32);
+        *code_len_out = code.bytecode().len();
+    }
+    host.accounts.push((address, info.code_hash));
+    if !code.is_empty() {
+        host.code.insert(address, code);
+    }
+    true
+}
+
+extern "C" fn load_code_trampoline<CTX: ContextTr>(
+    ctx: *mut c_void,
+    address: *const u8,
+    code_out: *mut u8,
+    code_len: usize,
+) -> bool {
+    let Some(host) = (unsafe { host::<CTX>(ctx) }) else {
+        return false;
+    };
+    let address = types::address_from_bytes(unsafe { &*(address as *const [u8; 20]) });
+
+    let Some(code) = host.code.remove(&address) else {
+        return false;
+    };
+    let bytes = code.bytecode();
+    if bytes.len() != code_len {
+        return false;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), code_out, code_len);
+    }
+    host.code_bytes += code_len as u64;
+    true
+}
+
+extern "C" fn load_storage_trampoline<CTX: ContextTr>(
+    ctx: *mut c_void,
+    address: *const u8,
+    slot: *const u8,
+    value_out: *mut u8,
+) -> bool {
+    let Some(host) = (unsafe { host::<CTX>(ctx) }) else {
+        return false;
+    };
+    let address = types::address_from_bytes(unsafe { &*(address as *const [u8; 20]) });
+    let slot = types::u256_from_be_bytes(unsafe { &*(slot as *const [u8; 32]) });
+
+    let Some(value) = host.guard(|db| db.storage(address, slot)) else {
+        return false;
+    };
+    unsafe {
+        std::ptr::copy_nonoverlapping(types::u256_to_be_bytes(&value).as_ptr(), value_out, 32);
+    }
+    host.slots.push((address, slot));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        context::{BlockEnv, CfgEnv, Context, TxEnv},
+        database::{CacheDB, EmptyDB},
+        primitives::{address, Bytes},
+        state::AccountInfo,
+        MainContext,
+    };
+
+    type TestContext = Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDB>>;
+
+    #[test]
+    fn test_host_callbacks_load_from_database() {
+        let contract = address!("1000000000000000000000000000000000000000");
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01, 0x00]));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo { balance: U256::from(9), nonce: 2, code_hash: code.hash_slow(), code: Some(code.clone()) },
+        );
+        db.insert_account_storage(contract, U256::from(0x14), U256::from(7)).unwrap();
+
+        let mut context = Context::mainnet().with_db(db);
+        let mut host = unsafe { HostContext::new(&mut context as *mut TestContext) };
+        let ctx = &mut host as *mut HostContext<TestContext> as *mut c_void;
+        let callbacks = HostContext::<TestContext>::callbacks();
+        let addr_bytes = types::address_to_bytes(&contract);
+
+        let (mut balance, mut nonce, mut code_hash, mut code_len) = ([0u8; 32], 0u64, [0u8; 32], 0usize);
+        assert!((callbacks.load_account)(
+            ctx,
+            addr_bytes.as_ptr(),
+            balance.as_mut_ptr(),
+            &mut nonce,
+            code_hash.as_mut_ptr(),
+            &mut code_len
+        ));
+        assert_eq!((types::u256_from_be_bytes(&balance), nonce, code_len), (U256::from(9), 2, 3));
+
+        let mut code_buf = vec![0u8; code_len];
+        assert!((callbacks.load_code)(ctx, addr_bytes.as_ptr(), code_buf.as_mut_ptr(), code_len));
+        assert_eq!(code_buf, code.bytecode().to_vec());
+
+        let slot_bytes = types::u256_to_be_bytes(&U256::from(0x14));
+        let mut value = [0u8; 32];
+        assert!((callbacks.load_storage)(ctx, addr_bytes.as_ptr(), slot_bytes.as_ptr(), value.as_mut_ptr()));
+        assert_eq!(types::u256_from_be_bytes(&value), U256::from(7));
+
+        assert_eq!(host.accounts, vec![(contract, code.hash_slow())]);
+        assert_eq!(host.slots, vec![(contract, U256::from(0x14))]);
+        assert_eq!(host.code_bytes, 3);
+    }
+
+    /// Inspector hooks borrow the context between host callbacks; run under Miri to check that
+    /// the callbacks keep no borrow of the database across them
+    #[test]
+    fn test_host_callbacks_share_the_context() {
+        let contract = address!("1000000000000000000000000000000000000000");
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_storage(contract, U256::from(1), U256::from(7)).unwrap();
+
+        let mut context = Context::mainnet().with_db(db);
+        let shared = &mut context as *mut TestContext;
+        let mut host = unsafe { HostContext::new(shared) };
+        let ctx = &mut host as *mut HostContext<TestContext> as *mut c_void;
+        let callbacks = HostContext::<TestContext>::callbacks();
+        let addr_bytes = types::address_to_bytes(&contract);
+        let slot_bytes = types::u256_to_be_bytes(&U256::from(1));
+
+        let load = || {
+            let mut value = [0u8; 32];
+            assert!((callbacks.load_storage)(ctx, addr_bytes.as_ptr(), slot_bytes.as_ptr(), value.as_mut_ptr()));
+            types::u256_from_be_bytes(&value)
+        };
+        assert_eq!(load(), U256::from(7));
+        // A hook writing to the database through the context, as an inspector's `step` may
+        let db = unsafe { (*shared).db_mut() };
+        db.insert_account_storage(contract, U256::from(1), U256::from(8)).unwrap();
+        assert_eq!(load(), U256::from(8));
+
+        assert_eq!(host.slots, vec![(contract, U256::from(1)); 2]);
+    }
+}
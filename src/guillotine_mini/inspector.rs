@@ -0,0 +1,844 @@
+//! REVM `Inspector` support
+//!
+//! guillotine-mini executes opcodes outside REVM's interpreter loop, so an `Inspector` is driven
+//! from the step callback instead: `GuillotineMiniEvm::inspect` mirrors each executing frame in
+//! a REVM interpreter holding the frame's code and, before every opcode, loads the frame's
+//! program counter, stack, memory and gas into it and runs `step`; `step_end` runs once the
+//! opcode has executed. `call`, `create`, `initialize_interp`, `log`, `selfdestruct` and the
+//! `_end` hooks run as frames start, emit and return, in the order REVM invokes them.
+//!
+//! Changes an inspector makes to the interpreter's stack, memory and remaining gas are written
+//! back to the frame. guillotine-mini cannot skip or replace a call, so outcomes returned from
+//! `call` and `create` are ignored, as are jumps and halts requested through the interpreter.
+//!
+//! The outcome of the transaction's own frame is exact. Outcomes of internal calls are read off
+//! the caller once it resumes: a halt is reported as the invalid opcode that caused it or
+//! otherwise as running out of gas, the gas spent excludes what the frame's final opcode cost,
+//! and calls that run no code (precompiles, accounts without code) report no gas spent.
+
+use super::{
+    ffi::{self, EvmHandle},
+    frame::FrameView,
+    gas, memory,
+    step::StepInfo,
+    types,
+};
+use revm::{
+    bytecode::{opcode, Bytecode},
+    context::TxEnv,
+    context_interface::{
+        result::{ExecutionResult, HaltReason, Output},
+        CreateScheme,
+    },
+    inspector::Inspector,
+    interpreter::{
+        interpreter::{ExtBytecode, InputsImpl},
+        interpreter_types::Jumps,
+        CallInput, CallInputs, CallOutcome, CallScheme, CallValue, CreateInputs, CreateOutcome, Gas, InstructionResult,
+        Interpreter, InterpreterResult, SharedMemory,
+    },
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::{hardfork::SpecId, Address, Bytes, Log, TxKind, B256, U256},
+};
+use std::any::Any;
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Deepest call depth a frame can run at
+const MAX_CALL_DEPTH: usize = 1024;
+
+/// Hook inputs of a frame
+enum FrameInputs {
+    Call(CallInputs),
+    Create(CreateInputs),
+}
+
+impl FrameInputs {
+    fn set_gas_limit(&mut self, gas_limit: u64) {
+        match self {
+            Self::Call(inputs) => inputs.gas_limit = gas_limit,
+            Self::Create(inputs) => inputs.gas_limit = gas_limit,
+        }
+    }
+
+    fn gas_limit(&self) -> u64 {
+        match self {
+            Self::Call(inputs) => inputs.gas_limit,
+            Self::Create(inputs) => inputs.gas_limit,
+        }
+    }
+}
+
+/// Message call or creation that has not started running code yet
+struct Entry {
+    inputs: FrameInputs,
+    /// Inputs seen by the frame's code
+    interp_inputs: InputsImpl,
+    is_static: bool,
+    /// Call depth the frame runs at
+    depth: usize,
+}
+
+/// Log or SELFDESTRUCT of an opcode, reported once the opcode completed
+enum Effect {
+    Log(Log),
+    SelfDestruct { address: Address, beneficiary: Address, balance: U256 },
+}
+
+/// Frame running code, mirrored in a REVM interpreter
+struct Frame {
+    inputs: FrameInputs,
+    depth: usize,
+    is_static: bool,
+    interp: Interpreter,
+    /// Opcode whose `step_end` is due
+    last: Option<StepInfo>,
+    /// Log or SELFDESTRUCT of that opcode
+    effect: Option<Effect>,
+    /// Data of the frame's RETURN or REVERT
+    output: Bytes,
+}
+
+/// Frame state loaded into an interpreter before a hook, to detect what the hook changed
+struct Loaded {
+    stack: Vec<U256>,
+    memory: Vec<u8>,
+    gas_remaining: u64,
+}
+
+/// Where the EVM publishes its REVM context for the hooks, type-erased
+///
+/// Holds a pointer derived from the EVM's own borrow of the context while execution runs, so
+/// the hooks keep no borrow of their own that the EVM's would invalidate.
+pub(crate) type ContextSlot = Cell<*mut c_void>;
+
+/// Inspector hooks driven by the step callback of an executing transaction
+///
+/// Handed to guillotine-mini as the context of [`inspector_trampoline`]; [`finish`](Self::finish)
+/// runs the hooks still due once execution has completed.
+pub(crate) struct InspectorHooks<'a, CTX, I> {
+    inspector: &'a mut I,
+    /// REVM context passed to the hooks, only dereferenced while a hook runs
+    ctx: &'a ContextSlot,
+    /// Type of the context `ctx` points to
+    context: PhantomData<*mut CTX>,
+    handle: *mut EvmHandle,
+    spec: SpecId,
+    /// Intrinsic gas of the transaction, charged before its frame starts
+    intrinsic_gas: u64,
+    /// Frame of the transaction itself, until its code starts
+    top: Option<Entry>,
+    /// Frames running code, innermost last
+    frames: Vec<Frame>,
+    /// Call or creation made by the last opcode, until its code starts or it returns
+    pending: Option<Entry>,
+    /// Panic raised by the inspector, resumed once execution has returned to Rust
+    pub(crate) panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a, CTX, I: Inspector<CTX>> InspectorHooks<'a, CTX, I> {
+    /// Hooks running `inspector` for `tx` executing on `handle`, on the context `ctx` holds
+    ///
+    /// # Safety
+    ///
+    /// While guillotine-mini runs a step callback or [`finish`](Self::finish) runs, `ctx` must
+    /// hold a valid pointer to a context that is not borrowed elsewhere.
+    pub(crate) unsafe fn new(
+        inspector: &'a mut I,
+        ctx: &'a ContextSlot,
+        handle: *mut EvmHandle,
+        spec: SpecId,
+        tx: &TxEnv,
+    ) -> Self {
+        let intrinsic_gas = gas::tx_intrinsic_gas(spec, tx).initial;
+        Self {
+            inspector,
+            ctx,
+            context: PhantomData,
+            handle,
+            spec,
+            intrinsic_gas,
+            top: Some(top_entry(tx, tx.gas_limit.saturating_sub(intrinsic_gas))),
+            frames: Vec::new(),
+            pending: None,
+            panic: None,
+        }
+    }
+
+    /// Run the hooks due before the opcode of `step`, and `step_end` of the opcode before it
+    fn on_step(&mut self, step: StepInfo) {
+        let frame_ptr = unsafe { ffi::evm_get_current_frame(self.handle) };
+        let Some(mut view) = (unsafe { FrameView::from_raw(frame_ptr) }) else { return };
+        let Some(ctx) = (unsafe { (self.ctx.get() as *mut CTX).as_mut() }) else { return };
+
+        // Frames deeper than the step have returned to their caller
+        while self.frames.last().is_some_and(|frame| frame.depth > step.depth) {
+            // A call made by the returning frame's last opcode never started
+            self.pending = None;
+            let frame = self.frames.pop().expect("frame checked above");
+            let success = view.peek(0).is_some_and(|flag| !flag.is_zero());
+            let created = success.then(|| address_from_word(view.peek(0).unwrap_or_default()));
+            exit(self.inspector, ctx, frame, success, created);
+        }
+
+        match self.frames.last_mut() {
+            Some(frame) if frame.depth == step.depth => {
+                if let Some(entry) = self.pending.take() {
+                    let precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(self.spec));
+                    skip(self.inspector, ctx, entry, &view, precompiles);
+                }
+                end_step(self.inspector, ctx, frame, Some(&mut view), true);
+            }
+            _ => {
+                // Code of the pending call or of the transaction itself starts
+                let Some(entry) = self.pending.take().or_else(|| self.top.take()) else { return };
+                let frame = enter(self.inspector, ctx, self.spec, entry, step, &view);
+                self.frames.push(frame);
+            }
+        }
+
+        let frame = self.frames.last_mut().expect("frame entered above");
+        let loaded = load_state(&mut frame.interp, &view, step.pc);
+        self.inspector.step(&mut frame.interp, ctx);
+        write_back(&frame.interp, &mut view, &loaded);
+        frame.last = Some(step);
+
+        // Operands are read after the hook, which may have changed them
+        let interp = &frame.interp;
+        match step.opcode {
+            opcode::LOG0..=opcode::LOG4 => {
+                frame.effect = log(interp, (step.opcode - opcode::LOG0) as usize).map(Effect::Log);
+            }
+            opcode::SELFDESTRUCT => {
+                let address = interp.input.target_address;
+                frame.effect = Some(Effect::SelfDestruct {
+                    address,
+                    beneficiary: address_from_word(operand(interp, 0)),
+                    balance: balance(self.handle, address),
+                });
+            }
+            opcode::RETURN | opcode::REVERT => {
+                frame.output = memory_range(interp, operand(interp, 0), operand(interp, 1)).unwrap_or_default();
+            }
+            opcode::CREATE | opcode::CREATE2 => {
+                let nonce = nonce(self.handle, interp.input.target_address);
+                self.pending = call_entry(frame, step.opcode, nonce);
+            }
+            op if is_call_opcode(op) => {
+                self.pending = call_entry(frame, op, 0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Run the hooks still due once execution has completed with `result`
+    pub(crate) fn finish(mut self, result: &ExecutionResult) {
+        let Some(ctx) = (unsafe { (self.ctx.get() as *mut CTX).as_mut() }) else { return };
+        self.pending = None;
+
+        // Internal frames still open ended with the last opcode they ran
+        while self.frames.len() > 1 {
+            let frame = self.frames.pop().expect("frame checked above");
+            let success = frame.last.is_some_and(|step| !is_failure(step.opcode));
+            exit(self.inspector, ctx, frame, success, None);
+        }
+
+        let mut inputs = match (self.frames.pop(), self.top.take()) {
+            (Some(mut frame), _) => {
+                end_step(self.inspector, ctx, &mut frame, None, result.is_success());
+                frame.inputs
+            }
+            // The transaction ran no code
+            (None, Some(mut entry)) => {
+                entry_hook(self.inspector, ctx, &mut entry.inputs);
+                entry.inputs
+            }
+            (None, None) => return,
+        };
+        let (instruction_result, output, gas_used, created) = top_outcome(result);
+        let mut gas = Gas::new(inputs.gas_limit());
+        gas.set_spent(gas_used.saturating_sub(self.intrinsic_gas).min(gas.limit()));
+        end_hook(
+            self.inspector,
+            ctx,
+            &mut inputs,
+            InterpreterResult::new(instruction_result, output, gas),
+            created,
+            0..0,
+        );
+    }
+}
+
+/// Trampoline forwarding step callbacks from Zig to [`InspectorHooks`]
+///
+/// A panicking inspector interrupts execution; the panic is kept in
+/// [`InspectorHooks::panic`] and no further hooks run.
+///
+/// # Safety
+///
+/// `ctx` must be null or point to live `InspectorHooks<CTX, I>` for the duration of execution.
+pub(crate) extern "C" fn inspector_trampoline<CTX, I: Inspector<CTX>>(
+    ctx: *mut c_void,
+    pc: u32,
+    opcode: u8,
+    gas_remaining: i64,
+    depth: u16,
+    stack_len: usize,
+) {
+    if ctx.is_null() {
+        return;
+    }
+    let hooks = unsafe { &mut *(ctx as *mut InspectorHooks<'_, CTX, I>) };
+    if hooks.panic.is_some() {
+        return;
+    }
+    let step = StepInfo::from_ffi(pc, opcode, gas_remaining, depth, stack_len);
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| hooks.on_step(step))) {
+        hooks.panic = Some(payload);
+        unsafe { ffi::evm_request_interrupt(hooks.handle) };
+    }
+}
+
+/// Invoke the entry hook of `entry` and mirror the frame whose first opcode is `step`
+fn enter<CTX, I: Inspector<CTX>>(
+    inspector: &mut I,
+    ctx: &mut CTX,
+    spec: SpecId,
+    mut entry: Entry,
+    step: StepInfo,
+    view: &FrameView<'_>,
+) -> Frame {
+    entry.inputs.set_gas_limit(step.gas_remaining);
+    entry_hook(inspector, ctx, &mut entry.inputs);
+
+    let bytecode = ExtBytecode::new(Bytecode::new_legacy(view.code()));
+    let mut interp =
+        Interpreter::new(SharedMemory::new(), bytecode, entry.interp_inputs, entry.is_static, spec, step.gas_remaining);
+    inspector.initialize_interp(&mut interp, ctx);
+    Frame {
+        inputs: entry.inputs,
+        depth: step.depth,
+        is_static: entry.is_static,
+        interp,
+        last: None,
+        effect: None,
+        output: Bytes::new(),
+    }
+}
+
+/// Invoke the hooks of a call that returned without running code; `view` is its caller
+fn skip<CTX, I: Inspector<CTX>>(
+    inspector: &mut I,
+    ctx: &mut CTX,
+    mut entry: Entry,
+    view: &FrameView<'_>,
+    precompiles: &Precompiles,
+) {
+    let gas_limit = entry.inputs.gas_limit();
+    entry_hook(inspector, ctx, &mut entry.inputs);
+
+    let flag = view.peek(0).unwrap_or_default();
+    let output = view.return_data();
+    let result = match (flag.is_zero(), output.is_empty()) {
+        (false, true) => InstructionResult::Stop,
+        (false, false) => InstructionResult::Return,
+        (true, false) => InstructionResult::Revert,
+        (true, true) if entry.depth > MAX_CALL_DEPTH => InstructionResult::CallTooDeep,
+        (true, true) if precompiles.contains(&entry.interp_inputs.target_address) => InstructionResult::PrecompileError,
+        (true, true) => InstructionResult::OutOfFunds,
+    };
+    let created = (!flag.is_zero()).then(|| address_from_word(flag));
+    let memory_offset = return_memory_offset(&entry.inputs);
+    end_hook(
+        inspector,
+        ctx,
+        &mut entry.inputs,
+        InterpreterResult::new(result, output, Gas::new(gas_limit)),
+        created,
+        memory_offset,
+    );
+}
+
+/// Complete the last opcode of `frame` and invoke its exit hook
+///
+/// `success` tells whether the frame returned without reverting or halting, and `created` the
+/// address a successful creation deployed to.
+fn exit<CTX, I: Inspector<CTX>>(
+    inspector: &mut I,
+    ctx: &mut CTX,
+    mut frame: Frame,
+    success: bool,
+    created: Option<Address>,
+) {
+    let result = frame_result(frame.last.map(|step| step.opcode), success);
+    end_step(inspector, ctx, &mut frame, None, success);
+
+    let mut gas = Gas::new(frame.inputs.gas_limit());
+    if result.is_ok() || result == InstructionResult::Revert {
+        gas.set_spent(frame.interp.gas.spent());
+    } else {
+        gas.set_spent(gas.limit());
+    }
+    let output = if result.is_ok_or_revert() { frame.output } else { Bytes::new() };
+    let created = if matches!(frame.inputs, FrameInputs::Create(_)) { created } else { None };
+    let memory_offset = return_memory_offset(&frame.inputs);
+    end_hook(inspector, ctx, &mut frame.inputs, InterpreterResult::new(result, output, gas), created, memory_offset);
+}
+
+/// Run `step_end` for the last opcode of `frame`, reporting its log or SELFDESTRUCT first if it
+/// `completed`; `view` holds the state after the opcode while the frame still runs
+fn end_step<CTX, I: Inspector<CTX>>(
+    inspector: &mut I,
+    ctx: &mut CTX,
+    frame: &mut Frame,
+    view: Option<&mut FrameView<'_>>,
+    completed: bool,
+) {
+    if frame.last.take().is_none() {
+        return;
+    }
+    match frame.effect.take() {
+        Some(Effect::Log(log)) if completed => inspector.log(&mut frame.interp, ctx, log),
+        Some(Effect::SelfDestruct { address, beneficiary, balance }) if completed => {
+            inspector.selfdestruct(address, beneficiary, balance)
+        }
+        _ => {}
+    }
+    match view {
+        Some(view) => {
+            let pc = view.pc();
+            let loaded = load_state(&mut frame.interp, view, pc);
+            inspector.step_end(&mut frame.interp, ctx);
+            write_back(&frame.interp, view, &loaded);
+        }
+        None => inspector.step_end(&mut frame.interp, ctx),
+    }
+}
+
+fn entry_hook<CTX, I: Inspector<CTX>>(inspector: &mut I, ctx: &mut CTX, inputs: &mut FrameInputs) {
+    match inputs {
+        FrameInputs::Call(inputs) => {
+            inspector.call(ctx, inputs);
+        }
+        FrameInputs::Create(inputs) => {
+            inspector.create(ctx, inputs);
+        }
+    }
+}
+
+fn end_hook<CTX, I: Inspector<CTX>>(
+    inspector: &mut I,
+    ctx: &mut CTX,
+    inputs: &mut FrameInputs,
+    result: InterpreterResult,
+    created: Option<Address>,
+    memory_offset: Range<usize>,
+) {
+    match inputs {
+        FrameInputs::Call(inputs) => {
+            let mut outcome = CallOutcome::new(result, memory_offset);
+            inspector.call_end(ctx, inputs, &mut outcome);
+        }
+        FrameInputs::Create(inputs) => {
+            let mut outcome = CreateOutcome::new(result, created);
+            inspector.create_end(ctx, inputs, &mut outcome);
+        }
+    }
+}
+
+fn return_memory_offset(inputs: &FrameInputs) -> Range<usize> {
+    match inputs {
+        FrameInputs::Call(inputs) => inputs.return_memory_offset.clone(),
+        FrameInputs::Create(_) => 0..0,
+    }
+}
+
+/// Entry of the call or creation the opcode `op` of `frame` is about to make
+///
+/// The gas limit is an estimate from the caller's gas, replaced by the callee's actual gas once
+/// its code starts. `nonce` is the caller's nonce, used to derive the address CREATE deploys to.
+/// `None` if the operands are out of range, in which case the opcode fails.
+fn call_entry(frame: &Frame, op: u8, nonce: u64) -> Option<Entry> {
+    let interp = &frame.interp;
+    let parent = &interp.input;
+    let depth = frame.depth + 1;
+    // All but one 64th of the caller's gas is available to the callee (EIP-150)
+    let available = interp.gas.remaining() - interp.gas.remaining() / 64;
+
+    if matches!(op, opcode::CREATE | opcode::CREATE2) {
+        let value = operand(interp, 0);
+        let init_code = memory_range(interp, operand(interp, 1), operand(interp, 2))?;
+        let scheme = match op {
+            opcode::CREATE2 => CreateScheme::Create2 { salt: operand(interp, 3) },
+            _ => CreateScheme::Create,
+        };
+        let inputs = CreateInputs { caller: parent.target_address, scheme, value, init_code, gas_limit: available };
+        let interp_inputs = InputsImpl {
+            target_address: inputs.created_address(nonce),
+            bytecode_address: None,
+            caller_address: parent.target_address,
+            input: CallInput::Bytes(Bytes::new()),
+            call_value: value,
+        };
+        return Some(Entry { inputs: FrameInputs::Create(inputs), interp_inputs, is_static: false, depth });
+    }
+
+    let has_value = matches!(op, opcode::CALL | opcode::CALLCODE);
+    let args = if has_value { 3 } else { 2 };
+    let gas_limit = u64::try_from(operand(interp, 0)).unwrap_or(u64::MAX).min(available);
+    let to = address_from_word(operand(interp, 1));
+    let value = if has_value { operand(interp, 2) } else { U256::ZERO };
+    let input = memory_range(interp, operand(interp, args), operand(interp, args + 1))?;
+    let return_memory_offset = range(operand(interp, args + 2), operand(interp, args + 3))?;
+
+    let (scheme, target, caller, value) = match op {
+        opcode::CALLCODE => {
+            (CallScheme::CallCode, parent.target_address, parent.target_address, CallValue::Transfer(value))
+        }
+        opcode::DELEGATECALL => (
+            CallScheme::DelegateCall,
+            parent.target_address,
+            parent.caller_address,
+            CallValue::Apparent(parent.call_value),
+        ),
+        opcode::STATICCALL => (CallScheme::StaticCall, to, parent.target_address, CallValue::Transfer(U256::ZERO)),
+        _ => (CallScheme::Call, to, parent.target_address, CallValue::Transfer(value)),
+    };
+    let is_static = frame.is_static || op == opcode::STATICCALL;
+    let interp_inputs = InputsImpl {
+        target_address: target,
+        bytecode_address: Some(to),
+        caller_address: caller,
+        input: CallInput::Bytes(input.clone()),
+        call_value: value.get(),
+    };
+    let inputs = CallInputs {
+        input: CallInput::Bytes(input),
+        return_memory_offset,
+        gas_limit,
+        bytecode_address: to,
+        known_bytecode: None,
+        target_address: target,
+        caller,
+        value,
+        scheme,
+        is_static,
+    };
+    Some(Entry { inputs: FrameInputs::Call(inputs), interp_inputs, is_static, depth })
+}
+
+/// Entry of the transaction's own frame, with `gas_limit` left for execution
+fn top_entry(tx: &TxEnv, gas_limit: u64) -> Entry {
+    let (inputs, target, input) = match tx.kind {
+        TxKind::Call(to) => {
+            let inputs = CallInputs {
+                input: CallInput::Bytes(tx.data.clone()),
+                return_memory_offset: 0..0,
+                gas_limit,
+                bytecode_address: to,
+                known_bytecode: None,
+                target_address: to,
+                caller: tx.caller,
+                value: CallValue::Transfer(tx.value),
+                scheme: CallScheme::Call,
+                is_static: false,
+            };
+            (FrameInputs::Call(inputs), to, tx.data.clone())
+        }
+        TxKind::Create => {
+            let inputs = CreateInputs {
+                caller: tx.caller,
+                scheme: CreateScheme::Create,
+                value: tx.value,
+                init_code: tx.data.clone(),
+                gas_limit,
+            };
+            (FrameInputs::Create(inputs), tx.caller.create(tx.nonce), Bytes::new())
+        }
+    };
+    let interp_inputs = InputsImpl {
+        target_address: target,
+        bytecode_address: Some(target),
+        caller_address: tx.caller,
+        input: CallInput::Bytes(input),
+        call_value: tx.value,
+    };
+    Entry { inputs, interp_inputs, is_static: false, depth: 0 }
+}
+
+/// Instruction result, output, gas used before refunds and created address of a transaction
+fn top_outcome(result: &ExecutionResult) -> (InstructionResult, Bytes, u64, Option<Address>) {
+    match result {
+        ExecutionResult::Success { output, gas_used, gas_refunded, .. } => {
+            let created = match output {
+                Output::Create(_, address) => *address,
+                Output::Call(_) => None,
+            };
+            (InstructionResult::Return, output.data().clone(), gas_used + gas_refunded, created)
+        }
+        ExecutionResult::Revert { output, gas_used } => (InstructionResult::Revert, output.clone(), *gas_used, None),
+        ExecutionResult::Halt { reason, gas_used } => (halt_instruction_result(reason), Bytes::new(), *gas_used, None),
+    }
+}
+
+/// Load the frame state `view` holds before the opcode at `pc` into `interp`
+fn load_state(interp: &mut Interpreter, view: &FrameView<'_>, pc: usize) -> Loaded {
+    let stack: Vec<U256> = (0..view.stack_len()).rev().map(|index| view.peek(index).unwrap_or_default()).collect();
+    let memory = view.memory().to_vec();
+    let gas_remaining = view.gas_remaining();
+
+    interp.bytecode.absolute_jump(pc);
+    let data = interp.stack.data_mut();
+    data.clear();
+    data.extend_from_slice(&stack);
+    interp.memory.resize(memory.len());
+    interp.memory.set(0, &memory);
+    interp.gas.set_spent(interp.gas.limit().saturating_sub(gas_remaining));
+    Loaded { stack, memory, gas_remaining }
+}
+
+/// Write the changes a hook made to `interp` since `loaded` back to the frame
+///
+/// Stack items that no longer fit and gas the frame does not have are dropped.
+fn write_back(interp: &Interpreter, view: &mut FrameView<'_>, loaded: &Loaded) {
+    let stack = interp.stack.data();
+    if *stack != loaded.stack {
+        while view.pop().is_some() {}
+        for &item in stack {
+            let _ = view.push(item);
+        }
+    }
+    let memory = interp.memory.context_memory();
+    if *memory != loaded.memory[..] {
+        let _ = view.write_memory(0, &memory);
+    }
+    let charged = loaded.gas_remaining.saturating_sub(interp.gas.remaining());
+    if charged > 0 {
+        let _ = view.consume_gas(charged);
+    }
+}
+
+/// Balance of `address` in guillotine-mini, zero if it cannot be read
+fn balance(handle: *mut EvmHandle, address: Address) -> U256 {
+    let mut balance = [0u8; 32];
+    if !unsafe { ffi::evm_get_balance(handle, address.as_ptr(), balance.as_mut_ptr()) } {
+        return U256::ZERO;
+    }
+    types::u256_from_be_bytes(&balance)
+}
+
+/// Nonce of `address` in guillotine-mini, zero if it cannot be read
+fn nonce(handle: *mut EvmHandle, address: Address) -> u64 {
+    let mut nonce = 0u64;
+    unsafe { ffi::evm_get_nonce(handle, address.as_ptr(), &mut nonce) };
+    nonce
+}
+
+/// Stack operand `index` positions below the top (0 = top); zero past the bottom
+fn operand(interp: &Interpreter, index: usize) -> U256 {
+    interp.stack.data().iter().rev().nth(index).copied().unwrap_or_default()
+}
+
+/// `offset..offset + len` as a memory range; `None` if it cannot be addressed
+fn range(offset: U256, len: U256) -> Option<Range<usize>> {
+    if len.is_zero() {
+        return Some(0..0);
+    }
+    let offset = usize::try_from(offset).ok()?;
+    let len = usize::try_from(len).ok()?;
+    Some(offset..offset.checked_add(len)?)
+}
+
+/// Memory of `interp` in `offset..offset + len`, zero-padded past its end as expansion would
+///
+/// `None` for ranges the frame's gas cannot expand memory to, where the opcode fails.
+fn memory_range(interp: &Interpreter, offset: U256, len: U256) -> Option<Bytes> {
+    let range = range(offset, len)?;
+    if range.is_empty() {
+        return Some(Bytes::new());
+    }
+    if range.end > interp.memory.len().max(memory::max_expansion(interp.gas.remaining())) {
+        return None;
+    }
+    let memory = interp.memory.context_memory();
+    let mut data = vec![0u8; range.len()];
+    let available = memory.len().clamp(range.start, range.end);
+    data[..available - range.start].copy_from_slice(&memory[range.start..available]);
+    Some(data.into())
+}
+
+/// Log emitted by a LOG opcode with `topics` topics, from its operands
+fn log(interp: &Interpreter, topics: usize) -> Option<Log> {
+    let data = memory_range(interp, operand(interp, 0), operand(interp, 1))?;
+    let topics = (0..topics).map(|index| B256::from(operand(interp, 2 + index))).collect();
+    Some(Log::new_unchecked(interp.input.target_address, topics, data))
+}
+
+/// Address held in the low 20 bytes of a stack word
+fn address_from_word(word: U256) -> Address {
+    Address::from_word(B256::from(word))
+}
+
+/// Instruction result of a frame whose last opcode was `last`
+fn frame_result(last: Option<u8>, success: bool) -> InstructionResult {
+    match (last, success) {
+        (Some(opcode::RETURN), true) => InstructionResult::Return,
+        (Some(opcode::SELFDESTRUCT), true) => InstructionResult::SelfDestruct,
+        (_, true) => InstructionResult::Stop,
+        (Some(opcode::REVERT), false) => InstructionResult::Revert,
+        (Some(opcode::INVALID), false) => InstructionResult::InvalidFEOpcode,
+        (Some(op), false) if opcode::OpCode::new(op).is_none() => InstructionResult::OpcodeNotFound,
+        (_, false) => InstructionResult::OutOfGas,
+    }
+}
+
+/// Whether a frame ending with `op` failed: REVERT and invalid opcodes
+fn is_failure(op: u8) -> bool {
+    op == opcode::INVALID || op == opcode::REVERT || opcode::OpCode::new(op).is_none()
+}
+
+fn is_call_opcode(op: u8) -> bool {
+    matches!(
+        op,
+        opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL | opcode::CREATE | opcode::CREATE2
+    )
+}
+
+/// Instruction result REVM reports for an exceptional halt
+pub(crate) fn halt_instruction_result(reason: &HaltReason) -> InstructionResult {
+    match reason {
+        HaltReason::OutOfGas(_) => InstructionResult::OutOfGas,
+        HaltReason::OpcodeNotFound => InstructionResult::OpcodeNotFound,
+        HaltReason::InvalidFEOpcode => InstructionResult::InvalidFEOpcode,
+        HaltReason::InvalidJump => InstructionResult::InvalidJump,
+        HaltReason::StackUnderflow => InstructionResult::StackUnderflow,
+        HaltReason::StackOverflow => InstructionResult::StackOverflow,
+        HaltReason::CallTooDeep => InstructionResult::CallTooDeep,
+        HaltReason::OutOfFunds => InstructionResult::OutOfFunds,
+        HaltReason::PrecompileError => InstructionResult::PrecompileError,
+        HaltReason::StateChangeDuringStaticCall => InstructionResult::StateChangeDuringStaticCall,
+        HaltReason::CreateCollision => InstructionResult::CreateCollision,
+        _ => InstructionResult::FatalExternalError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guillotine_mini::{ffi, types};
+
+    /// Frame of `target` at depth 0 whose stack holds `stack` (bottom first) and memory `memory`
+    fn frame(target: Address, stack: &[U256], memory: &[u8]) -> Frame {
+        let inputs = InputsImpl {
+            target_address: target,
+            bytecode_address: Some(target),
+            caller_address: Address::with_last_byte(0xca),
+            input: CallInput::Bytes(Bytes::new()),
+            call_value: U256::from(7),
+        };
+        let mut interp =
+            Interpreter::new(SharedMemory::new(), ExtBytecode::default(), inputs, false, SpecId::CANCUN, 64_000);
+        interp.stack.data_mut().extend_from_slice(stack);
+        interp.memory.resize(memory.len());
+        interp.memory.set(0, memory);
+        Frame {
+            inputs: FrameInputs::Call(CallInputs {
+                input: CallInput::Bytes(Bytes::new()),
+                return_memory_offset: 0..0,
+                gas_limit: 64_000,
+                bytecode_address: target,
+                known_bytecode: None,
+                target_address: target,
+                caller: Address::with_last_byte(0xca),
+                value: CallValue::Transfer(U256::from(7)),
+                scheme: CallScheme::Call,
+                is_static: false,
+            }),
+            depth: 0,
+            is_static: false,
+            interp,
+            last: None,
+            effect: None,
+            output: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn test_call_entry_reads_operands() {
+        let (target, callee) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let word = |value: u64| U256::from(value);
+        // retLen, retOffset, argsLen, argsOffset, address, gas (top last)
+        let stack = [word(32), word(64), word(4), word(1), U256::from_be_slice(callee.as_slice()), word(100_000)];
+        let frame = frame(target, &stack, &[0, 0xaa, 0xbb, 0xcc]);
+
+        let entry = call_entry(&frame, opcode::DELEGATECALL, 0).unwrap();
+        let FrameInputs::Call(inputs) = entry.inputs else { panic!("DELEGATECALL enters a call") };
+        assert_eq!(inputs.scheme, CallScheme::DelegateCall);
+        assert_eq!(
+            (inputs.target_address, inputs.bytecode_address, inputs.caller),
+            (target, callee, frame.interp.input.caller_address)
+        );
+        assert_eq!(inputs.value, CallValue::Apparent(U256::from(7)));
+        assert_eq!(
+            inputs.input,
+            CallInput::Bytes(Bytes::from_static(&[0xaa, 0xbb, 0xcc, 0])),
+            "Args past memory read as zero"
+        );
+        assert_eq!(inputs.return_memory_offset, 64..96);
+        assert_eq!(inputs.gas_limit, 63_000, "Gas is capped to all but one 64th of the caller's");
+        assert_eq!(entry.depth, 1);
+    }
+
+    #[test]
+    fn test_call_entry_create_address() {
+        let creator = Address::with_last_byte(1);
+        // size, offset, value (top last)
+        let frame = frame(creator, &[U256::ZERO, U256::ZERO, U256::from(5)], &[]);
+
+        let entry = call_entry(&frame, opcode::CREATE, 3).unwrap();
+        assert_eq!(entry.interp_inputs.target_address, creator.create(3));
+        assert!(matches!(entry.inputs, FrameInputs::Create(CreateInputs { value, .. }) if value == U256::from(5)));
+    }
+
+    #[test]
+    fn test_memory_range_bounded_by_gas() {
+        let frame = frame(Address::ZERO, &[], &[1, 2]);
+        assert_eq!(memory_range(&frame.interp, U256::from(1), U256::from(3)), Some(Bytes::from_static(&[2, 0, 0])));
+        assert_eq!(memory_range(&frame.interp, U256::MAX, U256::ZERO), Some(Bytes::new()));
+        assert_eq!(memory_range(&frame.interp, U256::ZERO, U256::from(1u64 << 32)), None);
+    }
+
+    #[test]
+    fn test_log_from_operands() {
+        let address = Address::with_last_byte(1);
+        // topic, size, offset (top last)
+        let frame = frame(address, &[U256::from(9), U256::from(2), U256::from(1)], &[0, 0xaa, 0xbb]);
+
+        let log = log(&frame.interp, 1).unwrap();
+        assert_eq!(
+            log,
+            Log::new_unchecked(address, vec![B256::from(U256::from(9))], Bytes::from_static(&[0xaa, 0xbb]))
+        );
+    }
+
+    #[test]
+    fn test_frame_result() {
+        assert_eq!(frame_result(Some(opcode::RETURN), true), InstructionResult::Return);
+        assert_eq!(frame_result(Some(opcode::ADD), true), InstructionResult::Stop, "Running off the code stops");
+        assert_eq!(frame_result(Some(opcode::REVERT), false), InstructionResult::Revert);
+        assert_eq!(frame_result(Some(opcode::INVALID), false), InstructionResult::InvalidFEOpcode);
+        assert_eq!(frame_result(Some(0x0c), false), InstructionResult::OpcodeNotFound);
+        assert_eq!(frame_result(Some(opcode::SSTORE), false), InstructionResult::OutOfGas);
+    }
+
+    #[test]
+    fn test_halt_instruction_result() {
+        let reason = types::halt_reason_from_status(ffi::EVM_STATUS_OUT_OF_GAS).unwrap();
+        assert_eq!(halt_instruction_result(&reason), InstructionResult::OutOfGas);
+        assert_eq!(halt_instruction_result(&HaltReason::InvalidJump), InstructionResult::InvalidJump);
+        assert_eq!(halt_instruction_result(&HaltReason::NonceOverflow), InstructionResult::FatalExternalError);
+    }
+}
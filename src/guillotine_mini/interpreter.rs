@@ -2,14 +2,14 @@
 //!
 //! Implements REVM's InterpreterTypes trait using guillotine-mini as the backend.
 
-use super::ffi::EvmHandle;
+use super::ffi::OwnedEvmHandle;
 
 /// Guillotine-mini interpreter type
 ///
 /// This will implement REVM's InterpreterTypes trait to provide
 /// a drop-in replacement for EthInterpreter.
 pub struct GuillotineMiniInterpreter {
-    handle: *mut EvmHandle,
+    handle: OwnedEvmHandle,
 }
 
 // TODO: Implement InterpreterTypes trait
@@ -0,0 +1,64 @@
+//! guillotine-mini diagnostics through `tracing`
+//!
+//! guillotine-mini writes its diagnostics to stderr unless a log callback is registered. With
+//! the `tracing` feature the adapter registers one the first time an instance is created and
+//! emits each message as a `tracing` event with target `guillotine_mini`, the Zig log scope as
+//! the `scope` field and the matching level. Instances are created with the most verbose level
+//! enabled by the current subscriber, so filtering stays with the host application.
+
+use super::ffi;
+use tracing::level_filters::LevelFilter;
+
+/// guillotine-mini log level enabling messages the subscriber may accept (see `LOG_LEVEL_*`)
+///
+/// Registers the log callback on first use.
+pub(crate) fn log_level() -> u8 {
+    static REGISTER: std::sync::Once = std::sync::Once::new();
+    REGISTER.call_once(|| unsafe { ffi::evm_set_log_callback(Some(log_trampoline)) });
+    level_for(LevelFilter::current())
+}
+
+/// guillotine-mini log level matching a `tracing` level filter
+///
+/// guillotine-mini has no trace level; `TRACE` enables its debug messages.
+fn level_for(filter: LevelFilter) -> u8 {
+    match filter.into_level() {
+        None => ffi::LOG_LEVEL_OFF,
+        Some(tracing::Level::ERROR) => ffi::LOG_LEVEL_ERR,
+        Some(tracing::Level::WARN) => ffi::LOG_LEVEL_WARN,
+        Some(tracing::Level::INFO) => ffi::LOG_LEVEL_INFO,
+        Some(_) => ffi::LOG_LEVEL_DEBUG,
+    }
+}
+
+/// Forward a guillotine-mini log message to `tracing`
+extern "C" fn log_trampoline(level: u8, scope: *const u8, scope_len: usize, message: *const u8, message_len: usize) {
+    let scope = text(scope, scope_len);
+    let message = text(message, message_len);
+    match level {
+        ffi::LOG_LEVEL_ERR => tracing::error!(target: "guillotine_mini", scope = %scope, "{message}"),
+        ffi::LOG_LEVEL_WARN => tracing::warn!(target: "guillotine_mini", scope = %scope, "{message}"),
+        ffi::LOG_LEVEL_INFO => tracing::info!(target: "guillotine_mini", scope = %scope, "{message}"),
+        _ => tracing::debug!(target: "guillotine_mini", scope = %scope, "{message}"),
+    }
+}
+
+/// UTF-8 text passed by guillotine-mini, valid for the duration of the callback
+fn text<'a>(ptr: *const u8, len: usize) -> std::borrow::Cow<'a, str> {
+    if ptr.is_null() {
+        return "".into();
+    }
+    String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for_filter() {
+        assert_eq!(level_for(LevelFilter::OFF), ffi::LOG_LEVEL_OFF);
+        assert_eq!(level_for(LevelFilter::WARN), ffi::LOG_LEVEL_WARN);
+        assert_eq!(level_for(LevelFilter::TRACE), ffi::LOG_LEVEL_DEBUG);
+    }
+}
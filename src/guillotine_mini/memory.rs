@@ -0,0 +1,92 @@
+//! Memory inspection
+//!
+//! [`MemoryView`] reads the memory of the frame executing the current opcode through the FFI,
+//! either between steps of a [`Stepper`](super::evm::Stepper) or from the [`FrameView`](super::FrameView)
+//! passed to a custom opcode handler. Contents are copied out on each read; nothing is cached.
+
+use super::ffi::{self, EvmHandle};
+use revm::primitives::U256;
+use std::marker::PhantomData;
+
+/// Where the memory is read from
+#[derive(Clone, Copy)]
+enum Source {
+    /// Current frame of an instance paused between steps
+    Handle(*mut EvmHandle),
+    /// Frame passed to an opcode handler
+    Frame(usize),
+}
+
+/// Read-only view of a call frame's memory, valid while the stepper or frame it came from is
+#[derive(Clone, Copy)]
+pub struct MemoryView<'a> {
+    source: Source,
+    _frame: PhantomData<&'a ()>,
+}
+
+impl<'a> MemoryView<'a> {
+    /// View of the memory of the frame `handle` executes next
+    pub(crate) fn from_handle(handle: *mut EvmHandle) -> Self {
+        Self { source: Source::Handle(handle), _frame: PhantomData }
+    }
+
+    /// View of the memory of a frame passed to an opcode handler
+    pub(crate) fn from_frame(frame_ptr: usize) -> Self {
+        Self { source: Source::Frame(frame_ptr), _frame: PhantomData }
+    }
+
+    /// Memory size in bytes (always a multiple of 32)
+    pub fn len(&self) -> usize {
+        match self.source {
+            Source::Handle(handle) => unsafe { ffi::evm_get_memory_size(handle) },
+            Source::Frame(frame_ptr) => unsafe { ffi::evm_get_frame_memory_size(frame_ptr) },
+        }
+    }
+
+    /// Whether memory has not been expanded yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy `len` bytes starting at `offset`
+    ///
+    /// The result is truncated at the end of memory; reading does not expand it.
+    pub fn read(&self, offset: usize, len: usize) -> Vec<u8> {
+        let available = self.len().saturating_sub(offset).min(len);
+        let mut buf = vec![0u8; available];
+        let copied = match self.source {
+            Source::Handle(handle) => unsafe { ffi::evm_get_memory(handle, offset, available, buf.as_mut_ptr()) },
+            Source::Frame(frame_ptr) => unsafe {
+                ffi::evm_get_frame_memory(frame_ptr, offset, available, buf.as_mut_ptr())
+            },
+        };
+        buf.truncate(copied);
+        buf
+    }
+
+    /// 32-byte word at `offset`, zero-padded past the end of memory as MLOAD would see it
+    pub fn word(&self, offset: usize) -> U256 {
+        let mut word = [0u8; 32];
+        let bytes = self.read(offset, 32);
+        word[..bytes.len()].copy_from_slice(&bytes);
+        U256::from_be_bytes(word)
+    }
+
+    /// Copy the whole memory
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.read(0, self.len())
+    }
+}
+
+/// Largest size in bytes a frame with `gas_remaining` gas can expand its memory to
+///
+/// Expanding memory to `words` words costs at least `words² / 512` gas.
+pub(crate) fn max_expansion(gas_remaining: u64) -> usize {
+    ((gas_remaining as f64 * 512.0).sqrt() as usize).saturating_mul(32)
+}
+
+impl std::fmt::Debug for MemoryView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryView").field("len", &self.len()).finish()
+    }
+}
@@ -3,43 +3,115 @@
 //! This module provides a REVM-compatible EVM backed by guillotine-mini's
 //! Zig implementation via native FFI.
 //!
-//! # Configuration API Status
+//! # Configuration API
 //!
-//! The configuration API (`config` module) is temporarily disabled pending upstream FFI support
-//! in guillotine-mini. The config module provides:
+//! The configuration API (`config` module, behind the `config-api` feature) provides:
 //!
 //! - Custom opcode handlers via `EvmConfigBuilder::override_opcode`
+//! - New opcodes in unassigned slots via `EvmConfigBuilder::define_opcode`
 //! - Custom precompile registration via `EvmConfigBuilder::override_precompile`
+//! - Built-in precompile removal via `EvmConfigBuilder::disable_precompile` and
+//!   `EvmConfigBuilder::precompiles`
+//! - Complete precompile sets from a `PrecompileProvider` via `EvmConfigBuilder::precompile_provider`
+//! - Individual EIP toggles via `EvmConfigBuilder::eip`
+//! - `ConfigSettings` recording every setting but the handlers, loadable from JSON or TOML files
+//! - Presets (`EvmConfig::mainnet`, `EvmConfig::testing`, `EvmConfig::l2_generic`)
+//! - One `EvmConfig` shared through an `Arc` by many EVMs or an `EvmPool`
+//! - `RuntimeConfig` parameters changed between blocks via `GuillotineMiniEvm::update_config`
 //! - Runtime parameter tuning (stack size, memory limits, gas limits, etc.)
 //! - System contract feature flags
 //!
-//! **Current Status**: The Rust-side configuration API is implemented and tested, but the
-//! corresponding FFI functions in guillotine-mini (commit: 25b2185) are not yet available in
-//! the stable C ABI. Once upstream adds these functions to `root_c.zig`, the config module
-//! will be re-enabled.
-//!
-//! **Tracking**: See commit 25b2185 - "refactor: Temporarily disable config API pending upstream FFI"
-//!
-//! **Workaround**: Use the default EVM configuration via `GuillotineMiniEvm::new()` or
-//! `GuillotineMiniEvm::try_new()`. These constructors create an EVM instance with standard
+//! Its `evm_config_*` functions are not yet part of every guillotine-mini build's C ABI. Default
+//! builds leave them out entirely; with the feature they are detected at runtime instead of being
+//! linked, and when the linked library does not export them `GuillotineMiniEvm::with_config`
+//! returns `EvmAdapterError::Unsupported("config")`. Use `GuillotineMiniEvm::new()`,
+//! `GuillotineMiniEvm::try_new()` or `GuillotineMiniEvm::builder()` for the standard
 //! hardfork-based configuration.
 
-// TODO: Re-enable once guillotine-mini upstream adds config FFI functions
-// The config API is fully implemented but requires upstream FFI support:
-// - evm_config_create()
-// - evm_config_set_* functions
-// - evm_config_add_opcode_override()
-// - evm_config_add_precompile_override()
-// - evm_create_with_config()
-// pub mod config;
+pub mod access_list;
+pub mod builder;
+pub mod call_trace;
+pub mod call_tracer;
+pub mod cancel;
+pub mod chain_spec;
+pub mod code_cache;
+#[cfg(feature = "config-api")]
+pub mod config;
 pub mod database_bridge;
 pub mod evm;
 pub mod ffi;
+pub(crate) mod ffi_safe;
 pub mod error;
+pub mod events;
+pub mod flamegraph;
+#[cfg(feature = "alloy")]
+pub mod fork;
+pub mod frame;
+pub mod gas;
+pub mod hardfork;
+pub(crate) mod host;
+pub mod inspector;
+#[cfg(feature = "tracing")]
+pub(crate) mod logging;
+pub mod memory;
+pub mod options;
+pub mod pool;
+#[cfg(feature = "config-api")]
+pub mod precompile_provider;
+pub mod report;
+pub mod revert;
+pub mod shared;
+pub mod slot_scan;
+pub(crate) mod spans;
+pub mod state_diff;
+pub mod step;
+pub mod storage_tracer;
+pub mod struct_log;
+pub mod sync_strategy;
+#[cfg(feature = "config-api")]
+pub(crate) mod symbols;
 pub mod types;
+pub mod validation;
 
-pub use evm::GuillotineMiniEvm;
-pub use error::EvmAdapterError;
-pub use database_bridge::{sync_account_to_ffi, sync_storage_to_ffi, sync_storage_slots_to_ffi};
-// TODO: Re-enable once guillotine-mini upstream adds config FFI functions
-// pub use config::{EvmConfigBuilder, EvmConfig, PrecompileResult, PrecompileError};
+pub use access_list::AccessListResult;
+pub use builder::EvmBuilder;
+pub use call_trace::{CallKind, InternalCall};
+pub use call_tracer::{CallFrame, CallLog, CallTracer};
+pub use cancel::CancellationToken;
+pub use chain_spec::{ChainContext, ChainSpec, ForkCondition, Genesis, GenesisAccount};
+pub use code_cache::CodeCache;
+pub use evm::{GuillotineMiniEvm, Stepper};
+pub use error::{EvmAdapterError, FfiError, FfiErrorKind};
+pub use events::ExecutionEvent;
+#[cfg(feature = "alloy")]
+pub use fork::{ForkContext, ForkDB};
+pub use flamegraph::folded_stacks;
+pub use frame::FrameView;
+pub use gas::{GasAnomaly, GasBreakdown};
+pub use hardfork::Hardfork;
+pub use memory::MemoryView;
+pub use options::TransactOptions;
+pub use pool::EvmPool;
+pub use report::{BridgeStats, CreatedContract, ExecutionReport, SelfDestruct};
+pub use revert::decode_revert_reason;
+pub use shared::SharedEvm;
+pub use state_diff::{AccountChange, AccountDiff, StateDiff, ValueChange};
+pub use step::{StepInfo, StepResult};
+pub use storage_tracer::{StorageAccess, StorageAccessKind, StorageAccessTracer};
+pub use struct_log::{DeepTraceLimits, StructLog};
+pub use sync_strategy::{AccessListSlots, CommonSlots, FullScan, SlotPlan, SyncStrategy};
+pub use database_bridge::{
+    array_slot, mapping_slot, nested_mapping_slot, sync_access_list_to_ffi, sync_account_to_ffi,
+    sync_account_to_ffi_ref, sync_account_to_ffi_verified, sync_all_storage_to_ffi, sync_block_env,
+    sync_block_hashes_to_ffi, sync_storage_slots_to_ffi, sync_storage_slots_to_ffi_ref, sync_storage_to_ffi,
+    sync_storage_to_ffi_ref, verify_code_hash,
+};
+#[cfg(feature = "asyncdb")]
+pub use database_bridge::{sync_account_to_ffi_async, sync_storage_slots_to_ffi_async};
+#[cfg(feature = "config-api")]
+pub use config::{
+    ConfigError, ConfigSettings, EvmConfig, EvmConfigBuilder, PrecompileError, PrecompileResult, RuntimeConfig,
+    SystemContracts,
+};
+#[cfg(feature = "config-api")]
+pub use precompile_provider::{GuillotinePrecompiles, Precompile, PrecompileHandler, PrecompileProvider};
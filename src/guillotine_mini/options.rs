@@ -0,0 +1,70 @@
+//! Per-transaction execution options
+//!
+//! [`TransactOptions`] overrides settings of one `GuillotineMiniEvm::transact_with_options` call
+//! without touching the EVM's own configuration, so one instance can serve workloads with
+//! different limits: a traced debug call next to an untraced simulation, or an untrusted call
+//! bounded by a memory limit and a deadline.
+
+use super::sync_strategy::SyncStrategy;
+use std::time::{Duration, Instant};
+
+/// Settings overridden for a single transaction; unset options keep the EVM's settings
+#[derive(Default)]
+pub struct TransactOptions {
+    pub(crate) memory_limit: Option<u64>,
+    pub(crate) tracing: Option<bool>,
+    pub(crate) sync_strategy: Option<Box<dyn SyncStrategy>>,
+    pub(crate) deadline: Option<Instant>,
+}
+
+impl TransactOptions {
+    /// Options overriding nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Memory limit in bytes of every frame, in place of the configured one
+    ///
+    /// Frames expanding memory past it halt with out of gas.
+    pub fn memory_limit(mut self, limit: u64) -> Self {
+        self.memory_limit = Some(limit);
+        self
+    }
+
+    /// Native tracing (see `GuillotineMiniEvm::set_native_tracing`)
+    pub fn tracing(mut self, enabled: bool) -> Self {
+        self.tracing = Some(enabled);
+        self
+    }
+
+    /// Sync strategy (see `GuillotineMiniEvm::set_sync_strategy`)
+    pub fn sync_strategy(mut self, strategy: impl SyncStrategy + 'static) -> Self {
+        self.sync_strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Wall-clock time by which execution must have finished
+    ///
+    /// Execution still running at the deadline is interrupted and the transaction fails with
+    /// `EvmAdapterError::DeadlineExceeded`, leaving no state behind. The deadline bounds every
+    /// execution of the transaction, including discovery-mode dry runs.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Deadline `timeout` from now
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+}
+
+impl std::fmt::Debug for TransactOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransactOptions")
+            .field("memory_limit", &self.memory_limit)
+            .field("tracing", &self.tracing)
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,221 @@
+//! Pool of guillotine-mini instances for concurrent execution
+//!
+//! Creating a guillotine-mini instance allocates and initializes the whole engine, which
+//! dominates the cost of short simulations. An [`EvmPool`] creates its instances up front and
+//! lends them to [`EvmPool::transact`] calls from any thread: an idle instance is taken by
+//! whichever call asks first, cleared after the transaction and handed back to the next one.
+//! With the `config-api` feature, [`EvmPool::with_config`] creates every instance from one
+//! shared custom configuration.
+
+use super::{
+    error::EvmAdapterError,
+    evm::GuillotineMiniEvm,
+    ffi::OwnedEvmHandle,
+    hardfork::Hardfork,
+};
+use revm::{
+    context::{Cfg, Context, TxEnv},
+    context_interface::result::ResultAndState,
+    database_interface::Database,
+    primitives::hardfork::SpecId,
+};
+#[cfg(feature = "config-api")]
+use std::sync::Arc;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Fixed-size pool of guillotine-mini instances for one hardfork
+pub struct EvmPool {
+    /// Hardfork the instances are created with
+    hardfork: Hardfork,
+    /// Number of instances the pool lends out at most
+    size: usize,
+    state: Mutex<PoolState>,
+    /// Signalled when an instance is returned or lost
+    available: Condvar,
+    /// Custom configuration the instances are created from (declared after `state`, whose
+    /// instances it must outlive)
+    #[cfg(feature = "config-api")]
+    config: Option<Arc<super::config::EvmConfig>>,
+}
+
+struct PoolState {
+    /// Instances waiting to be lent
+    idle: Vec<OwnedEvmHandle>,
+    /// Instances alive, idle or lent (below `size` after an instance was lost)
+    live: usize,
+}
+
+impl EvmPool {
+    /// Create `size` instances for `spec`
+    pub fn new(spec: SpecId, size: usize) -> Result<Self, &'static str> {
+        if size == 0 {
+            return Err("EvmPool size must be at least 1");
+        }
+        let pool = Self {
+            hardfork: Hardfork::from_spec(spec),
+            size,
+            state: Mutex::new(PoolState { idle: Vec::new(), live: size }),
+            available: Condvar::new(),
+            #[cfg(feature = "config-api")]
+            config: None,
+        };
+        pool.fill()
+    }
+
+    /// Create `size` instances for `spec` from `config`, shared by every instance
+    ///
+    /// Requires the `config-api` feature. The configuration and its handlers are built once;
+    /// each instance, including those replacing lost ones, only reads it. Contexts whose spec
+    /// differs from `spec` run on a new, unpooled instance created from the same configuration.
+    /// Fails if the linked libguillotine_mini does not export the config ABI.
+    #[cfg(feature = "config-api")]
+    pub fn with_config(
+        spec: SpecId,
+        size: usize,
+        config: impl Into<Arc<super::config::EvmConfig>>,
+    ) -> Result<Self, &'static str> {
+        if size == 0 {
+            return Err("EvmPool size must be at least 1");
+        }
+        let config = config.into();
+        if !config.is_supported() {
+            return Err("libguillotine_mini does not export the config ABI");
+        }
+        let pool = Self {
+            hardfork: Hardfork::from_spec(spec),
+            size,
+            state: Mutex::new(PoolState { idle: Vec::new(), live: size }),
+            available: Condvar::new(),
+            config: Some(config),
+        };
+        pool.fill()
+    }
+
+    /// Create the pool's instances
+    fn fill(self) -> Result<Self, &'static str> {
+        let idle = (0..self.size).map(|_| self.create_instance()).collect::<Result<Vec<_>, _>>()?;
+        self.lock().idle = idle;
+        Ok(self)
+    }
+
+    /// New instance for the pool's hardfork or configuration
+    fn create_instance(&self) -> Result<OwnedEvmHandle, &'static str> {
+        #[cfg(feature = "config-api")]
+        if let Some(config) = &self.config {
+            return OwnedEvmHandle::with_config(config.as_raw(), None);
+        }
+        OwnedEvmHandle::new(self.hardfork)
+    }
+
+    /// Maximum number of transactions executed concurrently
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of instances currently waiting to be lent
+    pub fn idle(&self) -> usize {
+        self.lock().idle.len()
+    }
+
+    /// Execute `tx` on a pooled instance, in the context built by `ctx_factory`
+    ///
+    /// Blocks while every instance is lent out. The context is built once an instance is
+    /// available, and each transaction starts from fresh guillotine-mini state, as with
+    /// [`GuillotineMiniEvm::transact`] in its default mode. A context whose spec differs from
+    /// the pool's hardfork is executed on a new, unpooled instance instead.
+    pub fn transact<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL, F>(
+        &self,
+        ctx_factory: F,
+        tx: TxEnv,
+    ) -> Result<ResultAndState, EvmAdapterError<DB::Error>>
+    where
+        BLOCK: revm::context_interface::Block,
+        TX: revm::context_interface::Transaction,
+        CFG: Cfg<Spec = SpecId>,
+        DB: Database,
+        JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+        LOCAL: revm::context_interface::LocalContextTr,
+        F: FnOnce() -> Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>,
+    {
+        let mut lease = self.checkout().map_err(EvmAdapterError::ffi)?;
+        let ctx = ctx_factory();
+        if Hardfork::from_spec(ctx.cfg.spec()) != self.hardfork {
+            drop(lease);
+            #[cfg(feature = "config-api")]
+            if let Some(config) = &self.config {
+                return GuillotineMiniEvm::with_config(ctx, Arc::clone(config))?.transact(tx);
+            }
+            return GuillotineMiniEvm::try_new(ctx)?.transact(tx);
+        }
+
+        let mut evm = GuillotineMiniEvm::from_handle(ctx, lease.take(), self.hardfork);
+        #[cfg(feature = "config-api")]
+        evm.set_config(self.config.clone());
+        let result = evm.transact(tx);
+        if let Ok(handle) = evm.into_handle() {
+            lease.handle = Some(handle);
+        }
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        // A panic while holding the lock cannot leave the state inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Take an idle instance, replacing a lost one or waiting for one to be returned
+    fn checkout(&self) -> Result<Lease<'_>, &'static str> {
+        let mut state = self.lock();
+        loop {
+            if let Some(handle) = state.idle.pop() {
+                return Ok(Lease { pool: self, handle: Some(handle) });
+            }
+            if state.live < self.size {
+                state.live += 1;
+                drop(state);
+                // Dropped empty if creation fails, which frees the slot again
+                let mut lease = Lease { pool: self, handle: None };
+                lease.handle = Some(self.create_instance()?);
+                return Ok(lease);
+            }
+            state = self.available.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/// Instance lent by the pool, returned on drop
+///
+/// If the instance is not put back (its execution panicked or it could not be cleared), the
+/// pool forgets it and creates a replacement on a later checkout.
+struct Lease<'p> {
+    pool: &'p EvmPool,
+    handle: Option<OwnedEvmHandle>,
+}
+
+impl Lease<'_> {
+    fn take(&mut self) -> OwnedEvmHandle {
+        self.handle.take().expect("lease holds an instance")
+    }
+}
+
+impl Drop for Lease<'_> {
+    fn drop(&mut self) {
+        let mut state = self.pool.lock();
+        match self.handle.take() {
+            Some(handle) => state.idle.push(handle),
+            None => state.live -= 1,
+        }
+        drop(state);
+        self.pool.available.notify_one();
+    }
+}
+
+impl std::fmt::Debug for EvmPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmPool")
+            .field("hardfork", &self.hardfork)
+            .field("size", &self.size)
+            .field("idle", &self.idle())
+            .finish()
+    }
+}
@@ -0,0 +1,110 @@
+//! Precompile sets supplied as a unit
+//!
+//! A [`PrecompileProvider`] decides, for a hardfork, every precompile address and what
+//! implements it: guillotine-mini's built-in implementation or a Rust handler. Installing one
+//! with `EvmConfigBuilder::precompile_provider` replaces the whole built-in set, so an
+//! alternative crypto backend or a chain's own precompiles are registered in one place instead
+//! of call by call:
+//!
+//! ```rust,ignore
+//! use guillotine_rs::guillotine_mini::{
+//!     EvmConfigBuilder, GuillotinePrecompiles, Hardfork, Precompile, PrecompileProvider, PrecompileResult,
+//! };
+//! use revm::primitives::{address, Address};
+//!
+//! /// Built-in precompiles plus an echo precompile at 0x0100
+//! struct WithEcho;
+//!
+//! impl PrecompileProvider for WithEcho {
+//!     fn precompiles(&self, hardfork: Hardfork) -> Vec<(Address, Precompile)> {
+//!         let mut set = GuillotinePrecompiles.precompiles(hardfork);
+//!         let echo = Precompile::custom(|_, input, _| Ok(PrecompileResult { output: input.to_vec(), gas_used: 15 }));
+//!         set.push((address!("0000000000000000000000000000000000000100"), echo));
+//!         set
+//!     }
+//! }
+//!
+//! let config = EvmConfigBuilder::new().precompile_provider(Hardfork::Cancun, &WithEcho).build();
+//! ```
+
+use super::{
+    config::{PrecompileError, PrecompileResult},
+    hardfork::Hardfork,
+};
+use revm::{
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::Address,
+};
+use std::sync::Arc;
+
+/// Handler implementing a precompile: (address, input, gas_limit) to output and gas used
+pub type PrecompileHandler =
+    dyn Fn(Address, &[u8], u64) -> Result<PrecompileResult, PrecompileError> + Send + Sync + 'static;
+
+/// Implementation of one precompile in a [`PrecompileProvider`]'s set
+#[derive(Clone)]
+pub enum Precompile {
+    /// guillotine-mini's built-in implementation of the precompile at that address
+    Builtin,
+    /// Rust handler, replacing any built-in implementation at that address
+    Custom(Arc<PrecompileHandler>),
+}
+
+impl Precompile {
+    /// Precompile implemented by `handler`
+    pub fn custom<F>(handler: F) -> Self
+    where
+        F: Fn(Address, &[u8], u64) -> Result<PrecompileResult, PrecompileError> + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(handler))
+    }
+}
+
+impl std::fmt::Debug for Precompile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Builtin => f.write_str("Builtin"),
+            Self::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+/// Supplies the complete precompile set of a hardfork
+///
+/// Addresses left out of the set are not precompiles: calls to them execute the account's code
+/// like any other call. A [`Precompile::Builtin`] entry must name an address guillotine-mini
+/// implements for the hardfork.
+pub trait PrecompileProvider {
+    /// Every precompile active under `hardfork`, with its implementation
+    fn precompiles(&self, hardfork: Hardfork) -> Vec<(Address, Precompile)>;
+}
+
+/// Default provider: the precompiles of the hardfork, all implemented by guillotine-mini
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GuillotinePrecompiles;
+
+impl PrecompileProvider for GuillotinePrecompiles {
+    fn precompiles(&self, hardfork: Hardfork) -> Vec<(Address, Precompile)> {
+        Precompiles::new(PrecompileSpecId::from_spec_id(hardfork.spec()))
+            .addresses()
+            .map(|address| (*address, Precompile::Builtin))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_provider_follows_hardfork() {
+        let count = |hardfork| GuillotinePrecompiles.precompiles(hardfork).len();
+        assert_eq!(count(Hardfork::Frontier), 4);
+        assert_eq!(count(Hardfork::Byzantium), 8);
+        assert_eq!(count(Hardfork::Cancun), 10);
+        assert!(GuillotinePrecompiles
+            .precompiles(Hardfork::Cancun)
+            .iter()
+            .all(|(_, precompile)| matches!(precompile, Precompile::Builtin)));
+    }
+}
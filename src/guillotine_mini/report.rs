@@ -0,0 +1,96 @@
+//! Adapter-level execution report
+//!
+//! Carries information about the last `transact` call that has no place in
+//! REVM's `ResultAndState`, such as anomalies detected at the FFI boundary.
+
+use super::call_trace::InternalCall;
+use super::gas::{GasAnomaly, GasBreakdown};
+use super::revert::decode_revert_reason;
+use super::struct_log::StructLog;
+use revm::{
+    primitives::{Address, Bytes, B256, U256},
+    state::TransientStorage,
+};
+
+/// Supplementary information about the most recent transaction execution
+///
+/// Retrieved via `GuillotineMiniEvm::last_report` after calling `transact`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionReport {
+    /// Gas counter inconsistencies reported by the engine (empty when healthy)
+    pub gas_anomalies: Vec<GasAnomaly>,
+    /// Price per gas paid by the sender (EIP-1559 effective gas price)
+    pub effective_gas_price: u128,
+    /// Non-zero transient storage (EIP-1153) at the end of execution (`None` before Cancun)
+    pub transient_storage: Option<TransientStorage>,
+    /// Blob gas used by the transaction (EIP-4844, zero for non-blob transactions)
+    pub blob_gas_used: u64,
+    /// Excess blob gas of the block the transaction executed in (`None` before Cancun)
+    pub excess_blob_gas: Option<u64>,
+    /// Message calls and creations made by the executed code, in the order they started
+    pub calls: Vec<InternalCall>,
+    /// SELFDESTRUCTs executed, in execution order (empty when execution failed)
+    pub selfdestructs: Vec<SelfDestruct>,
+    /// Contracts deployed by the transaction, at any call depth (empty when execution failed)
+    pub created_contracts: Vec<CreatedContract>,
+    /// Execution gas by category, when reported by guillotine-mini
+    pub gas_breakdown: Option<GasBreakdown>,
+    /// Opcodes executed, when native or deep tracing is enabled (`GuillotineMiniEvm::set_native_tracing`,
+    /// `GuillotineMiniEvm::set_deep_tracing`)
+    pub struct_logs: Vec<StructLog>,
+    /// Data passed to REVERT, when the transaction reverted
+    pub revert_data: Option<Bytes>,
+}
+
+/// Contract deployed by CREATE or CREATE2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedContract {
+    /// Address of the new contract
+    pub address: Address,
+    /// Account that executed the creation (the sender for a top-level create)
+    pub deployer: Address,
+    /// keccak256 of the init code
+    pub init_code_hash: B256,
+    /// Deployed runtime code
+    pub code: Bytes,
+}
+
+/// Effect of a SELFDESTRUCT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfDestruct {
+    /// Account that executed SELFDESTRUCT
+    pub address: Address,
+    /// Account credited with its balance
+    pub beneficiary: Address,
+    /// Balance moved to the beneficiary
+    pub balance: U256,
+}
+
+/// Pre-state sync work done for the most recent `transact` or `call`
+///
+/// Collected only when enabled with `GuillotineMiniEvm::set_bridge_stats`, to quantify sync
+/// overhead when tuning sync strategies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BridgeStats {
+    /// Accounts written to guillotine-mini
+    pub accounts_synced: u64,
+    /// Storage slots written to guillotine-mini
+    pub slots_synced: u64,
+    /// FFI calls made to write pre-state (accounts, code, storage, block hashes)
+    pub ffi_calls: u64,
+    /// Bytes of contract code copied into guillotine-mini
+    pub bytes_copied: u64,
+}
+
+impl ExecutionReport {
+    /// Whether the engine reported any gas values that no correct engine could produce
+    pub fn has_anomalies(&self) -> bool {
+        self.gas_anomalies.iter().any(GasAnomaly::is_impossible)
+    }
+
+    /// Human-readable reason the transaction reverted with, decoded from `Error(string)` or
+    /// `Panic(uint256)` revert data (see [`decode_revert_reason`])
+    pub fn revert_reason(&self) -> Option<String> {
+        self.revert_data.as_deref().and_then(|data| decode_revert_reason(data))
+    }
+}
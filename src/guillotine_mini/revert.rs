@@ -0,0 +1,72 @@
+//! Revert reason decoding
+//!
+//! Solidity reverts with ABI-encoded `Error(string)` for `require`/`revert` messages and
+//! `Panic(uint256)` for failed assertions and arithmetic errors. Custom errors are left to
+//! callers that know the contract ABI.
+
+use revm::primitives::U256;
+
+/// Selector of `Error(string)`
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decode revert data into a human-readable reason
+///
+/// `Error(string)` yields the message and `Panic(uint256)` yields `panic: 0x<code>`; any other
+/// payload (empty data, custom errors, malformed encodings) yields `None`.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+    let args = &data[4..];
+    match selector {
+        ERROR_SELECTOR => {
+            let offset = word_to_usize(args.get(..32)?)?;
+            let len_end = offset.checked_add(32)?;
+            let len = word_to_usize(args.get(offset..len_end)?)?;
+            let message = args.get(len_end..len_end.checked_add(len)?)?;
+            String::from_utf8(message.to_vec()).ok()
+        }
+        PANIC_SELECTOR => {
+            let code = U256::from_be_slice(args.get(..32)?);
+            Some(format!("panic: {code:#x}"))
+        }
+        _ => None,
+    }
+}
+
+/// ABI word as a length or offset; `None` if it does not fit a `usize`
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    usize::try_from(U256::from_be_slice(word)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: usize) -> [u8; 32] {
+        U256::from(value).to_be_bytes()
+    }
+
+    #[test]
+    fn test_decode_error_string() {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend(word(32));
+        data.extend(word(5));
+        data.extend(b"nope!");
+        data.extend([0u8; 27]);
+        assert_eq!(decode_revert_reason(&data), Some("nope!".to_string()));
+
+        // Truncated message
+        assert_eq!(decode_revert_reason(&data[..4 + 64 + 3]), None);
+    }
+
+    #[test]
+    fn test_decode_panic_and_unknown() {
+        let mut data = PANIC_SELECTOR.to_vec();
+        data.extend(word(0x11));
+        assert_eq!(decode_revert_reason(&data), Some("panic: 0x11".to_string()));
+
+        assert_eq!(decode_revert_reason(&[]), None);
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]), None);
+    }
+}
@@ -0,0 +1,84 @@
+//! Sharing one EVM between threads
+//!
+//! A guillotine-mini instance is not internally synchronized, so [`GuillotineMiniEvm`] is
+//! `Send` (when its context is) but not `Sync`. [`SharedEvm`] serializes access behind a mutex,
+//! for services where several threads execute against the same state. Independent simulations
+//! scale better with an [`EvmPool`](super::EvmPool).
+//!
+//! REVM's default `LocalContext` holds an `Rc`, so only EVMs over a context with a `Send` local
+//! context (installed through `Context::with_local`) can be shared this way.
+
+use super::{error::EvmAdapterError, evm::GuillotineMiniEvm};
+use revm::{
+    context::{Cfg, Context, TxEnv},
+    context_interface::result::ResultAndState,
+    database_interface::Database,
+    primitives::hardfork::SpecId,
+};
+use std::sync::{Mutex, MutexGuard};
+
+/// [`GuillotineMiniEvm`] usable from several threads, one at a time
+///
+/// `Sync` whenever the wrapped EVM is `Send`.
+///
+/// ```compile_fail
+/// use guillotine_rs::GuillotineMiniEvm;
+///
+/// fn assert_sync<T: Sync>() {}
+/// // Even over a `Send + Sync` context, a bare EVM cannot be shared between threads; wrap it in
+/// // a `SharedEvm`
+/// assert_sync::<GuillotineMiniEvm<Vec<u8>>>();
+/// ```
+///
+/// ```
+/// use guillotine_rs::{guillotine_mini::SharedEvm, GuillotineMiniEvm};
+///
+/// fn assert_send<T: Send>() {}
+/// fn assert_sync<T: Sync>() {}
+/// assert_send::<GuillotineMiniEvm<Vec<u8>>>();
+/// assert_sync::<SharedEvm<Vec<u8>>>();
+/// ```
+pub struct SharedEvm<CTX> {
+    evm: Mutex<GuillotineMiniEvm<CTX>>,
+}
+
+impl<CTX> SharedEvm<CTX> {
+    /// Wrap an EVM for shared use
+    pub fn new(evm: GuillotineMiniEvm<CTX>) -> Self {
+        Self { evm: Mutex::new(evm) }
+    }
+
+    /// Exclusive access to the EVM, waiting for other threads to release it
+    ///
+    /// A thread that panicked while holding the lock may have left guillotine-mini state
+    /// behind; the EVM is still handed out, and [`GuillotineMiniEvm::reset_state`] discards it.
+    pub fn lock(&self) -> MutexGuard<'_, GuillotineMiniEvm<CTX>> {
+        self.evm.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Unwrap the EVM
+    pub fn into_inner(self) -> GuillotineMiniEvm<CTX> {
+        self.evm.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<CTX> From<GuillotineMiniEvm<CTX>> for SharedEvm<CTX> {
+    fn from(evm: GuillotineMiniEvm<CTX>) -> Self {
+        Self::new(evm)
+    }
+}
+
+impl<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL> SharedEvm<Context<BLOCK, TX, CFG, DB, JOURNAL, CHAIN, LOCAL>>
+where
+    BLOCK: revm::context_interface::Block,
+    TX: revm::context_interface::Transaction,
+    CFG: Cfg<Spec = SpecId>,
+    DB: Database,
+    JOURNAL: revm::context_interface::JournalTr<Database = DB>,
+    LOCAL: revm::context_interface::LocalContextTr,
+{
+    /// Execute a transaction, holding the lock for its duration (see [`GuillotineMiniEvm::transact`])
+    pub fn transact(&self, tx: TxEnv) -> Result<ResultAndState, EvmAdapterError<DB::Error>> {
+        self.lock().transact(tx)
+    }
+}
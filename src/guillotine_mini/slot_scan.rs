@@ -0,0 +1,72 @@
+//! Static bytecode scan for fixed storage slots
+//!
+//! Finds storage keys pushed as immediates right before SLOAD/SSTORE (e.g. `PUSH2 0x1234 SLOAD`),
+//! which is how compilers access state variables at fixed slots. Syncing these slots covers
+//! high-numbered fixed slots that the default sync strategy would miss. Computed keys (mappings,
+//! dynamic arrays) cannot be found statically.
+
+use revm::primitives::U256;
+
+const PUSH0: u8 = 0x5f;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const DUP1: u8 = 0x80;
+const SLOAD: u8 = 0x54;
+const SSTORE: u8 = 0x55;
+
+/// Storage slots used as immediate keys of SLOAD/SSTORE in `code`, sorted and deduplicated
+///
+/// A `DUP1` between the push and the storage opcode is followed, as in `PUSH1 0 DUP1 SLOAD`.
+pub fn scan_storage_slots(code: &[u8]) -> Vec<U256> {
+    let mut slots = Vec::new();
+    // Value on top of the stack, when it is known to come from a push immediate
+    let mut top: Option<U256> = None;
+
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        pc += 1;
+
+        match opcode {
+            PUSH0 => top = Some(U256::ZERO),
+            PUSH1..=PUSH32 => {
+                let len = (opcode - PUSH1 + 1) as usize;
+                if pc + len > code.len() {
+                    break;
+                }
+                top = Some(U256::from_be_slice(&code[pc..pc + len]));
+                pc += len;
+            }
+            DUP1 => {}
+            SLOAD | SSTORE => {
+                if let Some(slot) = top.take() {
+                    slots.push(slot);
+                }
+            }
+            _ => top = None,
+        }
+    }
+
+    slots.sort_unstable();
+    slots.dedup();
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_storage_slots() {
+        // PUSH2 0x1234 SLOAD PUSH1 1 PUSH1 0 DUP1 SSTORE PUSH1 5 ADD SLOAD PUSH0 SLOAD PUSH2 0x1234 SLOAD
+        let code = hex::decode("61123454600160008055600501545f546112345400").unwrap();
+        assert_eq!(scan_storage_slots(&code), vec![U256::ZERO, U256::from(0x1234)]);
+    }
+
+    #[test]
+    fn test_scan_skips_push_data_and_truncated_push() {
+        // PUSH2 0x6054 (SLOAD byte inside push data) STOP PUSH32 <truncated>
+        let code = hex::decode("616054007f0102").unwrap();
+        assert!(scan_storage_slots(&code).is_empty());
+    }
+}
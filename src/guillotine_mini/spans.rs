@@ -0,0 +1,82 @@
+//! Per-transaction timing spans
+//!
+//! With the `trace-spans` feature, `transact` runs inside a `transact` span (target
+//! `guillotine_mini`) and every execution inside it in child spans, one per phase: `pre_sync`
+//! (pre-state sync to guillotine-mini), `setup` (bytecode, execution and block context),
+//! `execute` and `readback` (results and post-state). Each span records its wall-clock duration
+//! in microseconds as `duration_us` when it closes, so a subscriber reporting closed spans shows
+//! where the time of a transaction goes. Without the feature the guards compile to nothing.
+
+use revm::context::TxEnv;
+#[cfg(feature = "trace-spans")]
+use std::time::Instant;
+
+/// Phase of a transaction execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+    PreSync,
+    Setup,
+    Execute,
+    Readback,
+}
+
+/// Span entered until dropped, recording its duration on exit
+#[must_use = "the span closes when the guard is dropped"]
+pub(crate) struct Timed {
+    #[cfg(feature = "trace-spans")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "trace-spans")]
+    start: Instant,
+}
+
+#[cfg(feature = "trace-spans")]
+impl Timed {
+    fn enter(span: tracing::Span) -> Self {
+        Self { span: span.entered(), start: Instant::now() }
+    }
+}
+
+#[cfg(feature = "trace-spans")]
+impl Drop for Timed {
+    fn drop(&mut self) {
+        self.span.record("duration_us", self.start.elapsed().as_micros() as u64);
+    }
+}
+
+/// Enter the span of a whole transaction
+pub(crate) fn transact(tx: &TxEnv) -> Timed {
+    #[cfg(feature = "trace-spans")]
+    {
+        Timed::enter(tracing::info_span!(
+            target: "guillotine_mini",
+            "transact",
+            caller = %tx.caller,
+            gas_limit = tx.gas_limit,
+            duration_us = tracing::field::Empty,
+        ))
+    }
+    #[cfg(not(feature = "trace-spans"))]
+    {
+        let _ = tx;
+        Timed {}
+    }
+}
+
+/// Enter the span of one phase of an execution
+pub(crate) fn phase(phase: Phase) -> Timed {
+    #[cfg(feature = "trace-spans")]
+    {
+        use tracing::field::Empty;
+        Timed::enter(match phase {
+            Phase::PreSync => tracing::debug_span!(target: "guillotine_mini", "pre_sync", duration_us = Empty),
+            Phase::Setup => tracing::debug_span!(target: "guillotine_mini", "setup", duration_us = Empty),
+            Phase::Execute => tracing::debug_span!(target: "guillotine_mini", "execute", duration_us = Empty),
+            Phase::Readback => tracing::debug_span!(target: "guillotine_mini", "readback", duration_us = Empty),
+        })
+    }
+    #[cfg(not(feature = "trace-spans"))]
+    {
+        let _ = phase;
+        Timed {}
+    }
+}
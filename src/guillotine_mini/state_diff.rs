@@ -0,0 +1,192 @@
+//! Serializable state diffs
+//!
+//! Condenses the post-state of an execution into the accounts and storage slots that actually
+//! changed, in a form indexers and debuggers can consume directly (JSON with the `serde` feature).
+
+use revm::{
+    primitives::{Address, B256, U256},
+    state::{AccountInfo, EvmState},
+};
+use std::collections::BTreeMap;
+
+/// A value before and after execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValueChange<T> {
+    /// Value before execution
+    pub from: T,
+    /// Value after execution
+    pub to: T,
+}
+
+impl<T: PartialEq> ValueChange<T> {
+    /// The change from `from` to `to`, or `None` if the value is unchanged
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+}
+
+/// How an account was affected by execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum AccountChange {
+    /// The account was created (contract deployment or first funding)
+    Created,
+    /// An existing account was modified
+    Updated,
+    /// The account was selfdestructed
+    Deleted,
+}
+
+/// Changes to a single account
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountDiff {
+    /// Whether the account was created, updated or deleted
+    pub change: AccountChange,
+    /// Balance change, if any
+    pub balance: Option<ValueChange<U256>>,
+    /// Nonce change, if any
+    pub nonce: Option<ValueChange<u64>>,
+    /// Code hash change, if any (deployments and deletions)
+    pub code_hash: Option<ValueChange<B256>>,
+    /// Storage slots whose value changed
+    pub storage: BTreeMap<U256, ValueChange<U256>>,
+}
+
+/// Accounts and storage changed by an execution, ordered by address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiff {
+    /// Changed accounts
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+impl StateDiff {
+    /// Diff a post-state against the pre-state returned by `pre_state`
+    ///
+    /// `pre_state` yields the account info before execution (`None` for accounts that did not
+    /// exist). Accounts that were only touched or read are left out.
+    pub fn new(state: &EvmState, mut pre_state: impl FnMut(&Address) -> Option<AccountInfo>) -> Self {
+        let mut accounts = BTreeMap::new();
+
+        for (address, account) in state {
+            let pre = pre_state(address);
+            let existed = pre.as_ref().is_some_and(|info| !info.is_empty());
+            let pre = pre.unwrap_or_default();
+
+            let change = if account.is_selfdestructed() {
+                AccountChange::Deleted
+            } else if account.is_created() || !existed {
+                AccountChange::Created
+            } else {
+                AccountChange::Updated
+            };
+
+            let (post_balance, post_nonce, post_code_hash) = match change {
+                AccountChange::Deleted => (U256::ZERO, 0, AccountInfo::default().code_hash),
+                _ => (account.info.balance, account.info.nonce, account.info.code_hash),
+            };
+
+            let storage: BTreeMap<U256, ValueChange<U256>> = account
+                .storage
+                .iter()
+                .filter_map(|(slot, value)| {
+                    ValueChange::new(value.original_value, value.present_value).map(|change| (*slot, change))
+                })
+                .collect();
+
+            let diff = AccountDiff {
+                change,
+                balance: ValueChange::new(pre.balance, post_balance),
+                nonce: ValueChange::new(pre.nonce, post_nonce),
+                code_hash: ValueChange::new(pre.code_hash, post_code_hash),
+                storage,
+            };
+
+            let unchanged = diff.balance.is_none()
+                && diff.nonce.is_none()
+                && diff.code_hash.is_none()
+                && diff.storage.is_empty();
+            if !unchanged {
+                accounts.insert(*address, diff);
+            }
+        }
+
+        Self { accounts }
+    }
+
+    /// Whether no account changed
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        primitives::address,
+        state::{Account, AccountStatus, EvmStorageSlot},
+    };
+
+    fn account(info: AccountInfo, status: AccountStatus, storage: &[(u64, u64, u64)]) -> Account {
+        Account {
+            info,
+            storage: storage
+                .iter()
+                .map(|&(slot, original, present)| {
+                    let slot_value = EvmStorageSlot {
+                        original_value: U256::from(original),
+                        present_value: U256::from(present),
+                        transaction_id: 0,
+                        is_cold: false,
+                    };
+                    (U256::from(slot), slot_value)
+                })
+                .collect(),
+            status,
+            transaction_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_state_diff_classifies_accounts() {
+        let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+        let contract = address!("1000000000000000000000000000000000000000");
+        let reader = address!("2000000000000000000000000000000000000000");
+        let pre_sender = AccountInfo { balance: U256::from(100), ..Default::default() };
+        let pre_reader = AccountInfo { balance: U256::from(5), ..Default::default() };
+
+        let mut state = EvmState::default();
+        state.insert(
+            sender,
+            account(AccountInfo { balance: U256::from(90), nonce: 1, ..Default::default() }, AccountStatus::Touched, &[]),
+        );
+        state.insert(
+            contract,
+            account(AccountInfo::default(), AccountStatus::Touched | AccountStatus::Created, &[(1, 0, 7), (2, 3, 3)]),
+        );
+        state.insert(reader, account(pre_reader.clone(), AccountStatus::Touched, &[]));
+
+        let diff = StateDiff::new(&state, |address| match *address {
+            a if a == sender => Some(pre_sender.clone()),
+            a if a == reader => Some(pre_reader.clone()),
+            _ => None,
+        });
+
+        assert_eq!(diff.accounts.len(), 2, "Unchanged accounts are omitted");
+        let sender_diff = &diff.accounts[&sender];
+        assert_eq!(sender_diff.change, AccountChange::Updated);
+        assert_eq!(sender_diff.balance, Some(ValueChange { from: U256::from(100), to: U256::from(90) }));
+        assert_eq!(sender_diff.nonce, Some(ValueChange { from: 0, to: 1 }));
+
+        let contract_diff = &diff.accounts[&contract];
+        assert_eq!(contract_diff.change, AccountChange::Created);
+        assert_eq!(
+            contract_diff.storage.iter().collect::<Vec<_>>(),
+            vec![(&U256::from(1), &ValueChange { from: U256::ZERO, to: U256::from(7) })]
+        );
+    }
+}
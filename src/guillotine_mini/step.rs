@@ -0,0 +1,99 @@
+//! Opcode-level step observation
+//!
+//! Lightweight alternative to driving a REVM `Inspector`: guillotine-mini reports each opcode
+//! before it executes and the information is forwarded to a Rust closure.
+
+use super::ffi;
+use std::ffi::c_void;
+
+/// Interpreter state observed before an opcode is executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepInfo {
+    /// Program counter of the opcode
+    pub pc: usize,
+    /// Opcode about to be executed
+    pub opcode: u8,
+    /// Gas remaining in the current frame before the opcode is charged
+    pub gas_remaining: u64,
+    /// Call depth (0 for the top-level frame)
+    pub depth: usize,
+    /// Number of items on the stack
+    pub stack_len: usize,
+}
+
+impl StepInfo {
+    /// Step reported by a guillotine-mini step callback
+    pub(crate) fn from_ffi(pc: u32, opcode: u8, gas_remaining: i64, depth: u16, stack_len: usize) -> Self {
+        Self { pc: pc as usize, opcode, gas_remaining: gas_remaining.max(0) as u64, depth: depth as usize, stack_len }
+    }
+}
+
+/// Outcome of executing a single opcode with [`Stepper::step`](super::evm::Stepper::step)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepResult {
+    /// An opcode was executed and execution continues
+    Continue,
+    /// Execution has completed (success, revert or halt)
+    Finished,
+}
+
+impl StepResult {
+    /// Map an `evm_step` status code; `None` for a failed step
+    pub(crate) fn from_status(status: u8) -> Option<Self> {
+        match status {
+            ffi::EVM_STEP_CONTINUE => Some(Self::Continue),
+            ffi::EVM_STEP_FINISHED => Some(Self::Finished),
+            _ => None,
+        }
+    }
+}
+
+/// Observer type erased behind the FFI context pointer
+pub(crate) type StepObserver<'a> = &'a mut dyn FnMut(StepInfo);
+
+/// Trampoline forwarding step callbacks from Zig to a [`StepObserver`]
+///
+/// # Safety
+///
+/// `ctx` must be null or point to a live `StepObserver` for the duration of execution.
+pub(crate) extern "C" fn step_trampoline(
+    ctx: *mut c_void,
+    pc: u32,
+    opcode: u8,
+    gas_remaining: i64,
+    depth: u16,
+    stack_len: usize,
+) {
+    if ctx.is_null() {
+        return;
+    }
+    let observer = unsafe { &mut *(ctx as *mut StepObserver<'_>) };
+    observer(StepInfo::from_ffi(pc, opcode, gas_remaining, depth, stack_len));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_trampoline_forwards_to_observer() {
+        let mut steps = Vec::new();
+        let mut record = |step: StepInfo| steps.push(step);
+        let mut observer: StepObserver<'_> = &mut record;
+
+        step_trampoline(&mut observer as *mut StepObserver<'_> as *mut c_void, 2, 0x01, 97, 0, 2);
+        step_trampoline(std::ptr::null_mut(), 3, 0x00, 94, 0, 1);
+
+        assert_eq!(
+            steps,
+            vec![StepInfo { pc: 2, opcode: 0x01, gas_remaining: 97, depth: 0, stack_len: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_step_result_from_status() {
+        assert_eq!(StepResult::from_status(ffi::EVM_STEP_CONTINUE), Some(StepResult::Continue));
+        assert_eq!(StepResult::from_status(ffi::EVM_STEP_FINISHED), Some(StepResult::Finished));
+        assert_eq!(StepResult::from_status(ffi::EVM_STEP_ERROR), None);
+    }
+}
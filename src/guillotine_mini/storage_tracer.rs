@@ -0,0 +1,236 @@
+//! Storage access tracing
+//!
+//! [`StorageAccessTracer`] is a REVM `Inspector` recording every SLOAD and SSTORE of a
+//! transaction. Comparing the slots a transaction actually touched with the slots a sync
+//! strategy wrote to guillotine-mini shows directly which pre-state was missing. Run it with
+//! `GuillotineMiniEvm::inspect`, or on REVM itself.
+
+use revm::{
+    bytecode::opcode,
+    context_interface::{Cfg, ContextTr},
+    database_interface::Database,
+    inspector::Inspector,
+    interpreter::{interpreter_types::Jumps, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{hardfork::SpecId, Address, U256},
+};
+use std::collections::HashMap;
+
+/// Storage opcode of a [`StorageAccess`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StorageAccessKind {
+    #[cfg_attr(feature = "serde", serde(rename = "SLOAD"))]
+    Load,
+    #[cfg_attr(feature = "serde", serde(rename = "SSTORE"))]
+    Store,
+}
+
+/// SLOAD or SSTORE executed by a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StorageAccess {
+    #[cfg_attr(feature = "serde", serde(rename = "op"))]
+    pub kind: StorageAccessKind,
+    /// Account whose storage was accessed
+    pub address: Address,
+    pub slot: U256,
+    /// Value before the access; `None` when an SSTORE's previous value could not be loaded
+    pub old_value: Option<U256>,
+    /// Value after the access (the value read, for SLOAD)
+    pub new_value: U256,
+    /// Whether the slot was warm (EIP-2929), judged from the gas charged; `None` before Berlin
+    pub warm: Option<bool>,
+    /// Program counter of the opcode
+    pub pc: usize,
+    /// Call depth (0 for the top-level frame)
+    pub depth: usize,
+}
+
+/// Access seen by `step`, completed by `step_end`
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    access: StorageAccess,
+    gas_remaining: u64,
+}
+
+/// `Inspector` recording the storage accesses of the transactions it observes
+///
+/// Each top-level call replaces the accesses of the previous transaction.
+#[derive(Debug, Clone, Default)]
+pub struct StorageAccessTracer {
+    accesses: Vec<StorageAccess>,
+    pending: Option<Pending>,
+    /// Current value of every slot accessed
+    values: HashMap<(Address, U256), U256>,
+    /// Slot values replaced, with the value they had, to undo writes of failed calls
+    journal: Vec<((Address, U256), Option<U256>)>,
+    /// Journal length at the start of every open call, innermost last
+    checkpoints: Vec<usize>,
+}
+
+impl StorageAccessTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accesses of the last transaction, in execution order
+    pub fn accesses(&self) -> &[StorageAccess] {
+        &self.accesses
+    }
+
+    /// Take the accesses of the last transaction
+    pub fn into_accesses(self) -> Vec<StorageAccess> {
+        self.accesses
+    }
+
+    /// Pretty-printed JSON array of the accesses of the last transaction
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.accesses).expect("storage accesses serialize to JSON")
+    }
+
+    fn enter(&mut self) {
+        if self.checkpoints.is_empty() {
+            self.accesses.clear();
+            self.values.clear();
+            self.journal.clear();
+        }
+        self.checkpoints.push(self.journal.len());
+    }
+
+    fn exit(&mut self, success: bool) {
+        let Some(checkpoint) = self.checkpoints.pop() else { return };
+        if success {
+            return;
+        }
+        for (key, previous) in self.journal.drain(checkpoint..).rev() {
+            match previous {
+                Some(value) => self.values.insert(key, value),
+                None => self.values.remove(&key),
+            };
+        }
+    }
+
+    fn set_value(&mut self, key: (Address, U256), value: U256) {
+        let previous = self.values.insert(key, value);
+        if previous != Some(value) {
+            self.journal.push((key, previous));
+        }
+    }
+}
+
+impl<CTX: ContextTr> Inspector<CTX> for StorageAccessTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut CTX) {
+        let kind = match interp.bytecode.opcode() {
+            opcode::SLOAD => StorageAccessKind::Load,
+            opcode::SSTORE => StorageAccessKind::Store,
+            _ => return,
+        };
+        let stack = interp.stack.data();
+        let Some(&slot) = stack.last() else { return };
+        let address = interp.input.target_address;
+
+        let (old_value, new_value) = match kind {
+            StorageAccessKind::Load => (None, U256::ZERO),
+            StorageAccessKind::Store => {
+                let Some(&new_value) = stack.iter().rev().nth(1) else { return };
+                let old_value = match self.values.get(&(address, slot)) {
+                    Some(&value) => Some(value),
+                    None => context.db_mut().storage(address, slot).ok(),
+                };
+                (old_value, new_value)
+            }
+        };
+        self.pending = Some(Pending {
+            access: StorageAccess {
+                kind,
+                address,
+                slot,
+                old_value,
+                new_value,
+                warm: None,
+                pc: interp.bytecode.pc(),
+                depth: self.checkpoints.len().saturating_sub(1),
+            },
+            gas_remaining: interp.gas.remaining(),
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut CTX) {
+        let Some(Pending { mut access, gas_remaining }) = self.pending.take() else { return };
+        if access.kind == StorageAccessKind::Load {
+            access.new_value = interp.stack.data().last().copied().unwrap_or_default();
+            access.old_value = Some(access.new_value);
+        }
+        let cost = gas_remaining.saturating_sub(interp.gas.remaining());
+        access.warm = is_warm(context.cfg().spec().into(), access.kind, cost);
+
+        self.set_value((access.address, access.slot), access.new_value);
+        self.accesses.push(access);
+    }
+
+    fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.enter();
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.exit(outcome.result.is_ok());
+    }
+
+    fn create(&mut self, _context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        self.enter();
+        None
+    }
+
+    fn create_end(&mut self, _context: &mut CTX, _inputs: &CreateInputs, outcome: &mut CreateOutcome) {
+        self.exit(outcome.result.is_ok());
+    }
+}
+
+/// Whether a storage access charged `cost` gas found its slot warm; `None` before Berlin
+///
+/// Cold SLOADs cost 2100 and cold SSTOREs carry a 2100 surcharge on top of the warm 100, 2900
+/// or 20000 (EIP-2929).
+pub fn is_warm(spec: SpecId, kind: StorageAccessKind, cost: u64) -> Option<bool> {
+    if !spec.is_enabled_in(SpecId::BERLIN) {
+        return None;
+    }
+    Some(match kind {
+        StorageAccessKind::Load => cost < 2_100,
+        StorageAccessKind::Store => !matches!(cost, 2_200 | 5_000 | 22_100),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_warm() {
+        assert_eq!(is_warm(SpecId::CANCUN, StorageAccessKind::Load, 2_100), Some(false));
+        assert_eq!(is_warm(SpecId::CANCUN, StorageAccessKind::Load, 100), Some(true));
+        assert_eq!(is_warm(SpecId::CANCUN, StorageAccessKind::Store, 22_100), Some(false));
+        assert_eq!(is_warm(SpecId::CANCUN, StorageAccessKind::Store, 2_900), Some(true));
+        assert_eq!(is_warm(SpecId::ISTANBUL, StorageAccessKind::Load, 800), None);
+    }
+
+    #[test]
+    fn test_failed_call_undoes_values() {
+        let key = (Address::with_last_byte(1), U256::from(7));
+        let mut tracer = StorageAccessTracer::new();
+        tracer.enter();
+        tracer.set_value(key, U256::from(1));
+        tracer.enter();
+        tracer.set_value(key, U256::from(2));
+        tracer.exit(false);
+        assert_eq!(tracer.values.get(&key), Some(&U256::from(1)));
+        tracer.exit(true);
+
+        tracer.enter();
+        assert!(tracer.values.is_empty(), "A new transaction starts from the database values");
+    }
+}
@@ -0,0 +1,110 @@
+//! Native execution traces
+//!
+//! With native tracing enabled, guillotine-mini records one entry per executed opcode on the
+//! Zig side (the fields of geth's struct logger) and the whole trace is read back after
+//! execution, instead of crossing the FFI on every step as a step observer does.
+//!
+//! Deep tracing ([`DeepTraceLimits`]) additionally records a window of memory with every entry,
+//! for pinpointing the opcode where guillotine-mini and REVM diverge on a failing fixture.
+
+use super::ffi::{self, EvmHandle, FfiStructLog};
+use super::{ffi_safe, types};
+use revm::primitives::{Bytes, U256};
+
+/// What deep tracing records per opcode (see `GuillotineMiniEvm::set_deep_tracing`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeepTraceLimits {
+    /// Stack items recorded, the top-most ones (the whole stack by default)
+    pub max_stack_items: usize,
+    /// Bytes of memory recorded from offset 0 (4 KiB by default)
+    pub max_memory: usize,
+}
+
+impl Default for DeepTraceLimits {
+    fn default() -> Self {
+        Self { max_stack_items: 1024, max_memory: 4096 }
+    }
+}
+
+/// Interpreter state recorded before an opcode executed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StructLog {
+    /// Program counter of the opcode
+    pub pc: usize,
+    /// Opcode executed
+    pub op: u8,
+    /// Gas remaining before the opcode was charged
+    pub gas: u64,
+    /// Gas charged for the opcode
+    pub gas_cost: u64,
+    /// Call depth (0 for the top-level frame)
+    pub depth: usize,
+    /// Refund counter before the opcode executed
+    pub refund: u64,
+    /// Stack, bottom first (only the top-most items past `DeepTraceLimits::max_stack_items`)
+    pub stack: Vec<U256>,
+    /// Memory size in bytes
+    pub memory_size: usize,
+    /// Memory from offset 0, at most `DeepTraceLimits::max_memory` bytes, under deep tracing
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub memory: Option<Bytes>,
+}
+
+/// Read the struct logs recorded by the last execution from guillotine-mini FFI
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn read_struct_logs_from_ffi(handle: *mut EvmHandle) -> Result<Vec<StructLog>, &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    let count = unsafe { ffi::evm_get_struct_log_count(handle) };
+    let mut logs = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut entry = FfiStructLog::default();
+        if !unsafe { ffi::evm_get_struct_log(handle, i, &mut entry) } {
+            return Err("evm_get_struct_log failed");
+        }
+
+        let mut words = vec![0u8; entry.stack_len * 32];
+        let copied = unsafe { ffi::evm_get_struct_log_stack(handle, i, words.as_mut_ptr(), entry.stack_len) };
+        ffi_safe::check_buffer(entry.stack_len, copied)?;
+        let stack = words[..copied * 32]
+            .chunks_exact(32)
+            .map(|word| types::u256_from_be_bytes(word.try_into().expect("32-byte chunk")))
+            .collect();
+
+        logs.push(StructLog {
+            pc: entry.pc as usize,
+            op: entry.opcode,
+            gas: types::i64_to_u64_gas(entry.gas),
+            gas_cost: types::i64_to_u64_gas(entry.gas_cost),
+            depth: entry.depth as usize,
+            refund: entry.refund,
+            stack,
+            memory_size: entry.memory_size,
+            memory: None,
+        });
+    }
+    Ok(logs)
+}
+
+/// Read the memory recorded by deep tracing into `logs`, as read by [`read_struct_logs_from_ffi`]
+///
+/// # Safety
+/// The `handle` must be a valid non-null pointer to an EvmHandle created by `evm_create_with_hardfork`.
+pub(crate) fn read_struct_log_memory_from_ffi(handle: *mut EvmHandle, logs: &mut [StructLog]) -> Result<(), &'static str> {
+    ffi_safe::check_handle(handle)?;
+
+    for (i, log) in logs.iter_mut().enumerate() {
+        let len = unsafe { ffi::evm_get_struct_log_memory(handle, i, std::ptr::null_mut(), 0) };
+        let mut memory = vec![0u8; len];
+        let copied = unsafe { ffi::evm_get_struct_log_memory(handle, i, memory.as_mut_ptr(), memory.len()) };
+        if copied != len {
+            return Err("evm_get_struct_log_memory");
+        }
+        log.memory = Some(Bytes::from(memory));
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Detection of optional libguillotine_mini symbols
+//!
+//! Parts of the C ABI (the config builder) are only exported by some guillotine-mini builds.
+//! The build script compiles a small C shim referencing them as weak symbols: the linker resolves
+//! each reference to the library's function if it defines one and to null otherwise, so a library
+//! without them still links and the feature reports itself as unsupported. No linker flags are
+//! needed.
+//!
+//! Weak references do not pull archive members into the link by themselves, so the functions are
+//! found when they live in an object of libguillotine_mini that is linked anyway, as with its
+//! single-object builds. Weak undefined symbols need an ELF target; elsewhere nothing is detected.
+
+use std::ffi::{c_void, CStr};
+
+#[cfg(all(unix, not(target_vendor = "apple")))]
+extern "C" {
+    /// Address of the config ABI function `name` if libguillotine_mini defines it, else null
+    ///
+    /// Generated by the build script from the `config_abi!` declarations.
+    fn guillotine_rs_config_symbol(name: *const std::ffi::c_char) -> *mut c_void;
+}
+
+/// Address of the libguillotine_mini function `name`, or `None` if it is not linked
+#[cfg(all(unix, not(target_vendor = "apple")))]
+pub(crate) fn lookup(name: &CStr) -> Option<*mut c_void> {
+    let ptr = unsafe { guillotine_rs_config_symbol(name.as_ptr()) };
+    (!ptr.is_null()).then_some(ptr)
+}
+
+/// Address of the libguillotine_mini function `name`; weak symbols are not supported on this
+/// platform
+#[cfg(not(all(unix, not(target_vendor = "apple"))))]
+pub(crate) fn lookup(_name: &CStr) -> Option<*mut c_void> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_missing_symbol() {
+        assert!(lookup(CStr::from_bytes_with_nul(b"guillotine_rs_no_such_symbol\0").unwrap()).is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Storage pre-state sync strategies
+//!
+//! With host callbacks enabled (the default, see `GuillotineMiniEvm::set_host_callbacks`),
+//! guillotine-mini loads storage it does not hold from the REVM database on demand, and slots
+//! synced before execution only save a callback round trip each. With host callbacks disabled,
+//! only synced slots are visible to execution. A [`SyncStrategy`] decides which slots are synced
+//! up front, trading prefetching (or, without callbacks, correctness) against cost.
+
+use revm::{
+    context::TxEnv,
+    database::CacheDB,
+    primitives::{Address, U256},
+};
+use std::collections::HashMap;
+
+/// Storage slots to sync, grouped by account
+pub type SlotPlan = Vec<(Address, Vec<U256>)>;
+
+/// Chooses the storage slots synced to guillotine-mini before a transaction executes
+///
+/// Accounts named in the plan are synced along with their slots. Closures of the form
+/// `Fn(&TxEnv, Address) -> SlotPlan` implement this trait, for custom strategies.
+pub trait SyncStrategy: Send + Sync {
+    /// Slots to sync before executing `tx`, whose top-level code runs at `contract`
+    fn storage_slots(&self, tx: &TxEnv, contract: Address) -> SlotPlan;
+}
+
+impl<F> SyncStrategy for F
+where
+    F: Fn(&TxEnv, Address) -> SlotPlan + Send + Sync,
+{
+    fn storage_slots(&self, tx: &TxEnv, contract: Address) -> SlotPlan {
+        self(tx, contract)
+    }
+}
+
+/// Sync the first `count` storage slots of the called contract (10 by default)
+///
+/// Cheap and covers simple contracts whose state variables sit in low slots, but misses
+/// mappings, dynamic arrays and high-numbered slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommonSlots {
+    /// Number of leading slots to sync
+    pub count: u64,
+}
+
+impl Default for CommonSlots {
+    fn default() -> Self {
+        Self { count: 10 }
+    }
+}
+
+impl SyncStrategy for CommonSlots {
+    fn storage_slots(&self, _tx: &TxEnv, contract: Address) -> SlotPlan {
+        vec![(contract, (0..self.count).map(U256::from).collect())]
+    }
+}
+
+/// Sync exactly the accounts and slots declared in the transaction's EIP-2930 access list (the
+/// default strategy)
+///
+/// Correct for transactions carrying a complete access list (see
+/// `GuillotineMiniEvm::create_access_list`); anything not listed reads as zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccessListSlots;
+
+impl SyncStrategy for AccessListSlots {
+    fn storage_slots(&self, tx: &TxEnv, _contract: Address) -> SlotPlan {
+        tx.access_list
+            .0
+            .iter()
+            .map(|item| (item.address, item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0)).collect()))
+            .collect()
+    }
+}
+
+/// Sync every storage slot known to a `CacheDB`, for every account
+///
+/// The slot set is captured when the strategy is built (see `GuillotineMiniEvm::full_scan_strategy`),
+/// so slots inserted into the database afterwards are not included. Correct for fully cached
+/// state, at the cost of syncing storage the transaction never touches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FullScan {
+    slots: HashMap<Address, Vec<U256>>,
+}
+
+impl FullScan {
+    /// Capture the cached storage slots of every account in `db`
+    pub fn from_cache_db<ExtDB>(db: &CacheDB<ExtDB>) -> Self {
+        let slots = db
+            .cache
+            .accounts
+            .iter()
+            .filter(|(_, account)| !account.storage.is_empty())
+            .map(|(address, account)| (*address, account.storage.keys().copied().collect()))
+            .collect();
+        Self { slots }
+    }
+}
+
+impl SyncStrategy for FullScan {
+    fn storage_slots(&self, _tx: &TxEnv, _contract: Address) -> SlotPlan {
+        self.slots.iter().map(|(address, slots)| (*address, slots.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        context_interface::transaction::{AccessList, AccessListItem},
+        database::EmptyDB,
+        primitives::{address, B256},
+    };
+
+    #[test]
+    fn test_common_slots_and_access_list_plans() {
+        let contract = address!("1000000000000000000000000000000000000000");
+        let other = address!("2000000000000000000000000000000000000000");
+        let tx = TxEnv {
+            access_list: AccessList(vec![AccessListItem {
+                address: other,
+                storage_keys: vec![B256::with_last_byte(0x14)],
+            }]),
+            ..Default::default()
+        };
+
+        assert_eq!(CommonSlots { count: 2 }.storage_slots(&tx, contract), vec![(contract, vec![U256::ZERO, U256::from(1)])]);
+        assert_eq!(AccessListSlots.storage_slots(&tx, contract), vec![(other, vec![U256::from(0x14)])]);
+    }
+
+    #[test]
+    fn test_full_scan_captures_cached_storage() {
+        let contract = address!("1000000000000000000000000000000000000000");
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_storage(contract, U256::from(7), U256::from(1)).unwrap();
+
+        let plan = FullScan::from_cache_db(&db).storage_slots(&TxEnv::default(), contract);
+        assert_eq!(plan, vec![(contract, vec![U256::from(7)])]);
+    }
+}
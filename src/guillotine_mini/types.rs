@@ -4,6 +4,8 @@
 //! - REVM's alloy types (Address, U256, Bytes)
 //! - Guillotine-mini's C FFI types (byte arrays)
 
+use super::ffi;
+use revm::context_interface::result::{HaltReason, OutOfGasError};
 use revm::primitives::{Address, Bytes, U256};
 
 /// Convert REVM Address to 20-byte array for FFI
@@ -49,6 +51,22 @@ pub fn i64_to_u64_gas(gas: i64) -> u64 {
     gas.max(0) as u64
 }
 
+/// Convert an FFI execution status code to a REVM halt reason
+/// Returns None for success, revert, and unknown status codes
+#[inline]
+pub fn halt_reason_from_status(status: u8) -> Option<HaltReason> {
+    match status {
+        ffi::EVM_STATUS_OUT_OF_GAS => Some(HaltReason::OutOfGas(OutOfGasError::Basic)),
+        ffi::EVM_STATUS_INVALID_OPCODE => Some(HaltReason::OpcodeNotFound),
+        ffi::EVM_STATUS_STACK_OVERFLOW => Some(HaltReason::StackOverflow),
+        ffi::EVM_STATUS_STACK_UNDERFLOW => Some(HaltReason::StackUnderflow),
+        ffi::EVM_STATUS_INVALID_JUMP => Some(HaltReason::InvalidJump),
+        ffi::EVM_STATUS_CALL_DEPTH_EXCEEDED => Some(HaltReason::CallTooDeep),
+        ffi::EVM_STATUS_PRECOMPILE_FAILURE => Some(HaltReason::PrecompileError),
+        _ => None,
+    }
+}
+
 /// Log entry as exported from guillotine-mini
 /// Not currently used in the wrapper, but useful for conversions/tests
 /// TODO: Enable once log extraction API is finalized
@@ -99,6 +117,19 @@ mod tests {
         assert_eq!(bytes[0], 0);
     }
 
+    #[test]
+    fn test_halt_reason_from_status() {
+        assert_eq!(halt_reason_from_status(ffi::EVM_STATUS_SUCCESS), None);
+        assert_eq!(halt_reason_from_status(ffi::EVM_STATUS_REVERT), None);
+        assert_eq!(
+            halt_reason_from_status(ffi::EVM_STATUS_OUT_OF_GAS),
+            Some(HaltReason::OutOfGas(OutOfGasError::Basic))
+        );
+        assert_eq!(halt_reason_from_status(ffi::EVM_STATUS_INVALID_JUMP), Some(HaltReason::InvalidJump));
+        assert_eq!(halt_reason_from_status(ffi::EVM_STATUS_CALL_DEPTH_EXCEEDED), Some(HaltReason::CallTooDeep));
+        assert_eq!(halt_reason_from_status(ffi::EVM_STATUS_PRECOMPILE_FAILURE), Some(HaltReason::PrecompileError));
+    }
+
     #[test]
     fn test_bytes_conversion() {
         let data = Bytes::from(vec![0x60, 0x01, 0x60, 0x02, 0x01]);
@@ -0,0 +1,289 @@
+//! Pre-execution transaction validation
+//!
+//! Mirrors REVM's transaction validation so invalid transactions are rejected with
+//! `InvalidTransaction` errors before anything is handed to guillotine-mini.
+
+use revm::{
+    context::{Cfg, TxEnv},
+    context_interface::{result::InvalidTransaction, transaction::TransactionType, Block},
+    primitives::{
+        eip4844::{
+            BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, GAS_PER_BLOB,
+            VERSIONED_HASH_VERSION_KZG,
+        },
+        hardfork::SpecId,
+        B256, U256,
+    },
+    state::AccountInfo,
+};
+
+/// Value plus maximum gas fee of a transaction, and for blob transactions the maximum blob
+/// gas fee: the balance its sender must hold
+pub fn max_tx_cost(tx: &TxEnv) -> U256 {
+    let mut cost = U256::from(tx.gas_limit).saturating_mul(U256::from(tx.gas_price)).saturating_add(tx.value);
+    if tx.tx_type == TransactionType::Eip4844 as u8 {
+        let blob_gas = GAS_PER_BLOB.saturating_mul(tx.blob_hashes.len() as u64);
+        cost = cost.saturating_add(U256::from(blob_gas).saturating_mul(U256::from(tx.max_fee_per_blob_gas)));
+    }
+    cost
+}
+
+/// Validate a transaction against the configuration, block and sender pre-state
+///
+/// Checks the chain id, fee caps against the block base fee, the gas limit against the block
+/// gas limit, blob transaction shape, blob fee cap and versioned hashes, the sender nonce, and
+/// that the sender can pay the value plus the maximum gas and blob gas fees.
+/// Checks disabled in the configuration (e.g. `disable_nonce_check`) are skipped.
+pub fn validate_tx<CFG, BLOCK>(cfg: &CFG, block: &BLOCK, tx: &TxEnv, caller: &AccountInfo) -> Result<(), InvalidTransaction>
+where
+    CFG: Cfg<Spec = SpecId>,
+    BLOCK: Block,
+{
+    validate_tx_with_base_fee_check(cfg, block, tx, caller, !cfg.is_base_fee_check_disabled())
+}
+
+/// [`validate_tx`], checking the fee caps against the block base fee only if `check_base_fee`
+pub fn validate_tx_with_base_fee_check<CFG, BLOCK>(
+    cfg: &CFG,
+    block: &BLOCK,
+    tx: &TxEnv,
+    caller: &AccountInfo,
+    check_base_fee: bool,
+) -> Result<(), InvalidTransaction>
+where
+    CFG: Cfg<Spec = SpecId>,
+    BLOCK: Block,
+{
+    if let Some(chain_id) = tx.chain_id {
+        if chain_id != cfg.chain_id() {
+            return Err(InvalidTransaction::InvalidChainId);
+        }
+    }
+
+    if cfg.spec().is_enabled_in(SpecId::LONDON) {
+        if let Some(priority_fee) = tx.gas_priority_fee {
+            if priority_fee > tx.gas_price {
+                return Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee);
+            }
+        }
+        if check_base_fee && tx.gas_price < block.basefee() as u128 {
+            return Err(InvalidTransaction::GasPriceLessThanBasefee);
+        }
+    }
+
+    if !cfg.is_block_gas_limit_disabled() && tx.gas_limit > block.gas_limit() {
+        return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
+    }
+
+    if tx.tx_type == TransactionType::Eip4844 as u8 {
+        if tx.kind.is_create() {
+            return Err(InvalidTransaction::BlobCreateTransaction);
+        }
+        let block_blob_gas_price = block.blob_gasprice().unwrap_or_default();
+        if block_blob_gas_price > tx.max_fee_per_blob_gas {
+            return Err(InvalidTransaction::BlobGasPriceGreaterThanMax {
+                block_blob_gas_price,
+                tx_max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+            });
+        }
+        if tx.blob_hashes.is_empty() {
+            return Err(InvalidTransaction::EmptyBlobs);
+        }
+    }
+    validate_blob_hashes(cfg.spec(), &tx.blob_hashes)?;
+
+    if !cfg.is_nonce_check_disabled() {
+        if tx.nonce > caller.nonce {
+            return Err(InvalidTransaction::NonceTooHigh { tx: tx.nonce, state: caller.nonce });
+        }
+        if tx.nonce < caller.nonce {
+            return Err(InvalidTransaction::NonceTooLow { tx: tx.nonce, state: caller.nonce });
+        }
+    }
+
+    if !cfg.is_balance_check_disabled() {
+        let max_cost = max_tx_cost(tx);
+        if caller.balance < max_cost {
+            return Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(max_cost),
+                balance: Box::new(caller.balance),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Maximum number of blobs a single transaction may carry under `spec` (0 before Cancun)
+///
+/// Bounded by the block blob limit (EIP-4844, EIP-7691) and, from Osaka, by the per-transaction
+/// limit of EIP-7594.
+pub fn max_blobs_per_tx(spec: SpecId) -> usize {
+    if spec.is_enabled_in(SpecId::OSAKA) {
+        6
+    } else if spec.is_enabled_in(SpecId::PRAGUE) {
+        9
+    } else if spec.is_enabled_in(SpecId::CANCUN) {
+        6
+    } else {
+        0
+    }
+}
+
+/// Blob base fee update fraction under `spec`, from which BLOBBASEFEE is derived with the excess blob gas
+///
+/// Raised by EIP-7691 in Prague; Osaka keeps Prague's value.
+pub fn blob_base_fee_update_fraction(spec: SpecId) -> u64 {
+    if spec.is_enabled_in(SpecId::PRAGUE) {
+        BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE
+    } else {
+        BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN
+    }
+}
+
+/// Validate EIP-4844 versioned hashes: KZG version prefix and blob count limit of the fork
+pub fn validate_blob_hashes(spec: SpecId, hashes: &[B256]) -> Result<(), InvalidTransaction> {
+    if hashes.is_empty() {
+        return Ok(());
+    }
+    if !spec.is_enabled_in(SpecId::CANCUN) {
+        return Err(InvalidTransaction::Eip4844NotSupported);
+    }
+    if hashes.iter().any(|hash| hash[0] != VERSIONED_HASH_VERSION_KZG) {
+        return Err(InvalidTransaction::BlobVersionNotSupported);
+    }
+    let max = max_blobs_per_tx(spec);
+    if hashes.len() > max {
+        return Err(InvalidTransaction::TooManyBlobs { have: hashes.len(), max });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::context::{BlockEnv, CfgEnv};
+
+    fn funded(balance: u64, nonce: u64) -> AccountInfo {
+        AccountInfo { balance: U256::from(balance), nonce, ..Default::default() }
+    }
+
+    fn tx() -> TxEnv {
+        TxEnv { gas_limit: 100_000, gas_price: 10, nonce: 3, chain_id: Some(1), ..Default::default() }
+    }
+
+    #[test]
+    fn test_valid_transaction_passes() {
+        let cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        let block = BlockEnv { basefee: 7, ..Default::default() };
+        assert_eq!(validate_tx(&cfg, &block, &tx(), &funded(1_000_000, 3)), Ok(()));
+    }
+
+    #[test]
+    fn test_nonce_mismatch() {
+        let cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        let block = BlockEnv::default();
+        assert_eq!(
+            validate_tx(&cfg, &block, &tx(), &funded(1_000_000, 2)),
+            Err(InvalidTransaction::NonceTooHigh { tx: 3, state: 2 })
+        );
+        assert_eq!(
+            validate_tx(&cfg, &block, &tx(), &funded(1_000_000, 4)),
+            Err(InvalidTransaction::NonceTooLow { tx: 3, state: 4 })
+        );
+    }
+
+    #[test]
+    fn test_insufficient_balance_for_max_fee() {
+        let cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        let block = BlockEnv::default();
+        assert_eq!(
+            validate_tx(&cfg, &block, &tx(), &funded(999_999, 3)),
+            Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(U256::from(1_000_000)),
+                balance: Box::new(U256::from(999_999)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_fee_and_limit_checks() {
+        let cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        let caller = funded(u64::MAX, 3);
+
+        let block = BlockEnv { basefee: 11, ..Default::default() };
+        assert_eq!(validate_tx(&cfg, &block, &tx(), &caller), Err(InvalidTransaction::GasPriceLessThanBasefee));
+
+        let block = BlockEnv { gas_limit: 99_999, ..Default::default() };
+        assert_eq!(validate_tx(&cfg, &block, &tx(), &caller), Err(InvalidTransaction::CallerGasLimitMoreThanBlock));
+
+        let block = BlockEnv::default();
+        let wrong_chain = TxEnv { chain_id: Some(5), ..tx() };
+        assert_eq!(validate_tx(&cfg, &block, &wrong_chain, &caller), Err(InvalidTransaction::InvalidChainId));
+
+        let tip_above_cap = TxEnv { gas_priority_fee: Some(11), ..tx() };
+        assert_eq!(
+            validate_tx(&cfg, &block, &tip_above_cap, &caller),
+            Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee)
+        );
+    }
+
+    #[test]
+    fn test_blob_hash_validation() {
+        let mut hash = B256::repeat_byte(0x22);
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+        assert_eq!(validate_blob_hashes(SpecId::SHANGHAI, &[]), Ok(()));
+        assert_eq!(validate_blob_hashes(SpecId::SHANGHAI, &[hash]), Err(InvalidTransaction::Eip4844NotSupported));
+        assert_eq!(validate_blob_hashes(SpecId::CANCUN, &[hash; 6]), Ok(()));
+        assert_eq!(
+            validate_blob_hashes(SpecId::CANCUN, &[hash; 7]),
+            Err(InvalidTransaction::TooManyBlobs { have: 7, max: 6 })
+        );
+        assert_eq!(validate_blob_hashes(SpecId::PRAGUE, &[hash; 9]), Ok(()));
+        assert_eq!(
+            validate_blob_hashes(SpecId::OSAKA, &[hash; 7]),
+            Err(InvalidTransaction::TooManyBlobs { have: 7, max: 6 })
+        );
+        assert_eq!(
+            validate_blob_hashes(SpecId::CANCUN, &[B256::repeat_byte(0x22)]),
+            Err(InvalidTransaction::BlobVersionNotSupported)
+        );
+    }
+
+    #[test]
+    fn test_blob_fee_checks() {
+        let cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        // The default block has no excess blob gas: a blob gas price of 1
+        let block = BlockEnv::default();
+        let mut hash = B256::repeat_byte(0x22);
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+        let blob_tx =
+            TxEnv { tx_type: TransactionType::Eip4844 as u8, blob_hashes: vec![hash], max_fee_per_blob_gas: 2, ..tx() };
+
+        // 100_000 * 10 gas fee plus 131_072 blob gas at 2
+        assert_eq!(max_tx_cost(&blob_tx), U256::from(1_262_144));
+        assert_eq!(validate_tx(&cfg, &block, &blob_tx, &funded(1_262_144, 3)), Ok(()));
+        assert_eq!(
+            validate_tx(&cfg, &block, &blob_tx, &funded(1_262_143, 3)),
+            Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(U256::from(1_262_144)),
+                balance: Box::new(U256::from(1_262_143)),
+            })
+        );
+
+        let below_blob_price = TxEnv { max_fee_per_blob_gas: 0, ..blob_tx };
+        assert_eq!(
+            validate_tx(&cfg, &block, &below_blob_price, &funded(u64::MAX, 3)),
+            Err(InvalidTransaction::BlobGasPriceGreaterThanMax { block_blob_gas_price: 1, tx_max_fee_per_blob_gas: 0 })
+        );
+    }
+
+    #[test]
+    fn test_disabled_nonce_check_is_skipped() {
+        let mut cfg = CfgEnv::new_with_spec(SpecId::CANCUN);
+        cfg.disable_nonce_check = true;
+        let block = BlockEnv::default();
+        assert_eq!(validate_tx(&cfg, &block, &tx(), &funded(1_000_000, 0)), Ok(()));
+    }
+}
@@ -1,12 +1,26 @@
 //! Integration tests for EVM configuration API
 
-use guillotine_rs::guillotine_mini::{EvmConfigBuilder, GuillotineMiniEvm, PrecompileResult, PrecompileError};
+#![cfg(feature = "config-api")]
+
+use guillotine_rs::guillotine_mini::{
+    ffi, EvmAdapterError, EvmConfigBuilder, GuillotineMiniEvm, PrecompileError, PrecompileResult,
+};
 use revm::{
     context::{Context, TxEnv},
-    primitives::{Address, Bytes, TxKind, U256},
+    primitives::{hardfork::SpecId, Address, Bytes, TxKind, U256},
     MainContext,
 };
 
+/// Whether the linked libguillotine_mini exports the config ABI; tests running EVMs created from
+/// a configuration are skipped without it
+fn config_abi_available() -> bool {
+    let available = ffi::config_api().is_some();
+    if !available {
+        eprintln!("libguillotine_mini does not export the config ABI, skipping");
+    }
+    available
+}
+
 #[test]
 fn test_config_basic_creation() {
     let _config = EvmConfigBuilder::new().build();
@@ -15,7 +29,7 @@ fn test_config_basic_creation() {
 
 #[test]
 fn test_config_with_hardfork() {
-    let _config = EvmConfigBuilder::new().hardfork("Cancun").build();
+    let _config = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).build();
     // Config created successfully
 }
 
@@ -63,7 +77,7 @@ fn test_config_with_system_contracts_disabled() {
 #[test]
 fn test_config_chained_builder() {
     let _config = EvmConfigBuilder::new()
-        .hardfork("Cancun")
+        .hardfork(SpecId::CANCUN)
         .stack_size(2048)
         .max_call_depth(2048)
         .memory_limit(0x2000000)
@@ -77,18 +91,22 @@ fn test_config_chained_builder() {
 fn test_evm_creation_with_config() {
     let ctx = Context::mainnet();
     let config = EvmConfigBuilder::new()
-        .hardfork("Cancun")
+        .hardfork(SpecId::CANCUN)
         .build();
 
     let result = GuillotineMiniEvm::with_config(ctx, config);
-    assert!(result.is_ok());
+    if ffi::config_api().is_some() {
+        assert!(result.is_ok());
+    } else {
+        assert!(matches!(result, Err(EvmAdapterError::Unsupported("config"))));
+    }
 }
 
 #[test]
 fn test_config_with_custom_opcode() {
     let _config = EvmConfigBuilder::new()
-        .hardfork("Cancun")
-        .override_opcode(0xFF, |_frame_ptr, _opcode| {
+        .hardfork(SpecId::CANCUN)
+        .override_opcode(0xFF, |_frame, _opcode| {
             // This won't actually be called in this test, but validates compilation
             true
         })
@@ -99,8 +117,8 @@ fn test_config_with_custom_opcode() {
 #[test]
 fn test_config_with_custom_precompile() {
     let _config = EvmConfigBuilder::new()
-        .hardfork("Cancun")
-        .override_precompile([0u8; 20], |_addr, input, _gas| {
+        .hardfork(SpecId::CANCUN)
+        .override_precompile(Address::ZERO, |_addr, input, _gas| {
             // Echo precompile: returns input as output
             Ok(PrecompileResult {
                 output: input.to_vec(),
@@ -124,13 +142,13 @@ fn test_multiple_opcode_overrides() {
 #[test]
 fn test_multiple_precompile_overrides() {
     let _config = EvmConfigBuilder::new()
-        .override_precompile([1u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], |_, input, _| {
+        .override_precompile(Address::with_last_byte(1), |_, input, _| {
             Ok(PrecompileResult {
                 output: input.to_vec(),
                 gas_used: 100,
             })
         })
-        .override_precompile([2u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], |_, _input, _| {
+        .override_precompile(Address::with_last_byte(2), |_, _input, _| {
             Ok(PrecompileResult {
                 output: vec![],
                 gas_used: 50,
@@ -145,3 +163,521 @@ fn test_config_default_trait() {
     let _config = EvmConfigBuilder::default().build();
     // Config created successfully using default trait
 }
+
+/// Call the IDENTITY precompile (0x04) with `input` on a Cancun EVM created from `config`,
+/// returning the output and the gas used
+fn call_identity(config: guillotine_rs::guillotine_mini::EvmConfig, input: &[u8]) -> (Bytes, u64) {
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN);
+    let mut evm = GuillotineMiniEvm::with_config(ctx, config).unwrap();
+    let tx = TxEnv::builder()
+        .caller(Address::repeat_byte(0xaa))
+        .kind(TxKind::Call(Address::with_last_byte(4)))
+        .data(Bytes::copy_from_slice(input))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    (result.result.output().cloned().unwrap_or_default(), result.result.gas_used())
+}
+
+/// Gas of a call carrying `b"echo"` to an account without code: intrinsic plus 4 non-zero bytes
+const ECHO_TRANSFER_GAS: u64 = 21_000 + 4 * 16;
+
+/// Gas of the same call running IDENTITY: 15 plus 3 per word
+const ECHO_IDENTITY_GAS: u64 = ECHO_TRANSFER_GAS + 15 + 3;
+
+#[test]
+fn test_config_disable_precompile() {
+    if !config_abi_available() {
+        return;
+    }
+    let enabled = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).build();
+    let (output, gas_used) = call_identity(enabled, b"echo");
+    assert_eq!(output.as_ref(), b"echo");
+    assert_eq!(gas_used, ECHO_IDENTITY_GAS);
+
+    // Without the precompile, 0x04 is an empty account
+    let disabled = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .disable_precompile(Address::with_last_byte(4))
+        .build();
+    let (output, gas_used) = call_identity(disabled, b"echo");
+    assert!(output.is_empty());
+    assert_eq!(gas_used, ECHO_TRANSFER_GAS);
+}
+
+#[test]
+fn test_config_replace_precompile_set() {
+    if !config_abi_available() {
+        return;
+    }
+    let sha256_only = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .precompiles([Address::with_last_byte(2)])
+        .build();
+    let (output, gas_used) = call_identity(sha256_only, b"echo");
+    assert!(output.is_empty());
+    assert_eq!(gas_used, ECHO_TRANSFER_GAS);
+
+    let none = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).precompiles([]).build();
+    let (output, gas_used) = call_identity(none, b"echo");
+    assert!(output.is_empty());
+    assert_eq!(gas_used, ECHO_TRANSFER_GAS);
+}
+
+#[test]
+fn test_config_eip_toggle() {
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{AccountInfo, Bytecode},
+    };
+
+    if !config_abi_available() {
+        return;
+    }
+
+    // PUSH1 1 PUSH1 0 TSTORE PUSH1 0 TLOAD: invalid opcodes without EIP-1153
+    let contract = Address::repeat_byte(0x11);
+    let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x5d, 0x60, 0x00, 0x5c]));
+    let run = |config| {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo { code_hash: code.hash_slow(), code: Some(code.clone()), ..Default::default() },
+        );
+        let mut evm = GuillotineMiniEvm::with_config(Context::mainnet().with_db(db), config).unwrap();
+        let tx = TxEnv::builder().caller(Address::repeat_byte(0xaa)).kind(TxKind::Call(contract)).gas_limit(100_000);
+        evm.transact(tx.build().unwrap()).unwrap().result.is_success()
+    };
+
+    assert!(run(EvmConfigBuilder::new().hardfork(SpecId::CANCUN).build()));
+    assert!(!run(EvmConfigBuilder::new().hardfork(SpecId::CANCUN).eip(1153, false).build()));
+    assert!(run(EvmConfigBuilder::new().hardfork(SpecId::SHANGHAI).eip(1153, true).build()));
+}
+
+/// Call a custom precompile at 0x0100 answering with `response`, forwarding `gas_limit`
+///
+/// Panics unless the handler was invoked.
+fn call_custom_precompile(
+    response: Result<PrecompileResult, PrecompileError>,
+    gas_limit: u64,
+) -> revm::context_interface::result::ExecutionResult {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let precompile = revm::primitives::address!("0000000000000000000000000000000000000100");
+    let called = Arc::new(AtomicBool::new(false));
+    let handler_called = Arc::clone(&called);
+    let config = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .override_precompile(precompile, move |address, input, _gas| {
+            handler_called.store(true, Ordering::SeqCst);
+            assert_eq!(address, precompile);
+            assert_eq!(input, b"in");
+            response.clone()
+        })
+        .build();
+    let mut evm = GuillotineMiniEvm::with_config(Context::mainnet(), config).unwrap();
+    let tx = TxEnv::builder()
+        .caller(Address::repeat_byte(0xaa))
+        .kind(TxKind::Call(precompile))
+        .data(Bytes::from_static(b"in"))
+        .gas_limit(gas_limit)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx).unwrap().result;
+    assert!(called.load(Ordering::SeqCst), "the custom precompile handler was not called");
+    result
+}
+
+#[test]
+fn test_config_precompile_error_paths() {
+    use revm::context_interface::result::{ExecutionResult, HaltReason, OutOfGasError};
+
+    if !config_abi_available() {
+        return;
+    }
+
+    let ok = |gas_used| Ok(PrecompileResult { output: b"out".to_vec(), gas_used });
+    match call_custom_precompile(ok(100), 100_000) {
+        ExecutionResult::Success { output, gas_used, .. } => {
+            assert_eq!(output.data().as_ref(), b"out");
+            assert_eq!(gas_used, 21_000 + 16 * 2 + 100);
+        }
+        other => panic!("expected success, got {other:?}"),
+    }
+
+    // Errors halt the call and consume all its gas
+    let halt = |response, expected: HaltReason| match call_custom_precompile(response, 100_000) {
+        ExecutionResult::Halt { reason, gas_used } => {
+            assert_eq!(reason, expected);
+            assert_eq!(gas_used, 100_000);
+        }
+        other => panic!("expected halt {expected:?}, got {other:?}"),
+    };
+    halt(Err(PrecompileError::OutOfGas), HaltReason::OutOfGas(OutOfGasError::Basic));
+    halt(ok(100_000), HaltReason::OutOfGas(OutOfGasError::Basic));
+    halt(Err(PrecompileError::InvalidInput), HaltReason::PrecompileError);
+    halt(Err(PrecompileError::ExecutionFailed("unsupported curve".into())), HaltReason::PrecompileError);
+}
+
+#[test]
+fn test_config_opcode_handler_mutates_frame() {
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{AccountInfo, Bytecode},
+    };
+
+    if !config_abi_available() {
+        return;
+    }
+
+    // PUSH1 21 <0x0c> PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN, with 0x0c doubling the top item
+    let contract = Address::repeat_byte(0x11);
+    let code = Bytecode::new_raw(Bytes::from_static(&[
+        0x60, 0x15, 0x0c, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+    ]));
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        contract,
+        AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+    );
+
+    let config = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .override_opcode(0x0c, |frame, opcode| {
+            assert_eq!((opcode, frame.pc(), frame.peek(0)), (0x0c, 2, Some(U256::from(21))));
+            if frame.consume_gas(5).is_err() {
+                return false;
+            }
+            let value = frame.pop().unwrap();
+            assert_eq!(frame.stack_len(), 0);
+            frame.push(value * U256::from(2)).is_ok()
+        })
+        .build();
+    let mut evm = GuillotineMiniEvm::with_config(Context::mainnet().with_db(db), config).unwrap();
+    let tx = TxEnv::builder().caller(Address::repeat_byte(0xaa)).kind(TxKind::Call(contract)).gas_limit(100_000);
+    let result = evm.transact(tx.build().unwrap()).unwrap().result;
+    assert!(result.is_success());
+    assert_eq!(U256::from_be_slice(result.output().unwrap()), U256::from(42));
+}
+
+#[test]
+fn test_config_define_opcode() {
+    use guillotine_rs::guillotine_mini::ConfigError;
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{AccountInfo, Bytecode},
+    };
+
+    if !config_abi_available() {
+        return;
+    }
+
+    // <0x21> PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN, with 0x21 pushing 7 for 1000 gas
+    let contract = Address::repeat_byte(0x11);
+    let code = Bytecode::new_raw(Bytes::from_static(&[0x21, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]));
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        contract,
+        AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+    );
+
+    let config = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .define_opcode(0x21, 1_000, |frame| frame.push(U256::from(7)).is_ok())
+        .build();
+    let mut evm = GuillotineMiniEvm::with_config(Context::mainnet().with_db(db), config).unwrap();
+    let tx = TxEnv::builder().caller(Address::repeat_byte(0xaa)).kind(TxKind::Call(contract)).gas_limit(100_000);
+    let result = evm.transact(tx.build().unwrap()).unwrap().result;
+    assert!(result.is_success());
+    assert_eq!(U256::from_be_slice(result.output().unwrap()), U256::from(7));
+    assert!(result.gas_used() > 22_000);
+
+    let result = EvmConfigBuilder::new().try_define_opcode(0x01, 3, |_| true);
+    assert_eq!(result.err(), Some(ConfigError::AssignedOpcode(0x01)));
+}
+
+#[test]
+fn test_config_settings_recorded() {
+    use guillotine_rs::guillotine_mini::ConfigSettings;
+
+    let builder = EvmConfigBuilder::new()
+        .hardfork(SpecId::CANCUN)
+        .max_call_depth(512)
+        .loop_quota(None)
+        .disable_precompile(Address::with_last_byte(4))
+        .eip(1153, false)
+        .override_opcode(0x0c, |_, _| false);
+    let expected = ConfigSettings {
+        hardfork: Some("Cancun".to_string()),
+        max_call_depth: Some(512),
+        loop_quota: Some(0),
+        disabled_precompiles: vec![Address::with_last_byte(4)],
+        disabled_eips: vec![1153],
+        ..Default::default()
+    };
+    assert_eq!(builder.settings(), &expected);
+    assert_eq!(builder.build().settings(), &expected);
+    assert_eq!(EvmConfigBuilder::from_settings(&expected).settings(), &expected);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_config_json_round_trip() {
+    let config = EvmConfigBuilder::new().hardfork(SpecId::PRAGUE).stack_size(512).eip(6780, false).build();
+    let json = config.settings().to_json();
+    assert_eq!(EvmConfigBuilder::from_json(&json).unwrap().settings(), config.settings());
+
+    assert!(EvmConfigBuilder::from_json(r#"{ "stack_sise": 512 }"#).is_err(), "Unknown fields are rejected");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_config_from_toml() {
+    let builder = EvmConfigBuilder::from_toml(
+        r#"
+        hardfork = "Cancun"
+        max_call_depth = 256
+        precompiles = ["0x0000000000000000000000000000000000000001"]
+        disabled_eips = [1153]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(builder.settings().max_call_depth, Some(256));
+    assert_eq!(builder.settings().precompiles, Some(vec![Address::with_last_byte(1)]));
+    assert_eq!(EvmConfigBuilder::from_toml(&builder.settings().to_toml()).unwrap().settings(), builder.settings());
+}
+
+#[test]
+fn test_config_try_methods_return_errors() {
+    use guillotine_rs::guillotine_mini::ConfigError;
+
+    if !config_abi_available() {
+        return;
+    }
+
+    let not_a_precompile = Address::repeat_byte(0x42);
+    let result = EvmConfigBuilder::try_new().unwrap().hardfork(SpecId::CANCUN).try_disable_precompile(not_a_precompile);
+    assert_eq!(result.err(), Some(ConfigError::UnknownPrecompile(not_a_precompile)));
+
+    let result = EvmConfigBuilder::try_new().unwrap().try_eip(9999, true);
+    assert_eq!(result.err(), Some(ConfigError::UnsupportedEip(9999)));
+
+    let result = EvmConfigBuilder::try_new().unwrap().try_hardfork_name("cancun");
+    assert_eq!(result.err(), Some(ConfigError::UnknownHardfork("cancun".to_string())));
+
+    let result = EvmConfigBuilder::try_new().unwrap().try_precompiles([not_a_precompile]);
+    assert!(matches!(result, Err(ConfigError::Ffi(err)) if err.call == "evm_config_set_precompiles"));
+
+    let config = EvmConfigBuilder::try_new()
+        .and_then(|builder| builder.try_override_opcode(0x0c, |_, _| false))
+        .and_then(|builder| builder.try_override_precompile(Address::with_last_byte(0xff), |_, _, _| {
+            Err(PrecompileError::InvalidInput)
+        }));
+    assert!(config.is_ok());
+}
+
+#[test]
+fn test_config_presets() {
+    use guillotine_rs::guillotine_mini::{ConfigSettings, EvmConfig};
+
+    if !config_abi_available() {
+        return;
+    }
+
+    let shanghai = ConfigSettings::mainnet(SpecId::SHANGHAI);
+    assert_eq!(shanghai.hardfork.as_deref(), Some("Shanghai"));
+    assert!(!shanghai.system_contracts.unwrap().beacon_roots);
+    let prague = ConfigSettings::mainnet(SpecId::PRAGUE).system_contracts.unwrap();
+    assert!(prague.beacon_roots && prague.block_hashes && prague.deposits && prague.withdrawals);
+
+    for config in [EvmConfig::mainnet(SpecId::CANCUN), EvmConfig::testing(), EvmConfig::l2_generic()] {
+        assert!(GuillotineMiniEvm::with_config(Context::mainnet(), config).is_ok());
+    }
+    assert_eq!(EvmConfig::testing().settings(), &ConfigSettings::testing());
+}
+
+#[test]
+fn test_config_unlimited_code_size() {
+    if !config_abi_available() {
+        return;
+    }
+    // Initcode returning 0x6001 zero bytes, one over the EIP-170 limit:
+    // PUSH2 0x6001 PUSH1 0 RETURN
+    let deploy = |evm: &mut GuillotineMiniEvm<_>| {
+        let tx = TxEnv::builder()
+            .caller(Address::repeat_byte(0xaa))
+            .kind(TxKind::Create)
+            .data(Bytes::from_static(&[0x61, 0x60, 0x01, 0x60, 0x00, 0xf3]))
+            .gas_limit(10_000_000)
+            .build()
+            .unwrap();
+        evm.transact(tx).unwrap().result.is_success()
+    };
+
+    let limited = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).build();
+    assert!(!deploy(&mut GuillotineMiniEvm::with_config(Context::mainnet(), limited).unwrap()));
+    let unlimited = EvmConfigBuilder::new().hardfork(SpecId::CANCUN).unlimited_code_size().build();
+    assert!(deploy(&mut GuillotineMiniEvm::with_config(Context::mainnet(), unlimited).unwrap()));
+
+    // The context's limit is carried over when no configuration is given
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.limit_contract_code_size = Some(usize::MAX));
+    assert!(deploy(&mut GuillotineMiniEvm::builder().ctx(ctx).build().unwrap()));
+}
+
+#[test]
+fn test_config_precompile_provider() {
+    use guillotine_rs::guillotine_mini::{GuillotinePrecompiles, Hardfork, Precompile, PrecompileProvider};
+    use std::{
+        cell::Cell,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    if !config_abi_available() {
+        return;
+    }
+
+    /// Only IDENTITY, reimplemented to reverse its input; records the hardfork it was asked for
+    /// and counts the calls of its handler
+    #[derive(Default)]
+    struct Reversing {
+        asked: Cell<Option<Hardfork>>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl PrecompileProvider for Reversing {
+        fn precompiles(&self, hardfork: Hardfork) -> Vec<(Address, Precompile)> {
+            self.asked.set(Some(hardfork));
+            let calls = Arc::clone(&self.calls);
+            let reverse = Precompile::custom(move |_, input, _| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(PrecompileResult { output: input.iter().rev().copied().collect(), gas_used: 15 })
+            });
+            vec![(Address::with_last_byte(4), reverse)]
+        }
+    }
+
+    let builtin = EvmConfigBuilder::new().precompile_provider(Hardfork::Cancun, &GuillotinePrecompiles).build();
+    assert_eq!(builtin.settings().hardfork.as_deref(), Some("Cancun"));
+    let cancun: Vec<Address> =
+        GuillotinePrecompiles.precompiles(Hardfork::Cancun).into_iter().map(|(address, _)| address).collect();
+    assert_eq!(builtin.settings().precompiles.as_ref(), Some(&cancun));
+    assert_eq!(call_identity(builtin, b"echo"), (Bytes::from_static(b"echo"), ECHO_IDENTITY_GAS));
+
+    let provider = Reversing::default();
+    let reversing = EvmConfigBuilder::new().precompile_provider(Hardfork::Cancun, &provider).build();
+    assert_eq!(provider.asked.get(), Some(Hardfork::Cancun));
+    assert_eq!(reversing.settings().precompiles.as_deref(), Some(&[][..]));
+    assert_eq!(call_identity(reversing, b"echo"), (Bytes::from_static(b"ohce"), ECHO_TRANSFER_GAS + 15));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_config_shared_between_evms() {
+    use guillotine_rs::guillotine_mini::EvmPool;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    if !config_abi_available() {
+        return;
+    }
+
+    // IDENTITY replaced by a handler reversing its input and counting its calls, registered once
+    // for every instance
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let config = Arc::new(
+        EvmConfigBuilder::new()
+            .hardfork(SpecId::CANCUN)
+            .override_precompile(Address::with_last_byte(4), move |_, input, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(PrecompileResult { output: input.iter().rev().copied().collect(), gas_used: 15 })
+            })
+            .build(),
+    );
+    let tx = || {
+        TxEnv::builder()
+            .caller(Address::repeat_byte(0xaa))
+            .kind(TxKind::Call(Address::with_last_byte(4)))
+            .data(Bytes::from_static(b"echo"))
+            .gas_limit(100_000)
+            .build()
+            .unwrap()
+    };
+
+    let ctx = || Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN);
+    let reversed = |result: revm::context_interface::result::ExecutionResult| {
+        assert!(result.is_success());
+        assert_eq!(result.output().unwrap().as_ref(), b"ohce");
+    };
+
+    let mut first = GuillotineMiniEvm::with_config(ctx(), Arc::clone(&config)).unwrap();
+    let mut second = GuillotineMiniEvm::with_config(ctx(), Arc::clone(&config)).unwrap();
+    reversed(first.transact(tx()).unwrap().result);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    reversed(second.transact(tx()).unwrap().result);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    // Internal instances come from the configuration too
+    reversed(first.call(tx()).unwrap());
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    let pool = EvmPool::with_config(SpecId::CANCUN, 2, Arc::clone(&config)).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| reversed(pool.transact(ctx, tx()).unwrap().result));
+        }
+    });
+    assert_eq!(calls.load(Ordering::SeqCst), 7);
+}
+
+#[test]
+fn test_config_runtime_update() {
+    use guillotine_rs::guillotine_mini::{EvmAdapterError, SystemContracts};
+    use revm::context_interface::result::InvalidTransaction;
+
+    if !config_abi_available() {
+        return;
+    }
+
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+        .modify_block_chained(|block| block.basefee = 10);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    let runtime = evm.runtime_config();
+    assert_eq!(runtime.block_gas_limit, 30_000_000);
+    assert!(!runtime.disable_base_fee);
+    assert_eq!(
+        runtime.system_contracts,
+        SystemContracts { beacon_roots: true, block_hashes: false, deposits: false, withdrawals: false }
+    );
+
+    // Priced below the block base fee, from an empty account
+    let tx = || {
+        TxEnv::builder()
+            .caller(Address::repeat_byte(0xaa))
+            .kind(TxKind::Call(Address::repeat_byte(0xbb)))
+            .gas_price(0)
+            .gas_limit(21_000)
+            .build()
+            .unwrap()
+    };
+    let result = evm.transact(tx());
+    assert_eq!(result.err(), Some(EvmAdapterError::Transaction(InvalidTransaction::GasPriceLessThanBasefee)));
+
+    evm.update_config(|runtime| {
+        runtime.disable_base_fee = true;
+        runtime.block_gas_limit = 60_000_000;
+    })
+    .unwrap();
+    assert!(evm.transact(tx()).unwrap().result.is_success());
+    assert_eq!(evm.runtime_config().block_gas_limit, 60_000_000);
+}
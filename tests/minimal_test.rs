@@ -2,19 +2,13 @@
 
 use guillotine_rs::guillotine_mini::ffi;
 use guillotine_rs::guillotine_mini::types;
+use guillotine_rs::guillotine_mini::Hardfork;
 use revm::primitives::{address, Address, U256};
 
 #[test]
 fn test_ffi_create_only() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
 
     assert!(!handle.is_null(), "EVM handle should not be null");
     eprintln!("TEST: EVM handle created successfully");
@@ -28,14 +22,7 @@ fn test_ffi_create_only() {
 #[test]
 fn test_ffi_set_bytecode() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
 
     assert!(!handle.is_null());
     eprintln!("TEST: EVM handle created");
@@ -60,14 +47,7 @@ fn test_ffi_set_bytecode() {
 #[test]
 fn test_ffi_set_execution_context() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
     assert!(!handle.is_null());
     eprintln!("TEST: EVM handle created");
 
@@ -114,14 +94,7 @@ fn test_ffi_set_execution_context() {
 #[test]
 fn test_ffi_set_blockchain_context() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
     assert!(!handle.is_null());
     eprintln!("TEST: EVM handle created");
 
@@ -195,14 +168,7 @@ fn test_ffi_set_blockchain_context() {
 #[test]
 fn test_ffi_execute() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
     assert!(!handle.is_null());
     eprintln!("TEST: EVM handle created");
 
@@ -293,14 +259,7 @@ fn test_ffi_execute() {
 #[test]
 fn test_ffi_set_balance_before_execute() {
     eprintln!("TEST: Creating EVM handle...");
-    let hardfork = "Cancun";
-    let handle = unsafe {
-        ffi::evm_create(
-            hardfork.as_ptr(),
-            hardfork.len(),
-            0,
-        )
-    };
+    let handle = unsafe { ffi::evm_create_with_hardfork(Hardfork::Cancun.id(), 0) };
     assert!(!handle.is_null());
     eprintln!("TEST: EVM handle created");
 
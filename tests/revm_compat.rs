@@ -1,9 +1,10 @@
 //! REVM compatibility tests for guillotine-mini adapter
 //! Uses ethereum execution-specs fixtures to verify correctness
 
-use guillotine_rs::GuillotineMiniEvm;
+use guillotine_rs::{EvmAdapterError, GuillotineMiniEvm};
 use revm::{
     context::{Context, TxEnv},
+    context_interface::result::InvalidTransaction,
     database::{CacheDB, EmptyDB},
     primitives::{address, hardfork::SpecId, Bytes, TxKind, U256},
     state::{AccountInfo, Bytecode},
@@ -182,6 +183,85 @@ fn test_log_emission_simple() {
     }
 }
 
+#[test]
+fn test_large_log_data_not_truncated() {
+    // Bytecode: PUSH2 0x2000 PUSH1 0 LOG0 STOP => 8 KiB of zero log data
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("2000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("6120006000a000").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(200_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    let logs = result.result.logs();
+    assert_eq!(logs.len(), 1, "Should have 1 log entry");
+    assert_eq!(logs[0].data.data.len(), 0x2000, "Log data larger than 4 KiB should be returned intact");
+}
+
+#[test]
+fn test_log_topics_retrieved() {
+    // Bytecode: PUSH1 4 PUSH1 3 PUSH1 2 PUSH1 1 PUSH1 0 PUSH1 0 LOG4 STOP
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("2000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600460036002600160006000a400").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    let logs = result.result.logs();
+    assert_eq!(logs.len(), 1);
+    let topics: Vec<U256> = logs[0].topics().iter().map(|topic| U256::from_be_bytes(topic.0)).collect();
+    assert_eq!(topics, vec![U256::from(1), U256::from(2), U256::from(3), U256::from(4)]);
+}
+
 #[test]
 fn test_revert_maps_to_execution_result() {
     // Bytecode: PUSH1 0x00 PUSH1 0x00 REVERT => 0x60006000fd
@@ -225,6 +305,46 @@ fn test_revert_maps_to_execution_result() {
     }
 }
 
+#[test]
+fn test_revert_data_and_reason() {
+    // Panic(0x11): PUSH4 0x4e487b71 PUSH1 0xe0 SHL PUSH1 0 MSTORE PUSH1 0x11 PUSH1 4 MSTORE
+    // PUSH1 0x24 PUSH1 0 REVERT
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("5100000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("634e487b7160e01b600052601160045260246000fd").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let rs = evm.transact(tx).unwrap();
+    match rs.result {
+        revm::context_interface::result::ExecutionResult::Revert { ref output, .. } => {
+            assert_eq!(output.len(), 0x24);
+            assert_eq!(output[..4], guillotine_rs::guillotine_mini::revert::PANIC_SELECTOR);
+            assert_eq!(evm.last_report().revert_data.as_ref(), Some(output));
+        }
+        _ => panic!("Expected revert result"),
+    }
+    assert_eq!(evm.last_report().revert_reason().as_deref(), Some("panic: 0x11"));
+}
+
 #[test]
 fn test_storage_multi_slot_tracked() {
     // Bytecode: set slot1=2; set slot2=3; STOP
@@ -316,3 +436,1670 @@ fn test_gas_refund_sstore_restore() {
         panic!("Expected success result");
     }
 }
+
+#[test]
+fn test_gas_refund_capped_in_gas_used() {
+    // PUSH1 0x01 PUSH1 0x00 SSTORE PUSH1 0x00 PUSH1 0x00 SSTORE STOP
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("4000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("6001600055600060005500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(500_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+
+    // Spent: 21000 intrinsic + 4 * 3 (PUSH1) + 22100 (cold SSTORE 0->1) + 100 (warm SSTORE 1->0) = 43212
+    // Raw refund 19900 is capped at 43212 / 5 = 8642
+    match result.result {
+        revm::context_interface::result::ExecutionResult::Success { gas_used, gas_refunded, .. } => {
+            assert_eq!(gas_refunded, 8_642);
+            assert_eq!(gas_used, 43_212 - 8_642);
+        }
+        other => panic!("Expected success, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_gas_refund_restore_nonzero_original() {
+    // Slot 0 starts at 5: set it to 7, then restore it to 5
+    // PUSH1 0x07 PUSH1 0x00 SSTORE PUSH1 0x05 PUSH1 0x00 SSTORE STOP
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("4000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("6007600055600560005500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::ZERO, U256::from(5)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(500_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+
+    // The journal's original value is what the refund was computed against
+    let slot = &result.state[&contract_addr].storage[&U256::ZERO];
+    assert_eq!(slot.original_value, U256::from(5));
+    assert_eq!(slot.present_value, U256::from(5));
+
+    // Spent: 21000 intrinsic + 4 * 3 (PUSH1) + 5000 (cold SSTORE 5->7) + 100 (warm SSTORE 7->5) = 26112
+    // Restoring a non-zero original refunds 5000 - 2100 - 100 = 2800 (below the 26112 / 5 cap)
+    match result.result {
+        revm::context_interface::result::ExecutionResult::Success { gas_used, gas_refunded, .. } => {
+            assert_eq!(gas_refunded, 2_800);
+            assert_eq!(gas_used, 26_112 - 2_800);
+        }
+        other => panic!("Expected success, got {:?}", other),
+    }
+
+    // The cold SSTORE splits into 2100 cold access surcharge and 2900 storage cost
+    let breakdown = evm.last_report().gas_breakdown.expect("gas breakdown should be reported");
+    assert_eq!((breakdown.execution, breakdown.memory, breakdown.storage, breakdown.access), (12, 0, 3_000, 2_100));
+    assert_eq!(breakdown.refund, 2_800);
+    assert_eq!(breakdown.total(), 26_112 - 21_000);
+}
+
+#[test]
+fn test_create_with_value_transfers_to_new_contract() {
+    // Init code returns a single STOP byte as runtime code:
+    // PUSH1 0x00 PUSH1 0x00 MSTORE8 PUSH1 0x01 PUSH1 0x00 RETURN
+    // Hex: 600060005360016000f3
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Create)
+        .data(Bytes::from(hex::decode("600060005360016000f3").unwrap()))
+        .value(U256::from(1_000))
+        .gas_limit(200_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success(), "Creation should succeed");
+
+    let created = sender.create(0);
+    assert_eq!(result.result.created_address(), Some(created));
+
+    let acc = result.state.get(&created).expect("Created account should be present");
+    assert_eq!(acc.info.balance, U256::from(1_000), "Value should move to the new contract");
+    assert_eq!(acc.info.code.as_ref().map(|c| c.original_byte_slice().to_vec()), Some(vec![0x00]));
+}
+
+#[test]
+fn test_last_report_has_no_gas_anomalies() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600160020160005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.gas_used() <= 100_000);
+    assert!(!evm.last_report().has_anomalies(), "{:?}", evm.last_report());
+}
+
+#[test]
+fn test_slot_clearing_refund_has_no_gas_anomalies() {
+    // Slot 0 starts at 1 and is cleared: PUSH1 0x00 PUSH1 0x00 SSTORE STOP
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("1100000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600060005500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::ZERO, U256::from(1)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+
+    // Spent: 21000 intrinsic + 2 * 3 (PUSH1) + 5000 (cold SSTORE 1->0) = 26006
+    // Clearing refunds 4800, above a fifth of the execution gas but within 26006 / 5
+    match result.result {
+        revm::context_interface::result::ExecutionResult::Success { gas_used, gas_refunded, .. } => {
+            assert_eq!(gas_refunded, 4_800);
+            assert_eq!(gas_used, 26_006 - 4_800);
+        }
+        other => panic!("Expected success, got {:?}", other),
+    }
+    assert!(evm.last_report().gas_anomalies.is_empty(), "{:?}", evm.last_report().gas_anomalies);
+}
+
+#[test]
+fn test_gas_used_includes_intrinsic_gas() {
+    // Empty code: execution costs nothing, so gas used is exactly the intrinsic cost
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("6000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("00").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    // Calldata: one zero byte (4 gas) and one non-zero byte (16 gas)
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .data(Bytes::from(vec![0x00, 0x01]))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.gas_used(), 21_000 + 4 + 16);
+}
+
+#[test]
+fn test_gas_limit_below_intrinsic_is_invalid() {
+    let db = CacheDB::new(EmptyDB::default());
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b"))
+        .kind(TxKind::Call(address!("6000000000000000000000000000000000000000")))
+        .gas_limit(20_000)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        evm.transact(tx).unwrap_err(),
+        EvmAdapterError::Transaction(InvalidTransaction::CallGasCostMoreThanGasLimit {
+            initial_gas: 21_000,
+            gas_limit: 20_000
+        })
+    );
+}
+
+#[test]
+fn test_caller_nonce_and_gas_fee_in_state() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600160020160005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(10_000_000_u64), nonce: 7, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .nonce(7)
+        .gas_price(2)
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let gas_used = result.result.gas_used();
+    let acc = result.state.get(&sender).expect("Sender should be present in state");
+    assert_eq!(acc.info.nonce, 8, "Sender nonce should be incremented");
+    assert_eq!(acc.info.balance, U256::from(10_000_000_u64 - gas_used * 2), "Sender should pay for gas");
+}
+
+#[test]
+fn test_coinbase_receives_priority_fee() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600160020160005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(10_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let coinbase = address!("c000000000000000000000000000000000000000");
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+        .modify_block_chained(|block| {
+            block.beneficiary = coinbase;
+            block.basefee = 7;
+        })
+        .with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_price(10)
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    // Only the priority fee (10 - 7) reaches the coinbase; the base fee is burned
+    let gas_used = result.result.gas_used();
+    let acc = result.state.get(&coinbase).expect("Coinbase should be present in state");
+    assert_eq!(acc.info.balance, U256::from(gas_used * 3));
+}
+
+#[test]
+fn test_plain_value_transfer_to_eoa() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    let recipient = address!("b000000000000000000000000000000000000000");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(recipient))
+        .value(U256::from(500))
+        .gas_limit(21_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.gas_used(), 21_000);
+
+    let sender_acc = result.state.get(&sender).expect("Sender should be present in state");
+    assert_eq!(sender_acc.info.balance, U256::from(1_000_000_u64 - 500));
+    assert_eq!(sender_acc.info.nonce, 1);
+
+    let recipient_acc = result.state.get(&recipient).expect("Recipient should be present in state");
+    assert_eq!(recipient_acc.info.balance, U256::from(500));
+}
+
+#[test]
+fn test_top_level_precompile_call() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    let identity = address!("0000000000000000000000000000000000000004");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    // The identity precompile has no code but must still run and echo its input
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(identity))
+        .data(Bytes::from_static(b"echo"))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.output().unwrap().as_ref(), b"echo");
+    // Intrinsic 21000 plus 4 non-zero calldata bytes at 16 each, then 15 + 3 per word for identity
+    assert_eq!(result.result.gas_used(), 21_000 + 4 * 16 + 15 + 3);
+}
+
+#[test]
+fn test_invalid_jump_maps_to_halt() {
+    // Bytecode: PUSH1 0x00 JUMP => 0x600056 (0x00 is not a JUMPDEST)
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("7000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600056").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let rs = evm.transact(tx).unwrap();
+    match rs.result {
+        revm::context_interface::result::ExecutionResult::Halt { reason, gas_used } => {
+            assert_eq!(reason, revm::context_interface::result::HaltReason::InvalidJump);
+            assert_eq!(gas_used, 100_000, "Halts consume all gas");
+        }
+        other => panic!("Expected halt result, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_selfdestruct_marks_account_and_pays_beneficiary() {
+    // Deploy a contract that immediately self-destructs to the beneficiary 0xbe..ef
+    // (EIP-6780: only contracts created in the same transaction are destroyed).
+    // Init code: PUSH20 <beneficiary> SELFDESTRUCT
+    let beneficiary = address!("beefbeefbeefbeefbeefbeefbeefbeefbeefbeef");
+    let mut init_code = vec![0x73];
+    init_code.extend_from_slice(beneficiary.as_slice());
+    init_code.push(0xff);
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Create)
+        .data(Bytes::from(init_code))
+        .value(U256::from(1_000))
+        .gas_limit(200_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let destroyed = result.state.get(&sender.create(0)).expect("Destroyed account should be present");
+    assert!(destroyed.is_selfdestructed());
+    assert_eq!(destroyed.info.balance, U256::ZERO);
+
+    let paid = result.state.get(&beneficiary).expect("Beneficiary should be present");
+    assert_eq!(paid.info.balance, U256::from(1_000));
+
+    let selfdestructs = &evm.last_report().selfdestructs;
+    assert_eq!(selfdestructs.len(), 1);
+    assert_eq!(
+        (selfdestructs[0].address, selfdestructs[0].beneficiary, selfdestructs[0].balance),
+        (sender.create(0), beneficiary, U256::from(1_000))
+    );
+}
+
+#[test]
+fn test_nested_create_in_state() {
+    // Child init code deploys a single INVALID byte:
+    // PUSH1 0xfe PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+    let init_code = hex::decode("60fe60005360016000f3").unwrap();
+    // Factory: PUSH10 <init code> PUSH1 0 MSTORE PUSH1 10 PUSH1 22 PUSH1 0 CREATE
+    //          PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let mut factory_code = vec![0x69];
+    factory_code.extend_from_slice(&init_code);
+    factory_code.extend_from_slice(&hex::decode("600052600a60166000f060005260206000f3").unwrap());
+    let factory_code = Bytes::from(factory_code);
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    let factory = address!("c100000000000000000000000000000000000000");
+    db.insert_account_info(
+        factory,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 1,
+            code_hash: revm::primitives::keccak256(&factory_code),
+            code: Some(Bytecode::new_raw(factory_code)),
+        },
+    );
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(factory)).gas_limit(200_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let child_addr = factory.create(1);
+    assert_eq!(&result.result.output().unwrap()[12..], child_addr.as_slice());
+    let child = result.state.get(&child_addr).expect("Nested create should be in the state");
+    assert!(child.is_created());
+    assert_eq!(child.info.nonce, 1);
+    assert_eq!(child.info.code.as_ref().unwrap().original_byte_slice(), &[0xfe]);
+    assert_eq!(child.info.code_hash, revm::primitives::keccak256([0xfe]));
+
+    let created = &evm.last_report().created_contracts;
+    assert_eq!(created.len(), 1);
+    assert_eq!((created[0].address, created[0].deployer), (child_addr, factory));
+    assert_eq!(created[0].init_code_hash, revm::primitives::keccak256(&init_code));
+}
+
+#[test]
+fn test_create_tx_constructor_creates_contract() {
+    // Child init code deploys a single INVALID byte:
+    // PUSH1 0xfe PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+    let init_code = hex::decode("60fe60005360016000f3").unwrap();
+    // Factory constructor: PUSH10 <init code> PUSH1 0 MSTORE PUSH1 10 PUSH1 22 PUSH1 0 CREATE POP STOP
+    let mut factory_init = vec![0x69];
+    factory_init.extend_from_slice(&init_code);
+    factory_init.extend_from_slice(&hex::decode("600052600a60166000f05000").unwrap());
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Create).data(Bytes::from(factory_init)).gas_limit(200_000);
+    let result = evm.transact(tx.build().unwrap()).unwrap();
+    assert!(result.result.is_success());
+
+    // The factory starts with nonce 1 (EIP-161) and its CREATE bumps it to 2
+    let factory_addr = sender.create(0);
+    let factory = result.state.get(&factory_addr).expect("Created factory should be in the state");
+    assert!(factory.is_created());
+    assert_eq!(factory.info.nonce, 2);
+
+    let child = result.state.get(&factory_addr.create(1)).expect("Child should be derived from nonce 1");
+    assert!(child.is_created());
+    assert_eq!(child.info.nonce, 1);
+    assert_eq!(child.info.code.as_ref().unwrap().original_byte_slice(), &[0xfe]);
+}
+
+#[test]
+fn test_state_contains_full_account_info() {
+    // Bytecode: set slot1=2; STOP => 6002600155 00
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("3000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600260015500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::from(10),
+            nonce: 1,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code.clone())),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .value(U256::from(5))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let acc = result.state.get(&contract_addr).expect("Contract should be present");
+    assert_eq!(acc.info.balance, U256::from(15), "Contract should hold pre-state balance plus value");
+    assert_eq!(acc.info.nonce, 1, "Contract nonce should be preserved");
+    assert_eq!(acc.info.code_hash, revm::primitives::keccak256(&code), "Contract code should be preserved");
+}
+
+#[test]
+fn test_storage_original_value_from_pre_state() {
+    // Bytecode: set slot1=2; STOP => 600260015500
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("3000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600260015500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::from(1), U256::from(7)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let slot = result.state[&contract_addr].storage.get(&U256::from(1)).expect("Slot 1 should be present");
+    assert_eq!(slot.original_value, U256::from(7));
+    assert_eq!(slot.present_value, U256::from(2));
+    assert!(!slot.is_cold, "A written slot is warm at the end of execution");
+    let status = result.state[&contract_addr].status;
+    assert!(!status.contains(revm::state::AccountStatus::Cold), "The called contract is warm");
+}
+
+#[test]
+fn test_transact_commit_applies_state_to_database() {
+    use revm::{context_interface::JournalTr, database_interface::Database};
+
+    // Counter: slot0 = slot0 + 1
+    // PUSH1 0x00 SLOAD PUSH1 0x01 ADD PUSH1 0x00 SSTORE STOP => 60005460010160005500
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("8000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("60005460010160005500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    for nonce in 0..2 {
+        let tx = TxEnv::builder()
+            .caller(sender)
+            .kind(TxKind::Call(contract_addr))
+            .nonce(nonce)
+            .gas_limit(100_000)
+            .build()
+            .unwrap();
+        assert!(evm.transact_commit(tx).unwrap().is_success());
+    }
+
+    let db = evm.ctx.journaled_state.db_mut();
+    assert_eq!(db.storage(contract_addr, U256::ZERO).unwrap(), U256::from(2), "Counter should be incremented twice");
+    assert_eq!(db.basic(sender).unwrap().unwrap().nonce, 2, "Sender nonce should be committed");
+}
+
+#[test]
+fn test_transact_with_observer_reports_steps() {
+    // PUSH1 0x01 PUSH1 0x02 ADD STOP => 600160020100
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("9000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600160020100").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+
+    let mut steps = Vec::new();
+    let result = evm.transact_with_observer(tx, |step| steps.push(step)).unwrap();
+    assert!(result.result.is_success());
+
+    let trace: Vec<(usize, u8, usize)> = steps.iter().map(|s| (s.pc, s.opcode, s.stack_len)).collect();
+    assert_eq!(trace, vec![(0, 0x60, 0), (2, 0x60, 1), (4, 0x01, 2), (5, 0x00, 1)]);
+    assert!(steps.iter().all(|s| s.depth == 0));
+    assert!(steps.windows(2).all(|w| w[1].gas_remaining < w[0].gas_remaining), "Gas should decrease each step");
+}
+
+#[test]
+fn test_create_access_list_records_accessed_state() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("9100000000000000000000000000000000000000");
+    let other = address!("9200000000000000000000000000000000000000");
+    // PUSH20 other BALANCE POP PUSH1 0x14 SLOAD POP
+    let code = Bytes::from(hex::decode("739200000000000000000000000000000000000000315060145450").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let generated = evm.create_access_list(tx).unwrap();
+
+    let items: Vec<_> = generated.access_list.0.iter().map(|item| (item.address, item.storage_keys.clone())).collect();
+    assert_eq!(
+        items,
+        vec![(other, vec![]), (contract_addr, vec![revm::primitives::B256::with_last_byte(0x14)])],
+        "Sender should be omitted and the recipient only listed for its storage"
+    );
+    assert!(generated.result.is_success());
+    assert_eq!(generated.gas_used, generated.result.gas_used());
+}
+
+#[test]
+fn test_invalid_transactions_are_rejected_before_execution() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 3, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let base = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(address!("6000000000000000000000000000000000000000")))
+        .nonce(3)
+        .gas_limit(21_000)
+        .build()
+        .unwrap();
+
+    let stale_nonce = TxEnv { nonce: 2, ..base.clone() };
+    assert_eq!(
+        evm.transact(stale_nonce).unwrap_err(),
+        EvmAdapterError::Transaction(InvalidTransaction::NonceTooLow { tx: 2, state: 3 })
+    );
+
+    let wrong_chain = TxEnv { chain_id: Some(1337), ..base.clone() };
+    assert_eq!(evm.transact(wrong_chain).unwrap_err(), EvmAdapterError::Transaction(InvalidTransaction::InvalidChainId));
+
+    let overdrawn = TxEnv { value: U256::from(1_000_000_u64), gas_price: 1, ..base.clone() };
+    assert_eq!(
+        evm.transact(overdrawn).unwrap_err(),
+        EvmAdapterError::Transaction(InvalidTransaction::LackOfFundForMaxFee {
+            fee: Box::new(U256::from(1_021_000_u64)),
+            balance: Box::new(U256::from(1_000_000_u64)),
+        })
+    );
+
+    assert!(evm.transact(base).unwrap().result.is_success());
+}
+
+#[test]
+fn test_eip1559_effective_gas_price() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    // GASPRICE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("3a60005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(10_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let coinbase = address!("c000000000000000000000000000000000000000");
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+        .modify_block_chained(|block| {
+            block.beneficiary = coinbase;
+            block.basefee = 7;
+        })
+        .with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    // Max fee 50, tip 2 => effective price min(50, 7 + 2) = 9
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_price(50)
+        .gas_priority_fee(Some(2))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(9), "GASPRICE should be the effective price");
+    assert_eq!(evm.last_report().effective_gas_price, 9);
+
+    let gas_used = result.result.gas_used();
+    assert_eq!(result.state[&sender].info.balance, U256::from(10_000_000 - gas_used * 9));
+    assert_eq!(result.state[&coinbase].info.balance, U256::from(gas_used * 2));
+
+    // Legacy transactions pay (and GASPRICE reports) their gas price as is
+    let legacy =
+        TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_price(11).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(legacy).unwrap();
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(11));
+    assert_eq!(evm.last_report().effective_gas_price, 11);
+}
+
+#[test]
+fn test_discovery_mode_syncs_call_tree_pre_state() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let router = address!("a100000000000000000000000000000000000000");
+    let target = address!("a200000000000000000000000000000000000000");
+
+    // Target returns storage slot 0x50: PUSH1 0x50 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let target_code = Bytes::from(hex::decode("60505460005260206000f3").unwrap());
+    db.insert_account_info(
+        target,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&target_code),
+            code: Some(Bytecode::new_raw(target_code)),
+        },
+    );
+    db.insert_account_storage(target, U256::from(0x50), U256::from(0xbeef)).unwrap();
+
+    // Router calls target and returns its output:
+    // PUSH1 32 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH20 target GAS CALL POP PUSH1 32 PUSH1 0 RETURN
+    let router_code =
+        Bytes::from(hex::decode("6020600060006000600073a2000000000000000000000000000000000000005af15060206000f3").unwrap());
+    db.insert_account_info(
+        router,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&router_code),
+            code: Some(Bytecode::new_raw(router_code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    evm.set_discovery_mode(true);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(router)).gas_limit(200_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(
+        U256::from_be_slice(result.result.output().unwrap()),
+        U256::from(0xbeef),
+        "Nested call should see the target's code and high storage slot"
+    );
+
+    use guillotine_rs::guillotine_mini::CallKind;
+    let calls = &evm.last_report().calls;
+    assert_eq!(calls.len(), 1, "{calls:?}");
+    assert_eq!((calls[0].kind, calls[0].depth, calls[0].from, calls[0].to), (CallKind::Call, 1, router, target));
+    assert!(calls[0].is_success() && calls[0].value.is_zero());
+    assert_eq!(U256::from_be_slice(&calls[0].output), U256::from(0xbeef));
+}
+
+#[test]
+fn test_transient_storage_in_report() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b100000000000000000000000000000000000000");
+    // PUSH1 0x42 PUSH1 0x01 TSTORE PUSH1 0x00 PUSH1 0x02 TSTORE STOP
+    let code = Bytes::from(hex::decode("604260015d600060025d00").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    assert!(evm.transact(tx).unwrap().result.is_success());
+
+    let transient = evm.last_report().transient_storage.as_ref().expect("Cancun should report transient storage");
+    assert_eq!(transient.len(), 1, "Zero-valued entries should be omitted");
+    assert_eq!(transient.get(&(contract_addr, U256::from(1))), Some(&U256::from(0x42)));
+}
+
+#[test]
+fn test_blob_gas_reported() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b200000000000000000000000000000000000000");
+    // PUSH1 0 BLOBHASH PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("60004960005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+        .modify_block_chained(|block| {
+            block.blob_excess_gas_and_price = Some(revm::context_interface::block::BlobExcessGasAndPrice {
+                excess_blob_gas: 0x40000,
+                blob_gasprice: 1,
+            });
+        })
+        .with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let blob_hash = revm::primitives::b256!("0100000000000000000000000000000000000000000000000000000000000001");
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .blob_hashes(vec![blob_hash, blob_hash])
+        .max_fee_per_blob_gas(1)
+        .build()
+        .unwrap();
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.output().unwrap().as_ref(), blob_hash.as_slice(), "BLOBHASH should see the tx hashes");
+    assert_eq!(evm.last_report().blob_gas_used, 2 * 131_072);
+    assert_eq!(evm.last_report().excess_blob_gas, Some(0x40000));
+
+    let unversioned = revm::primitives::b256!("0200000000000000000000000000000000000000000000000000000000000001");
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .blob_hashes(vec![unversioned])
+        .max_fee_per_blob_gas(1)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        evm.transact(tx),
+        Err(EvmAdapterError::Transaction(InvalidTransaction::BlobVersionNotSupported))
+    ));
+}
+
+#[test]
+fn test_blob_fee_deducted_from_sender() {
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    let coinbase = address!("c000000000000000000000000000000000000000");
+    let contract_addr = address!("b220000000000000000000000000000000000000");
+    let code = Bytes::from_static(&[0x00]);
+    let blob_hash = revm::primitives::b256!("0100000000000000000000000000000000000000000000000000000000000001");
+
+    // Once through the interpreter (STOP contract) and once as a plain transfer (empty account)
+    for target in [contract_addr, address!("b230000000000000000000000000000000000000")] {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract_addr,
+            AccountInfo {
+                balance: U256::ZERO,
+                nonce: 0,
+                code_hash: revm::primitives::keccak256(&code),
+                code: Some(Bytecode::new_raw(code.clone())),
+            },
+        );
+        db.insert_account_info(
+            sender,
+            AccountInfo {
+                balance: U256::from(2_000_000_u64),
+                nonce: 0,
+                code_hash: revm::primitives::KECCAK_EMPTY,
+                code: None,
+            },
+        );
+
+        let ctx = Context::mainnet()
+            .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+            .modify_block_chained(|block| {
+                block.beneficiary = coinbase;
+                block.blob_excess_gas_and_price = Some(revm::context_interface::block::BlobExcessGasAndPrice {
+                    excess_blob_gas: 0,
+                    blob_gasprice: 2,
+                });
+            })
+            .with_db(db);
+        let mut evm = GuillotineMiniEvm::new(ctx);
+
+        let tx = TxEnv::builder()
+            .caller(sender)
+            .kind(TxKind::Call(target))
+            .gas_limit(100_000)
+            .gas_price(1)
+            .blob_hashes(vec![blob_hash, blob_hash])
+            .max_fee_per_blob_gas(2)
+            .build()
+            .unwrap();
+
+        let result = evm.transact(tx).unwrap();
+        assert!(result.result.is_success());
+        let gas_used = result.result.gas_used();
+        // Two blobs of 131072 blob gas at 2 each, burned rather than paid to the coinbase
+        let blob_fee = 2 * 131_072 * 2;
+        let sender_acc = result.state.get(&sender).expect("Sender should be present in state");
+        assert_eq!(sender_acc.info.balance, U256::from(2_000_000 - gas_used - blob_fee));
+        let coinbase_acc = result.state.get(&coinbase).expect("Coinbase should be present in state");
+        assert_eq!(coinbase_acc.info.balance, U256::from(gas_used));
+    }
+}
+
+#[test]
+fn test_blob_base_fee_follows_fork_update_fraction() {
+    let contract_addr = address!("b210000000000000000000000000000000000000");
+    // BLOBBASEFEE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("4a60005260206000f3").unwrap());
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+
+    let mut fees = Vec::new();
+    for spec in [SpecId::CANCUN, SpecId::PRAGUE] {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract_addr,
+            AccountInfo {
+                balance: U256::ZERO,
+                nonce: 0,
+                code_hash: revm::primitives::keccak256(&code),
+                code: Some(Bytecode::new_raw(code.clone())),
+            },
+        );
+
+        let ctx = Context::mainnet()
+            .modify_cfg_chained(|cfg| cfg.spec = spec)
+            .modify_block_chained(|block| {
+                block.set_blob_excess_gas_and_price(
+                    10_000_000,
+                    guillotine_rs::guillotine_mini::validation::blob_base_fee_update_fraction(spec),
+                );
+            })
+            .with_db(db);
+        let expected = U256::from(ctx.block.blob_excess_gas_and_price.unwrap().blob_gasprice);
+        let mut evm = GuillotineMiniEvm::new(ctx);
+
+        let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+        let result = evm.transact(tx).unwrap();
+        assert!(result.result.is_success());
+        let fee = U256::from_be_slice(result.result.output().unwrap());
+        assert_eq!(fee, expected, "BLOBBASEFEE under {spec:?}");
+        fees.push(fee);
+    }
+    // EIP-7691 raised the update fraction, so the same excess gas prices blobs lower in Prague
+    assert!(fees[1] < fees[0]);
+}
+
+#[test]
+fn test_sync_all_storage_from_cache_db() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b300000000000000000000000000000000000000");
+    // PUSH1 0x50 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("60505460005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::from(0x50), U256::from(0xbeef)).unwrap();
+    db.insert_account_storage(contract_addr, U256::from(0x51), U256::ZERO).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    assert_eq!(evm.sync_all_storage(contract_addr).unwrap(), 1, "Only non-zero slots should be synced");
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(0xbeef));
+}
+
+#[test]
+fn test_state_includes_accessed_accounts_without_storage_changes() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("9100000000000000000000000000000000000000");
+    let other = address!("9200000000000000000000000000000000000000");
+    // PUSH20 other BALANCE POP
+    let code = Bytes::from(hex::decode("7392000000000000000000000000000000000000003150").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_info(
+        other,
+        AccountInfo { balance: U256::from(77), nonce: 3, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    evm.set_discovery_mode(true);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let acc = result.state.get(&other).expect("Accessed account should be in state");
+    assert_eq!(acc.info.balance, U256::from(77));
+    assert_eq!(acc.info.nonce, 3);
+    assert!(acc.storage.is_empty());
+}
+
+#[test]
+fn test_shared_ref_database() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b400000000000000000000000000000000000000");
+    // PUSH1 0x01 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("60015460005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::from(1), U256::from(0x2a)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    // Two EVMs reading the same immutable database
+    let shared = std::sync::Arc::new(db);
+    for _ in 0..2 {
+        let ctx = Context::mainnet()
+            .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+            .with_ref_db(std::sync::Arc::clone(&shared));
+        let mut evm = GuillotineMiniEvm::new(ctx);
+
+        let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+        let result = evm.transact(tx).unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(0x2a));
+    }
+}
+
+#[test]
+fn test_extcodehash_matches_synced_code_hash() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b500000000000000000000000000000000000000");
+    // ADDRESS EXTCODEHASH PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("303f60005260206000f3").unwrap());
+    let code_hash = revm::primitives::keccak256(&code);
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo { balance: U256::ZERO, nonce: 0, code_hash, code: Some(Bytecode::new_raw(code)) },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.output().unwrap().as_ref(), code_hash.as_slice());
+}
+
+#[test]
+fn test_code_hash_mismatch_rejected_when_verifying() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b600000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("600160005260206000f3").unwrap());
+    let wrong_hash = revm::primitives::keccak256(b"not the code");
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo { balance: U256::ZERO, nonce: 0, code_hash: wrong_hash, code: Some(Bytecode::new_raw(code)) },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    evm.set_verify_code_hashes(true);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    match evm.transact(tx) {
+        Err(EvmAdapterError::CodeHashMismatch { address, expected, .. }) => {
+            assert_eq!(address, contract_addr);
+            assert_eq!(expected, wrong_hash);
+        }
+        other => panic!("expected CodeHashMismatch, got {:?}", other.map(|r| r.result)),
+    }
+}
+
+#[test]
+fn test_blockhash_from_database() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b700000000000000000000000000000000000000");
+    // PUSH1 0x09 BLOCKHASH PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("60094060005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    let block_hash = revm::primitives::B256::repeat_byte(0x99);
+    db.cache.block_hashes.insert(U256::from(9), block_hash);
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN)
+        .modify_block_chained(|block| block.number = U256::from(10))
+        .with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.output().unwrap().as_ref(), block_hash.as_slice());
+}
+
+#[test]
+fn test_access_list_sync_strategy_syncs_high_slot() {
+    use guillotine_rs::guillotine_mini::AccessListSlots;
+    use revm::context_interface::transaction::{AccessList, AccessListItem};
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b800000000000000000000000000000000000000");
+    // PUSH2 0x1234 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("6112345460005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::from(0x1234), U256::from(0x2a)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    evm.set_sync_strategy(AccessListSlots);
+
+    let access_list = AccessList(vec![AccessListItem {
+        address: contract_addr,
+        storage_keys: vec![U256::from(0x1234).into()],
+    }]);
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .access_list(access_list)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(0x2a));
+}
+
+#[test]
+fn test_bytecode_scan_syncs_fixed_high_slot() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("b900000000000000000000000000000000000000");
+    // PUSH2 0x1234 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("6112345460005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    db.insert_account_storage(contract_addr, U256::from(0x1234), U256::from(0x2a)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    assert!(evm.scan_bytecode());
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx.clone()).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(0x2a));
+
+    evm.set_scan_bytecode(false);
+    evm.set_host_callbacks(false);
+    let result = evm.transact(tx).unwrap();
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::ZERO, "Slot 0x1234 is not synced");
+}
+
+#[test]
+fn test_host_callbacks_load_unsynced_state() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("ba00000000000000000000000000000000000000");
+    let other = address!("bb00000000000000000000000000000000000000");
+    let other_code = Bytes::from_static(&[0x00]);
+    // PUSH1 0 CALLDATALOAD SLOAD PUSH1 0 MSTORE
+    // PUSH20 other BALANCE PUSH1 32 MSTORE PUSH20 other EXTCODEHASH PUSH1 64 MSTORE
+    // PUSH1 96 PUSH1 0 RETURN
+    let mut code = hex::decode("60003554600052").unwrap();
+    for (opcode, offset) in [(0x31, 0x20), (0x3f, 0x40)] {
+        code.push(0x73);
+        code.extend_from_slice(other.as_slice());
+        code.extend_from_slice(&[opcode, 0x60, offset, 0x52]);
+    }
+    code.extend_from_slice(&hex::decode("60606000f3").unwrap());
+    let code = Bytes::from(code);
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    // Slot computed at runtime: invisible to the bytecode scan and the sync strategy
+    let slot = U256::from(0xdead_beef_u64);
+    db.insert_account_storage(contract_addr, slot, U256::from(0x2a)).unwrap();
+    db.insert_account_info(
+        other,
+        AccountInfo {
+            balance: U256::from(77),
+            nonce: 1,
+            code_hash: revm::primitives::keccak256(&other_code),
+            code: Some(Bytecode::new_raw(other_code.clone())),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    assert!(evm.host_callbacks());
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .data(Bytes::from(slot.to_be_bytes::<32>().to_vec()))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    let output = result.result.output().unwrap();
+    assert_eq!(U256::from_be_slice(&output[..32]), U256::from(0x2a));
+    assert_eq!(U256::from_be_slice(&output[32..64]), U256::from(77));
+    assert_eq!(&output[64..], revm::primitives::keccak256(&other_code).as_slice());
+}
+
+#[test]
+fn test_state_diff_from_transact() {
+    use guillotine_rs::guillotine_mini::{AccountChange, ValueChange};
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("ba00000000000000000000000000000000000000");
+    // PUSH1 0x2a PUSH1 0x01 SSTORE STOP
+    let code = Bytes::from(hex::decode("602a60015500").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+
+    let diff = evm.state_diff(&result.state).unwrap();
+    assert_eq!(diff.accounts[&sender].change, AccountChange::Updated);
+    assert_eq!(diff.accounts[&sender].nonce, Some(ValueChange { from: 0, to: 1 }));
+    let contract_diff = &diff.accounts[&contract_addr];
+    assert_eq!(contract_diff.change, AccountChange::Updated);
+    assert_eq!(contract_diff.storage[&U256::from(1)], ValueChange { from: U256::ZERO, to: U256::from(0x2a) });
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&diff).unwrap();
+        let decoded: guillotine_rs::guillotine_mini::StateDiff = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, diff);
+    }
+}
+
+#[test]
+fn test_eip7702_delegated_account_runs_delegate_code() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let delegate_addr = address!("bb00000000000000000000000000000000000000");
+    // ADDRESS PUSH1 0x01 SLOAD ADD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = Bytes::from(hex::decode("306001540160005260206000f3").unwrap());
+    db.insert_account_info(
+        delegate_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    // EOA delegating to the contract above, with its own storage
+    let eoa = address!("bc00000000000000000000000000000000000000");
+    let designator = Bytecode::new_eip7702(delegate_addr);
+    db.insert_account_info(
+        eoa,
+        AccountInfo { balance: U256::ZERO, nonce: 1, code_hash: designator.hash_slow(), code: Some(designator) },
+    );
+    db.insert_account_storage(eoa, U256::from(1), U256::from(5)).unwrap();
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::PRAGUE).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(eoa)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    // Delegate code runs with the EOA's address and storage
+    let expected = U256::from_be_slice(eoa.as_slice()) + U256::from(5);
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), expected);
+}
+
+#[test]
+fn test_precompile_call_is_warm() {
+    let mut db = CacheDB::new(EmptyDB::default());
+    let contract_addr = address!("bd00000000000000000000000000000000000000");
+    // GAS PUSH1 0 DUP1 DUP1 DUP1 PUSH1 0x04 GAS STATICCALL POP GAS SWAP1 SUB PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    // Measures the gas consumed by a STATICCALL to the identity precompile (0x04)
+    let code = Bytes::from(hex::decode("5a600080808060045afa505a900360005260206000f3").unwrap());
+
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo { balance: U256::from(1_000_000_u64), nonce: 0, code_hash: revm::primitives::KECCAK_EMPTY, code: None },
+    );
+
+    let ctx = Context::mainnet().modify_cfg_chained(|cfg| cfg.spec = SpecId::CANCUN).with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    // A cold call would cost at least 2600 gas; a warm precompile call costs 100 plus the
+    // precompile's own 15 gas and the surrounding pushes
+    let spent = U256::from_be_slice(result.result.output().unwrap());
+    assert!(spent < U256::from(2600), "precompile call should be warm, spent {spent}");
+}
@@ -2,12 +2,14 @@
 
 use guillotine_rs::GuillotineMiniEvm;
 use revm::{
-    context::Context,
+    context::{BlockEnv, CfgEnv, Context, Journal, TxEnv},
+    context_interface::LocalContextTr,
     database::{CacheDB, EmptyDB},
     primitives::{address, hardfork::SpecId, Address, Bytes, TxKind, U256},
     state::{AccountInfo, Bytecode},
     MainContext,
 };
+use std::{cell::RefCell, rc::Rc};
 
 #[test]
 fn test_wrapper_create() {
@@ -23,6 +25,12 @@ fn test_wrapper_create() {
     eprintln!("WRAPPER TEST: GuillotineMiniEvm dropped");
 }
 
+#[test]
+fn test_wrapper_abi_version_matches() {
+    use guillotine_rs::guillotine_mini::ffi;
+    assert_eq!(ffi::check_abi_version(), Ok(()), "Linked libguillotine_mini should match ABI {}", ffi::ABI_VERSION);
+}
+
 #[test]
 fn test_wrapper_with_database() {
     eprintln!("WRAPPER TEST: Creating database...");
@@ -68,8 +76,6 @@ fn test_wrapper_with_database() {
 
 #[test]
 fn test_wrapper_transact_simple() {
-    use revm::context::TxEnv;
-
     eprintln!("WRAPPER TEST: Creating database...");
     let mut db = CacheDB::new(EmptyDB::default());
 
@@ -126,3 +132,798 @@ fn test_wrapper_transact_simple() {
         eprintln!("WRAPPER TEST: Error: {:?}", result.err());
     }
 }
+
+type CacheCtx = Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDB>>;
+type CacheEvm = GuillotineMiniEvm<CacheCtx>;
+
+/// Contract that increments storage slot 0x14 (outside the pre-synced range) and returns it:
+/// PUSH1 0x14 SLOAD PUSH1 0x01 ADD DUP1 PUSH1 0x14 SSTORE PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+fn counter_ctx() -> (CacheCtx, Address, Address) {
+    let mut db = CacheDB::new(EmptyDB::default());
+
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("6014546001018060145560005260206000f3").unwrap());
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    db.insert_account_info(
+        sender,
+        AccountInfo {
+            balance: U256::from(1_000_000_u64),
+            nonce: 0,
+            code_hash: revm::primitives::KECCAK_EMPTY,
+            code: None,
+        },
+    );
+
+    // Repeated calls reuse nonce 0 regardless of how much sender state carries over
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| {
+            cfg.spec = SpecId::CANCUN;
+            cfg.disable_nonce_check = true;
+        })
+        .with_db(db);
+    (ctx, sender, contract_addr)
+}
+
+fn counter_evm() -> (CacheEvm, Address, Address) {
+    let (ctx, sender, contract_addr) = counter_ctx();
+    (GuillotineMiniEvm::new(ctx), sender, contract_addr)
+}
+
+fn call_counter(evm: &mut CacheEvm, sender: Address, contract_addr: Address) -> u8 {
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    result.result.output().unwrap()[31]
+}
+
+#[test]
+fn test_wrapper_fresh_state_per_transact() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    assert!(!evm.persist_state());
+
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1, "Uncommitted writes should not carry over");
+}
+
+#[test]
+fn test_wrapper_persist_state_carries_over() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    evm.set_persist_state(true);
+
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 2, "Writes should carry over in persist mode");
+
+    evm.reset_state().unwrap();
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1, "reset_state should discard carried-over state");
+}
+
+#[test]
+fn test_wrapper_transact_block_cumulative_state() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let txs: Vec<TxEnv> = (0..3)
+        .map(|nonce| {
+            TxEnv::builder()
+                .caller(sender)
+                .kind(TxKind::Call(contract_addr))
+                .gas_limit(100_000)
+                .nonce(nonce)
+                .build()
+                .unwrap()
+        })
+        .collect();
+
+    let results = evm.transact_block(txs).unwrap();
+    assert_eq!(results.len(), 3);
+    for (i, result) in results.iter().enumerate() {
+        assert!(result.result.is_success());
+        assert_eq!(result.result.output().unwrap()[31], i as u8 + 1, "Each tx should see the previous writes");
+        assert_eq!(result.state[&sender].info.nonce, i as u64 + 1, "Caller nonce should accumulate");
+    }
+
+    // The block does not change the transact mode
+    assert!(!evm.persist_state());
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+}
+
+#[test]
+fn test_wrapper_transact_block_commit_feeds_forward() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let txs: Vec<TxEnv> = (0..3)
+        .map(|nonce| {
+            TxEnv::builder()
+                .caller(sender)
+                .kind(TxKind::Call(contract_addr))
+                .gas_limit(100_000)
+                .nonce(nonce)
+                .build()
+                .unwrap()
+        })
+        .collect();
+
+    let results = evm.transact_block_commit(txs).unwrap();
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result.result.output().unwrap()[31], i as u8 + 1);
+        let slot = &result.state[&contract_addr].storage[&U256::from(0x14)];
+        assert_eq!(slot.original_value, U256::from(i), "Original value should be the pre-transaction value");
+        assert_eq!(slot.present_value, U256::from(i + 1));
+    }
+
+    // Committed to the database, so a fresh transact continues from it
+    assert!(!evm.persist_state());
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 4);
+}
+
+#[test]
+fn test_wrapper_estimate_gas_is_minimal() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = |gas_limit: u64| {
+        TxEnv::builder()
+            .caller(sender)
+            .kind(TxKind::Call(contract_addr))
+            .gas_limit(gas_limit)
+            .build()
+            .unwrap()
+    };
+
+    let estimate = evm.estimate_gas(tx(1_000_000)).unwrap().expect("Counter call should succeed");
+    assert!(estimate > 21_000 && estimate < 1_000_000);
+
+    assert!(evm.transact(tx(estimate)).unwrap().result.is_success());
+    assert!(!evm.transact(tx(estimate - 1)).unwrap().result.is_success());
+}
+
+#[test]
+fn test_wrapper_estimate_gas_reports_failure_at_cap() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(25_000)
+        .build()
+        .unwrap();
+
+    let failure = evm.estimate_gas(tx).unwrap().expect_err("Cold SSTORE cannot fit in the cap");
+    assert!(!failure.is_success());
+}
+
+#[test]
+fn test_wrapper_call_is_read_only() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    evm.set_persist_state(true);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+
+    // Unfunded caller with an arbitrary nonce
+    let tx = TxEnv::builder()
+        .caller(address!("00000000000000000000000000000000000000ca"))
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .gas_price(1_000_000_000)
+        .value(U256::from(5))
+        .nonce(42)
+        .build()
+        .unwrap();
+    let result = evm.call(tx).unwrap();
+    assert!(result.is_success());
+    assert_eq!(result.output().unwrap()[31], 1, "call should run against the database pre-state");
+
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 2, "call should not disturb carried-over state");
+}
+
+#[test]
+fn test_wrapper_code_cache_reused_across_transactions() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    evm.enable_code_cache().unwrap();
+
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    // The counter contract is registered once and survives the per-transaction reset
+    assert_eq!(evm.code_cache().unwrap().len(), 1);
+
+    evm.set_persist_state(true);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 2);
+}
+
+#[test]
+fn test_wrapper_bridge_stats() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    assert!(evm.bridge_stats().is_none(), "Stats are opt-in");
+
+    evm.set_bridge_stats(true);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    let stats = *evm.bridge_stats().unwrap();
+    assert!(stats.accounts_synced >= 2, "sender and contract should be synced: {stats:?}");
+    assert!(stats.slots_synced >= 1, "counter slot should be synced: {stats:?}");
+    assert!(stats.ffi_calls > stats.accounts_synced);
+    assert!(stats.bytes_copied > 0, "contract code should be copied");
+
+    evm.set_bridge_stats(false);
+    assert!(evm.bridge_stats().is_none());
+}
+
+#[test]
+fn test_wrapper_stepper_matches_transact() {
+    use guillotine_rs::guillotine_mini::StepResult;
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let expected = evm.transact(tx.clone()).unwrap();
+
+    let mut stepper = evm.stepper(tx.clone()).unwrap();
+    let mut steps = 0;
+    while stepper.step().unwrap() == StepResult::Continue {
+        steps += 1;
+    }
+    // PUSH1 SLOAD PUSH1 ADD DUP1 PUSH1 SSTORE PUSH1 MSTORE PUSH1 PUSH1, then RETURN finishes
+    assert_eq!(steps, 11);
+    assert!(stepper.is_finished());
+    assert_eq!(stepper.step().unwrap(), StepResult::Finished);
+    let stepped = stepper.finish().unwrap();
+    assert_eq!(stepped.result, expected.result);
+    assert_eq!(stepped.state, expected.state);
+
+    // An abandoned stepper leaves no partial execution behind, even in persist mode
+    evm.set_persist_state(true);
+    let mut stepper = evm.stepper(tx.clone()).unwrap();
+    assert_eq!(stepper.step().unwrap(), StepResult::Continue);
+    drop(stepper);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+}
+
+#[test]
+fn test_wrapper_stepper_memory() {
+    use guillotine_rs::guillotine_mini::StepResult;
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let mut stepper = evm.stepper(tx).unwrap();
+    assert!(stepper.memory().is_empty());
+
+    // PUSH1 SLOAD PUSH1 ADD DUP1 PUSH1 SSTORE PUSH1 MSTORE stores the new counter at offset 0
+    for _ in 0..9 {
+        assert_eq!(stepper.step().unwrap(), StepResult::Continue);
+    }
+    let memory = stepper.memory();
+    assert_eq!(memory.len(), 32);
+    assert_eq!(memory.word(0), U256::from(1));
+    assert_eq!(memory.word(16), U256::from(1) << 128, "Past the end reads as zero");
+    assert_eq!(memory.read(31, 8), vec![1], "Reads are truncated at the end of memory");
+    assert_eq!(memory.to_vec().len(), 32);
+    stepper.finish().unwrap();
+}
+
+#[test]
+fn test_wrapper_cancellation() {
+    use guillotine_rs::guillotine_mini::{CancellationToken, EvmAdapterError};
+
+    // JUMPDEST PUSH1 0 JUMP: loops until the gas runs out, which takes hours at this limit
+    let looper = address!("1100000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("5b600056").unwrap());
+    let (ctx, sender, contract_addr) = counter_ctx();
+    let ctx = ctx
+        .modify_block_chained(|block| block.gas_limit = u64::MAX)
+        .modify_db_chained(|db| {
+            db.insert_account_info(
+                looper,
+                AccountInfo {
+                    code_hash: revm::primitives::keccak256(&code),
+                    code: Some(Bytecode::new_raw(code)),
+                    ..Default::default()
+                },
+            )
+        });
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(looper)).gas_limit(1 << 60).build().unwrap();
+    let token = evm.cancellation_token();
+    let result = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            token.cancel();
+        });
+        evm.transact(tx.clone())
+    });
+    assert!(matches!(result, Err(EvmAdapterError::Cancelled)));
+    assert!(matches!(evm.transact(tx), Err(EvmAdapterError::Cancelled)), "Cancellation is permanent");
+
+    evm.set_cancellation_token(CancellationToken::new());
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+}
+
+#[test]
+fn test_wrapper_builder() {
+    use guillotine_rs::guillotine_mini::{AccessListSlots, Hardfork};
+
+    let (ctx, sender, contract_addr) = counter_ctx();
+    let mut evm = GuillotineMiniEvm::builder()
+        .ctx(ctx)
+        .hardfork(Hardfork::from_spec(SpecId::SHANGHAI))
+        .sync(AccessListSlots)
+        .tracing(true)
+        .persist_state(true)
+        .code_cache(true)
+        .build()
+        .unwrap();
+    assert_eq!(evm.hardfork(), Hardfork::from_spec(SpecId::SHANGHAI));
+    assert!(evm.native_tracing() && evm.persist_state() && evm.code_cache().is_some());
+
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert!(!evm.last_report().struct_logs.is_empty(), "Native tracing enabled by the builder");
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 2, "State persisted between transactions");
+}
+
+#[test]
+fn test_wrapper_simulation_checks_disabled() {
+    use guillotine_rs::guillotine_mini::EvmAdapterError;
+    use revm::context_interface::result::InvalidTransaction;
+
+    let (mut ctx, _, contract_addr) = counter_ctx();
+    ctx.cfg.disable_nonce_check = false;
+    // Unfunded sender with a nonce it never used
+    let sender = address!("7700000000000000000000000000000000000077");
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .kind(TxKind::Call(contract_addr))
+        .gas_limit(100_000)
+        .gas_price(10)
+        .nonce(3)
+        .build()
+        .unwrap();
+
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    assert!(matches!(
+        evm.transact(tx.clone()),
+        Err(EvmAdapterError::Transaction(InvalidTransaction::NonceTooHigh { .. }))
+    ));
+    evm.set_disable_nonce_check(true);
+    assert!(matches!(
+        evm.transact(tx.clone()),
+        Err(EvmAdapterError::Transaction(InvalidTransaction::LackOfFundForMaxFee { .. }))
+    ));
+    evm.set_disable_balance_check(true);
+    assert!(evm.nonce_check_disabled() && evm.balance_check_disabled());
+
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.result.output().unwrap()[31], 1);
+    let sender_info = &result.state[&sender].info;
+    assert_eq!(sender_info.nonce, 1, "The sender's own nonce is bumped");
+    assert_eq!(
+        sender_info.balance,
+        U256::from(100_000 * 10 - result.result.gas_used() * 10),
+        "Topped up to the maximum fee, minus the fee paid"
+    );
+}
+
+#[test]
+fn test_wrapper_chain_spec() {
+    use guillotine_rs::guillotine_mini::{ChainSpec, ForkCondition, GenesisAccount, Hardfork};
+
+    // CHAINID PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let contract = address!("2200000000000000000000000000000000000000");
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    let code = Bytes::from(hex::decode("4660005260206000f3").unwrap());
+    let chain = ChainSpec::dev(31337, SpecId::SHANGHAI)
+        .with_fork(SpecId::CANCUN, ForkCondition::Block(10))
+        .with_account(sender, GenesisAccount { balance: U256::from(10u128.pow(18)), ..Default::default() })
+        .with_account(contract, GenesisAccount { code: Some(code), ..Default::default() });
+    let mut evm = GuillotineMiniEvm::builder().chain(&chain).build().unwrap();
+    assert_eq!(evm.hardfork(), Hardfork::Shanghai);
+
+    let tx = TxEnv::builder()
+        .caller(sender)
+        .chain_id(Some(31337))
+        .kind(TxKind::Call(contract))
+        .gas_limit(100_000)
+        .gas_price(1_000_000_000)
+        .build()
+        .unwrap();
+    let result = evm.transact(tx.clone()).unwrap();
+    assert_eq!(U256::from_be_slice(result.result.output().unwrap()), U256::from(31337));
+
+    let block = BlockEnv { number: U256::from(10), ..evm.ctx.block.clone() };
+    evm.advance_block(&chain, block).unwrap();
+    assert_eq!(evm.hardfork(), Hardfork::Cancun);
+    assert!(evm.transact(tx).unwrap().result.is_success());
+}
+
+#[test]
+fn test_wrapper_pool_concurrent_transact() {
+    use guillotine_rs::guillotine_mini::EvmPool;
+
+    let pool = EvmPool::new(SpecId::CANCUN, 2).unwrap();
+    let (_, sender, contract_addr) = counter_ctx();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> =
+            (0..8).map(|_| scope.spawn(|| pool.transact(|| counter_ctx().0, tx.clone()).unwrap())).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    // Every transaction starts from fresh state, whichever pooled instance ran it
+    for result in &results {
+        assert!(result.result.is_success());
+        assert_eq!(result.result.output().unwrap()[31], 1);
+        assert_eq!(result.state[&contract_addr].storage[&U256::from(0x14)].present_value, U256::from(1));
+    }
+    assert_eq!(pool.idle(), 2);
+}
+
+/// Local context without revm's shared interpreter memory, which guillotine-mini never uses;
+/// unlike revm's `LocalContext` (which holds an `Rc`) it is `Send`
+#[derive(Default)]
+struct SendLocal {
+    precompile_error: Option<String>,
+}
+
+impl LocalContextTr for SendLocal {
+    fn shared_memory_buffer(&self) -> &Rc<RefCell<Vec<u8>>> {
+        unreachable!("guillotine-mini executes with its own memory")
+    }
+
+    fn clear(&mut self) {
+        self.precompile_error = None;
+    }
+
+    fn set_precompile_error_context(&mut self, output: String) {
+        self.precompile_error = Some(output);
+    }
+
+    fn take_precompile_error_context(&mut self) -> Option<String> {
+        self.precompile_error.take()
+    }
+}
+
+type SendCtx = Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDB>, Journal<CacheDB<EmptyDB>>, (), SendLocal>;
+type SendEvm = GuillotineMiniEvm<SendCtx>;
+
+fn send_counter_evm() -> (SendEvm, Address, Address) {
+    let (ctx, sender, contract_addr) = counter_ctx();
+    (GuillotineMiniEvm::new(ctx.with_local(SendLocal::default())), sender, contract_addr)
+}
+
+fn call_send_counter(evm: &mut SendEvm, sender: Address, contract_addr: Address) -> u8 {
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact(tx).unwrap();
+    assert!(result.result.is_success());
+    result.result.output().unwrap()[31]
+}
+
+#[test]
+fn test_wrapper_evm_moves_between_threads() {
+    use guillotine_rs::guillotine_mini::SharedEvm;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+    assert_send::<SendEvm>();
+    assert_sync::<SharedEvm<SendCtx>>();
+
+    let (mut evm, sender, contract_addr) = send_counter_evm();
+    evm.set_persist_state(true);
+    assert_eq!(call_send_counter(&mut evm, sender, contract_addr), 1);
+
+    // Carried-over guillotine-mini state moves with the EVM
+    let mut evm = std::thread::spawn(move || {
+        assert_eq!(call_send_counter(&mut evm, sender, contract_addr), 2);
+        evm
+    })
+    .join()
+    .unwrap();
+    assert_eq!(call_send_counter(&mut evm, sender, contract_addr), 3);
+}
+
+#[test]
+fn test_wrapper_shared_evm_serializes_threads() {
+    use guillotine_rs::guillotine_mini::SharedEvm;
+
+    let (mut evm, sender, contract_addr) = send_counter_evm();
+    evm.set_persist_state(true);
+    let shared = SharedEvm::new(evm);
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..5 {
+                    assert!(shared.transact(tx.clone()).unwrap().result.is_success());
+                }
+            });
+        }
+    });
+
+    // Every increment landed exactly once
+    let mut evm = shared.into_inner();
+    assert_eq!(call_send_counter(&mut evm, sender, contract_addr), 21);
+}
+
+#[test]
+fn test_wrapper_transact_options() {
+    use guillotine_rs::guillotine_mini::{EvmAdapterError, TransactOptions};
+    use std::time::Duration;
+
+    // JUMPDEST PUSH1 0 JUMP: loops until the gas runs out
+    let looper = address!("1100000000000000000000000000000000000000");
+    let code = Bytes::from(hex::decode("5b600056").unwrap());
+    let (ctx, sender, contract_addr) = counter_ctx();
+    let ctx = ctx.modify_block_chained(|block| block.gas_limit = u64::MAX).modify_db_chained(|db| {
+        db.insert_account_info(
+            looper,
+            AccountInfo {
+                code_hash: revm::primitives::keccak256(&code),
+                code: Some(Bytecode::new_raw(code)),
+                ..Default::default()
+            },
+        )
+    });
+    let mut evm = GuillotineMiniEvm::new(ctx);
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.transact_with_options(tx, TransactOptions::new().tracing(true)).unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(evm.last_report().struct_logs.len(), 12);
+    assert!(!evm.native_tracing(), "Options only apply to their transaction");
+
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(looper)).gas_limit(1 << 60).build().unwrap();
+    let options = TransactOptions::new().timeout(Duration::from_millis(50));
+    assert!(matches!(evm.transact_with_options(tx, options), Err(EvmAdapterError::DeadlineExceeded)));
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1, "Later transactions run without a deadline");
+}
+
+#[test]
+fn test_wrapper_native_tracing() {
+    let (mut evm, sender, contract_addr) = counter_evm();
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert!(evm.last_report().struct_logs.is_empty(), "Tracing is disabled by default");
+
+    evm.set_native_tracing(true);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    let logs = &evm.last_report().struct_logs;
+    // PUSH1 SLOAD PUSH1 ADD DUP1 PUSH1 SSTORE PUSH1 MSTORE PUSH1 PUSH1 RETURN
+    assert_eq!(logs.len(), 12);
+    assert_eq!((logs[0].pc, logs[0].op, logs[0].depth), (0, 0x60, 0));
+    assert!(logs[0].stack.is_empty());
+    assert_eq!((logs[1].op, logs[1].stack.clone()), (0x54, vec![U256::from(0x14)]));
+    assert_eq!(logs[11].op, 0xf3);
+    assert!(logs.windows(2).all(|pair| pair[1].gas == pair[0].gas - pair[0].gas_cost));
+}
+
+#[test]
+fn test_wrapper_inspect() {
+    use revm::{
+        inspector::Inspector,
+        interpreter::{interpreter_types::Jumps, CallInputs, CallOutcome, Interpreter},
+    };
+
+    #[derive(Default)]
+    struct OpcodeCounter {
+        opcodes: Vec<u8>,
+        calls: Vec<Address>,
+        outputs: Vec<Bytes>,
+    }
+
+    impl<CTX> Inspector<CTX> for OpcodeCounter {
+        fn step(&mut self, interp: &mut Interpreter, _: &mut CTX) {
+            self.opcodes.push(interp.bytecode.opcode());
+        }
+
+        fn call(&mut self, _: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+            self.calls.push(inputs.target_address);
+            None
+        }
+
+        fn call_end(&mut self, _: &mut CTX, _: &CallInputs, outcome: &mut CallOutcome) {
+            self.outputs.push(outcome.result.output.clone());
+        }
+    }
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let mut inspector = OpcodeCounter::default();
+    let result = evm.inspect(tx.clone(), &mut inspector).unwrap();
+
+    assert!(result.result.is_success());
+    let (mut observed_evm, ..) = counter_evm();
+    let mut observed = Vec::new();
+    observed_evm.transact_with_observer(tx, |step| observed.push(step.opcode)).unwrap();
+    assert_eq!(inspector.opcodes, observed);
+    assert_eq!(inspector.opcodes[..2], [0x60, 0x54]);
+    assert_eq!(inspector.calls, vec![contract_addr]);
+    assert_eq!(inspector.outputs, vec![result.result.output().unwrap().clone()]);
+}
+
+#[test]
+fn test_wrapper_inspect_changes_apply_to_execution() {
+    use revm::{
+        inspector::Inspector,
+        interpreter::{interpreter_types::Jumps, Interpreter},
+    };
+
+    /// Replaces the value of every SSTORE
+    struct StoreOverride(U256);
+
+    impl<CTX> Inspector<CTX> for StoreOverride {
+        fn step(&mut self, interp: &mut Interpreter, _: &mut CTX) {
+            if interp.bytecode.opcode() == 0x55 {
+                let stack = interp.stack.data_mut();
+                let value = stack.len() - 2;
+                stack[value] = self.0;
+            }
+        }
+    }
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let result = evm.inspect(tx, &mut StoreOverride(U256::from(42))).unwrap();
+
+    assert!(result.result.is_success());
+    let slot = &result.state[&contract_addr].storage[&U256::from(0x14)];
+    assert_eq!(slot.present_value, U256::from(42), "The inspector's stack change is executed");
+}
+
+#[test]
+fn test_wrapper_call_tracer() {
+    use guillotine_rs::guillotine_mini::{CallKind, CallTracer};
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let mut tracer = CallTracer::new();
+    let result = evm.inspect(tx, &mut tracer).unwrap();
+
+    let frame = tracer.frame().unwrap();
+    assert_eq!((frame.kind, frame.from, frame.to), (CallKind::Call, sender, Some(contract_addr)));
+    assert_eq!(frame.gas, 100_000 - 21_000);
+    assert_eq!(&frame.output, result.result.output().unwrap());
+    assert!(frame.is_success() && frame.calls.is_empty());
+    assert!(frame.gas_used > 20_000, "SSTORE to a fresh slot is charged");
+}
+
+#[test]
+fn test_wrapper_gas_flamegraph() {
+    use guillotine_rs::guillotine_mini::{folded_stacks, CallTracer};
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let mut tracer = CallTracer::new();
+    evm.inspect(tx, &mut tracer).unwrap();
+
+    let frame = tracer.frame().unwrap();
+    assert_eq!(folded_stacks(frame), format!("CALL {contract_addr} {}\n", frame.gas_used));
+}
+
+#[test]
+fn test_wrapper_storage_access_tracer() {
+    use guillotine_rs::guillotine_mini::{StorageAccess, StorageAccessKind, StorageAccessTracer};
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let mut tracer = StorageAccessTracer::new();
+    evm.inspect(tx, &mut tracer).unwrap();
+
+    let slot = U256::from(0x14);
+    assert_eq!(
+        tracer.accesses(),
+        &[
+            StorageAccess {
+                kind: StorageAccessKind::Load,
+                address: contract_addr,
+                slot,
+                old_value: Some(U256::ZERO),
+                new_value: U256::ZERO,
+                warm: Some(false),
+                pc: 2,
+                depth: 0,
+            },
+            StorageAccess {
+                kind: StorageAccessKind::Store,
+                address: contract_addr,
+                slot,
+                old_value: Some(U256::ZERO),
+                new_value: U256::from(1),
+                warm: Some(true),
+                pc: 9,
+                depth: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_wrapper_transact_with_events() {
+    use guillotine_rs::guillotine_mini::ExecutionEvent;
+    use std::sync::mpsc;
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+    let (events, receiver) = mpsc::channel();
+    let consumer = std::thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+    let result = evm.transact_with_events(tx, events).unwrap();
+    let events = consumer.join().unwrap();
+
+    let steps = events.iter().filter(|event| matches!(event, ExecutionEvent::Step(_))).count();
+    assert_eq!(steps, 12);
+    assert_eq!(
+        events.last(),
+        Some(&ExecutionEvent::Finished { success: true, gas_used: result.result.gas_used() })
+    );
+}
+
+#[test]
+fn test_wrapper_deep_tracing() {
+    use guillotine_rs::guillotine_mini::DeepTraceLimits;
+
+    let (mut evm, sender, contract_addr) = counter_evm();
+    evm.set_deep_tracing(Some(DeepTraceLimits::default()));
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    let logs = &evm.last_report().struct_logs;
+    assert_eq!(logs.len(), 12, "Deep tracing records struct logs without native tracing");
+    assert_eq!(logs[0].memory, Some(Bytes::new()));
+    // PUSH1 0x20 after MSTORE of the new counter at 0
+    let mut word = [0u8; 32];
+    word[31] = 1;
+    assert_eq!(logs[9].memory, Some(Bytes::from(word.to_vec())));
+
+    evm.set_deep_tracing(Some(DeepTraceLimits { max_stack_items: 1, max_memory: 8 }));
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    let logs = &evm.last_report().struct_logs;
+    // SSTORE with [1, 1, 0x14] on the stack
+    assert_eq!(logs[6].stack, vec![U256::from(0x14)]);
+    assert_eq!(logs[9].memory.as_ref().map(|memory| memory.len()), Some(8));
+
+    evm.set_deep_tracing(None);
+    assert_eq!(call_counter(&mut evm, sender, contract_addr), 1);
+    assert!(evm.last_report().struct_logs.is_empty());
+}
+
+#[test]
+fn test_wrapper_tx_origin_override() {
+    // ORIGIN PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+    let code = Bytes::from(hex::decode("3260005260206000f3").unwrap());
+    let contract_addr = address!("1000000000000000000000000000000000000000");
+    let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+    let origin = address!("00000000000000000000000000000000000000ee");
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        contract_addr,
+        AccountInfo {
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: revm::primitives::keccak256(&code),
+            code: Some(Bytecode::new_raw(code)),
+        },
+    );
+    let ctx = Context::mainnet()
+        .modify_cfg_chained(|cfg| {
+            cfg.spec = SpecId::CANCUN;
+            cfg.disable_nonce_check = true;
+        })
+        .with_db(db);
+    let mut evm = GuillotineMiniEvm::new(ctx);
+    let tx = TxEnv::builder().caller(sender).kind(TxKind::Call(contract_addr)).gas_limit(100_000).build().unwrap();
+
+    let returned_origin = |evm: &mut CacheEvm| {
+        let result = evm.transact(tx.clone()).unwrap();
+        Address::from_slice(&result.result.output().unwrap()[12..])
+    };
+    assert_eq!(evm.tx_origin(), None);
+    assert_eq!(returned_origin(&mut evm), sender, "ORIGIN defaults to the caller");
+
+    evm.set_tx_origin(Some(origin));
+    assert_eq!(returned_origin(&mut evm), origin);
+}